@@ -0,0 +1,79 @@
+//! Anthologies: Markdown files that collect poems, each added by `:anthology` as a new section holding
+//! the highlighted words and where they came from.
+
+use crate::timeline;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// One poem as an anthology section
+pub struct Section<'a> {
+    pub title: Option<&'a str>,  // The scatter's title; untitled poems are headed with the date
+    pub words: &'a [String],  // The highlighted words, in the order they were highlighted
+    pub seed: u64,
+    pub size: (u16, u16),  // Canvas size the scatter was generated for
+    pub sources: Vec<&'a str>,  // Names of the files the words came from
+}
+
+impl Section<'_> {
+    fn to_markdown(&self, timestamp: &str) -> String {
+        format!(
+            "## {}\n\n{}\n\n- Added: {}\n- Seed: {} ({}x{})\n- Sources: {}\n\n",
+            self.title.unwrap_or(timestamp),
+            self.words.join(" "),
+            timestamp,
+            self.seed,
+            self.size.0,
+            self.size.1,
+            self.sources.join(", "),
+        )
+    }
+}
+
+/// Append a section to the anthology at `path`, starting the file with a heading named after it if
+/// it's new and keeping a blank line between the file's last line and the section
+pub fn append(path: &Path, section: &Section) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let lead = if existing.trim().is_empty() {
+        let name = path.file_stem().map_or_else(|| "Anthology".to_string(), |stem| stem.to_string_lossy().to_string());
+        format!("# {}\n\n", name)
+    } else if existing.ends_with("\n\n") {
+        String::new()
+    } else if existing.ends_with('\n') {
+        "\n".to_string()
+    } else {
+        "\n\n".to_string()
+    };
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(format!("{}{}", lead, section.to_markdown(&timeline::now())).as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_appended() {
+        let words = vec!["lantern".to_string(), "harbor".to_string()];
+        let section = Section { title: Some("Harbor Songs"), words: &words, seed: 4127, size: (80, 24), sources: vec!["a.txt"] };
+        assert_eq!(
+            section.to_markdown("2026-10-15 14:03 UTC"),
+            "## Harbor Songs\n\nlantern harbor\n\n- Added: 2026-10-15 14:03 UTC\n- Seed: 4127 (80x24)\n- Sources: a.txt\n\n"
+        );
+
+        let path = std::env::temp_dir().join(format!("scatters-anthology-{}.md", std::process::id()));
+        fs::write(&path, "# Mine\n\nno trailing newline").unwrap();
+        append(&path, &section).unwrap();
+        append(&path, &Section { title: None, ..section }).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(content.starts_with("# Mine\n\nno trailing newline\n\n## Harbor Songs\n"));
+        assert_eq!(content.matches("\n## ").count(), 2);
+        assert!(!content.contains("\n\n\n"));
+    }
+}
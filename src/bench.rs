@@ -0,0 +1,121 @@
+//! `text-scatters bench`: how long the corpus takes to parse, format by format, against loading it from
+//! the cache, and how long placing a scatter takes at a range of densities and canvas sizes.
+
+use crate::cache;
+use crate::corpus::{self, LoadOptions};
+use crate::scatters::ScattersGenerator;
+use crate::stop_words::StopWords;
+use crate::word_bank::WordBank;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Densities placement is timed at, from sparse to the densest the arrow keys reach
+const DENSITIES: &[f32] = &[0.5, 1.0, 2.0, 4.0, 6.0];
+/// Canvas sizes placement is timed at: a small terminal, a large one and a big `--width`/`--height` page
+const SIZES: &[(u16, u16)] = &[(80, 24), (200, 60), (400, 120)];
+
+/// Parse time for the files of one format
+#[derive(Default)]
+struct FormatTiming {
+    files: usize,
+    failed: usize,
+    words: usize,
+    time: Duration,
+}
+
+/// Time parsing and placement on the corpus at `input_path` and return the results as a table,
+/// averaging placement over `rounds` scatters per density and size
+pub fn run(input_path: &Path, options: &LoadOptions, rounds: u32) -> Result<String, Box<dyn std::error::Error>> {
+    let mut formats: BTreeMap<String, FormatTiming> = BTreeMap::new();
+    let mut word_bank = WordBank::new();
+    for path in corpus::corpus_files(input_path, options)? {
+        let extension = path.extension().map_or_else(String::new, |extension| extension.to_string_lossy().to_lowercase());
+        let timing = formats.entry(extension).or_default();
+        let start = Instant::now();
+        let parsed = corpus::parse_corpus_file(&path);
+        timing.time += start.elapsed();
+        timing.files += 1;
+        match parsed {
+            Ok(words) => {
+                timing.words += words.len();
+                word_bank.add_words(words, path.display().to_string());
+            }
+            Err(_) => timing.failed += 1,
+        }
+    }
+    if word_bank.word_count() == 0 {
+        return Err("No words found".into());
+    }
+
+    let mut table = format!("Parsing {}\n\n", input_path.display());
+    table.push_str(&format!("{:<8} {:>6} {:>10} {:>10} {:>11} {:>12}\n", "format", "files", "words", "total ms", "ms / file", "words / ms"));
+    for (extension, timing) in &formats {
+        let milliseconds = timing.time.as_secs_f64() * 1000.0;
+        let failed = if timing.failed > 0 { format!(" ({} failed)", timing.failed) } else { String::new() };
+        table.push_str(&format!(
+            "{:<8} {:>6} {:>10} {:>10.1} {:>11.2} {:>12.0}{}\n",
+            if extension.is_empty() { "-" } else { extension },
+            timing.files,
+            timing.words,
+            milliseconds,
+            milliseconds / timing.files as f64,
+            timing.words as f64 / milliseconds.max(0.001),
+            failed
+        ));
+    }
+    let parse_total: Duration = formats.values().map(|timing| timing.time).sum();
+    let start = Instant::now();
+    let cached = cache::load(input_path, options);
+    table.push_str(&match cached {
+        Some(_) => format!(
+            "\nFrom the cache: {:.1} ms, against {:.1} ms parsing\n",
+            start.elapsed().as_secs_f64() * 1000.0,
+            parse_total.as_secs_f64() * 1000.0
+        ),
+        None => "\nNo cache yet for this corpus (it's written the first time it's loaded without --no-cache)\n".to_string(),
+    });
+
+    let mut generator = ScattersGenerator::new(word_bank.get_words());
+    generator.set_stop_words(StopWords::load().words());
+    generator.set_frequencies(word_bank.frequencies());
+
+    table.push_str(&format!("\nPlacing {} unique words, mean of {} scatters\n\n", word_bank.word_count(), rounds));
+    table.push_str(&format!("{:<9} {:>8} {:>8} {:>10}\n", "canvas", "density", "words", "ms"));
+    for &(width, height) in SIZES {
+        for &density in DENSITIES {
+            let mut placed = 0;
+            let start = Instant::now();
+            for seed in 0..rounds {
+                placed += generator.generate_with_seed(seed as u64, width, height, density).words.len();
+            }
+            let mean = start.elapsed().as_secs_f64() * 1000.0 / rounds as f64;
+            table.push_str(&format!(
+                "{:<9} {:>8.1} {:>8} {:>10.2}\n",
+                format!("{}x{}", width, height),
+                density,
+                placed / rounds as usize,
+                mean
+            ));
+        }
+    }
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_covers_formats_and_sizes() {
+        let dir = std::env::temp_dir().join(format!("scatters-bench-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("tide.txt"), "lantern harbor salt wandering marigolds silver").unwrap();
+        let table = run(&dir, &LoadOptions::default(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let table = table.unwrap();
+        assert!(table.lines().any(|line| line.starts_with("txt ") && line.contains(" 1 ")));
+        assert_eq!(table.lines().filter(|line| line.starts_with("400x120")).count(), DENSITIES.len());
+    }
+}
@@ -0,0 +1,43 @@
+//! Corpora compiled into the binary, so there's something to scatter before any files are chosen.
+
+use crate::parser;
+use crate::word_bank::WordBank;
+
+/// Name and text of each built-in corpus, for `--builtin NAME`
+pub const CORPORA: &[(&str, &str)] = &[
+    ("poetry", include_str!("../corpora/poetry.txt")), // Public-domain poems, also the first-run demo
+    ("proverbs", include_str!("../corpora/proverbs.txt")),
+    ("abstracts", include_str!("../corpora/abstracts.txt")), // Short scientific abstracts
+];
+
+pub fn names() -> Vec<&'static str> {
+    CORPORA.iter().map(|(name, _)| *name).collect()
+}
+
+/// The named corpus as a word bank, with `NAME.txt` as the source of every word
+pub fn word_bank(name: &str) -> Option<WordBank> {
+    let (name, text) = CORPORA.iter().find(|(corpus, _)| *corpus == name)?;
+    let mut word_bank = WordBank::new();
+    word_bank.add_words(parser::parse_text(text), format!("{}.txt", name));
+    Some(word_bank)
+}
+
+/// The text behind a `NAME.txt` source of a built-in corpus
+pub fn source_text(source: &str) -> Option<&'static str> {
+    let name = source.strip_suffix(".txt")?;
+    CORPORA.iter().find(|(corpus, _)| *corpus == name).map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_corpora_have_words() {
+        for name in names() {
+            assert!(word_bank(name).unwrap().word_count() > 100, "{} is too small", name);
+        }
+        assert!(word_bank("poetry").unwrap().get_words().iter().any(|(word, _)| word == "feathers"));
+        assert!(word_bank("novels").is_none());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::config;
+use crate::corpus::{self, LoadOptions};
+use crate::parser::Location;
+use crate::word_bank::{BankEntry, PruneDecision, WordBank};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Bump whenever parsing or filtering changes, so older caches are rebuilt
+const CACHE_VERSION: u32 = 10;
+const CACHE_HEADER: &str = "text-scatters-cache";
+/// Marks a `#title<TAB>source<TAB>title` line; words never start with `#`
+const TITLE_PREFIX: &str = "#title\t";
+
+/// Hash of every corpus file's path, size and modification time
+/// Options that change which files are read are covered through the file list itself, apart from
+/// skipping duplicates, which is decided while parsing
+fn fingerprint(input_path: &Path, options: &LoadOptions) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut hasher = DefaultHasher::new();
+    CACHE_VERSION.hash(&mut hasher);
+    options.keep_duplicates.hash(&mut hasher);
+
+    for path in corpus::corpus_files(input_path, options)? {
+        let metadata = fs::metadata(&path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        path.hash(&mut hasher);
+        metadata.len().hash(&mut hasher);
+        modified.hash(&mut hasher);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Cache file for a corpus path, named by a hash of its absolute path
+fn cache_path(input_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let absolute = fs::canonicalize(input_path)?;
+    let mut hasher = DefaultHasher::new();
+    absolute.hash(&mut hasher);
+
+    let cache_dir = config::get_config_dir()?.join("cache");
+    fs::create_dir_all(&cache_dir)?;
+
+    Ok(cache_dir.join(format!("{:016x}.txt", hasher.finish())))
+}
+
+/// Load the cached bank for this corpus, if one exists and the files haven't changed since
+pub fn load(input_path: &Path, options: &LoadOptions) -> Option<WordBank> {
+    let content = fs::read_to_string(cache_path(input_path).ok()?).ok()?;
+    let mut lines = content.lines();
+
+    let expected_header = format!("{} {:016x}", CACHE_HEADER, fingerprint(input_path, options).ok()?);
+    if lines.next()? != expected_header {
+        return None;
+    }
+
+    let (titles, entries): (Vec<&str>, Vec<&str>) = lines.partition(|line| line.starts_with(TITLE_PREFIX));
+    let entries: Vec<BankEntry> = entries.into_iter().filter_map(parse_entry).collect();
+
+    if entries.is_empty() {
+        return None;
+    }
+
+    let mut word_bank = WordBank::from_entries(entries);
+    for line in titles {
+        if let Some((source, title)) = line[TITLE_PREFIX.len()..].split_once('\t') {
+            word_bank.set_title(source.to_string(), title.to_string());
+        }
+    }
+    Some(word_bank)
+}
+
+/// Parse a `word<TAB>count<TAB>chapter<TAB>paragraph<TAB>source[<TAB>context<TAB>context source]...` line,
+/// where chapter is `-` when absent
+fn parse_entry(line: &str) -> Option<BankEntry> {
+    let mut fields = line.split('\t');
+    let word = fields.next()?;
+    let count = fields.next()?.parse().ok()?;
+    let chapter = match fields.next()? {
+        "-" => None,
+        chapter => Some(chapter.parse().ok()?),
+    };
+    let paragraph = fields.next()?.parse().ok()?;
+    let source = fields.next()?;
+    let contexts: Vec<&str> = fields.collect();
+
+    Some(BankEntry {
+        word: word.to_string(),
+        source_file: source.to_string(),
+        location: Location { chapter, paragraph },
+        count,
+        contexts: contexts.chunks_exact(2).map(|pair| (pair[0].to_string(), pair[1].to_string())).collect(),
+    })
+}
+
+/// Write the bank to the cache as a header line followed by one entry line per word
+pub fn save(input_path: &Path, options: &LoadOptions, word_bank: &WordBank) -> Result<(), Box<dyn std::error::Error>> {
+    let mut content = format!("{} {:016x}\n", CACHE_HEADER, fingerprint(input_path, options)?);
+
+    for (source, title) in word_bank.titles() {
+        content.push_str(&format!("{}{}\t{}\n", TITLE_PREFIX, source, title));
+    }
+
+    for entry in word_bank.entries() {
+        content.push_str(&entry_line(&entry));
+    }
+
+    fs::write(cache_path(input_path)?, content)?;
+    Ok(())
+}
+
+/// An entry as the line `parse_entry` reads back
+fn entry_line(entry: &BankEntry) -> String {
+    let chapter = entry.location.chapter.map_or("-".to_string(), |c| c.to_string());
+    let mut line = format!("{}\t{}\t{}\t{}\t{}", entry.word, entry.count, chapter, entry.location.paragraph, entry.source_file);
+    for (context, context_source) in &entry.contexts {
+        line.push_str(&format!("\t{}\t{}", context, context_source));
+    }
+    line.push('\n');
+    line
+}
+
+/// Pruning decisions for a corpus, kept beside its cache but in a file of their own so rebuilding the
+/// cache after the corpus changes doesn't lose them
+fn pruning_path(input_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(cache_path(input_path)?.with_extension("prune"))
+}
+
+/// Every pruning decision made for this corpus, oldest first
+pub fn load_pruning(input_path: &Path) -> Vec<PruneDecision> {
+    let Some(content) = pruning_path(input_path).ok().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content.lines().filter_map(parse_decision).collect()
+}
+
+/// Parse a `merge<TAB>from<TAB>into` or `delete<TAB>word` line
+fn parse_decision(line: &str) -> Option<PruneDecision> {
+    let mut fields = line.split('\t');
+    match (fields.next()?, fields.next()?, fields.next()) {
+        ("merge", from, Some(into)) => Some(PruneDecision::Merge { from: from.to_string(), into: into.to_string() }),
+        ("delete", word, None) => Some(PruneDecision::Delete(word.to_string())),
+        _ => None,
+    }
+}
+
+fn decision_line(decision: &PruneDecision) -> String {
+    match decision {
+        PruneDecision::Merge { from, into } => format!("merge\t{}\t{}\n", from, into),
+        PruneDecision::Delete(word) => format!("delete\t{}\n", word),
+    }
+}
+
+/// Add decisions to the ones already kept for this corpus
+pub fn save_pruning(input_path: &Path, decisions: &[PruneDecision]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(pruning_path(input_path)?)?;
+    file.write_all(decisions.iter().map(decision_line).collect::<String>().as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_line_round_trip() {
+        let entry = BankEntry {
+            word: "lantern".to_string(),
+            source_file: "a.txt".to_string(),
+            location: Location { chapter: Some(2), paragraph: 7 },
+            count: 4,
+            contexts: vec![
+                ("the lantern swung".to_string(), "a.txt".to_string()),
+                ("a lantern at the door".to_string(), "b.txt".to_string()),
+            ],
+        };
+        let line = entry_line(&entry);
+        assert_eq!(parse_entry(line.trim_end()), Some(entry));
+    }
+
+    #[test]
+    fn test_pruning_lines_round_trip() {
+        let decisions = [
+            PruneDecision::Merge { from: "Lanterns".to_string(), into: "lantern".to_string() },
+            PruneDecision::Delete("sea-glass".to_string()),
+        ];
+        let content: String = decisions.iter().map(decision_line).collect();
+        assert_eq!(content, "merge\tLanterns\tlantern\ndelete\tsea-glass\n");
+        let parsed: Vec<PruneDecision> = content.lines().filter_map(parse_decision).collect();
+        assert_eq!(parsed, decisions);
+        assert_eq!(parse_decision("merge\tlonely"), None);
+    }
+}
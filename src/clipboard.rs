@@ -0,0 +1,53 @@
+//! Copying to the clipboard through the terminal: an OSC 52 escape sequence asks the terminal itself to
+//! set the clipboard, so it works over SSH and needs no clipboard tool on the machine running the app.
+
+use std::io::{self, Write};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk.iter().enumerate().fold(0u32, |triple, (i, &byte)| triple | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(triple >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// The escape sequence that puts `text` on the clipboard; inside tmux it's wrapped so tmux passes it
+/// on to the terminal instead of keeping it
+fn osc52(text: &str, in_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if in_tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+/// Ask the terminal to put `text` on the clipboard
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52(text, std::env::var_os("TMUX").is_some()).as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(base64(b"tide"), "dGlkZQ==");
+        assert_eq!(base64(b"harbor"), "aGFyYm9y");
+        assert_eq!(base64("sé".as_bytes()), "c8Op");
+        assert_eq!(osc52("tide", false), "\x1b]52;c;dGlkZQ==\x07");
+        assert_eq!(osc52("tide", true), "\x1bPtmux;\x1b\x1b]52;c;dGlkZQ==\x07\x1b\\");
+    }
+}
@@ -0,0 +1,333 @@
+//! Experimental live collaboration: two instances share one canvas over a TCP or unix socket, each
+//! sending the other its scatter, selection and pinned words as they change. Messages are JSON, one
+//! object per line, carrying only the parts that changed:
+//!   {"op": "state", "scatter": {...}, "selection": {"selected": 3, "highlighted": [0, 3]}, "pins": [...]}
+
+use crate::export;
+use crate::logging;
+use crate::scatters::{Scatter, ScatteredWord, ScattersGenerator, Voice, MAX_CANVAS};
+use crate::ui::App;
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+
+/// Longest message taken from the other side, in bytes; a longer line ends the session rather than
+/// growing without bound
+const MAX_LINE: u64 = 4 << 20;
+
+/// Where the two instances meet
+#[derive(Clone, Debug)]
+pub enum Address {
+    Tcp(String),  // host:port, e.g. forwarded over SSH
+    Unix(PathBuf),
+}
+
+impl Address {
+    /// A path (anything with a `/`) is a unix socket, anything else a TCP `host:port`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.contains('/') {
+            Ok(Self::Unix(PathBuf::from(value)))
+        } else if value.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok()) {
+            Ok(Self::Tcp(value.to_string()))
+        } else {
+            Err(format!("'{}' is neither host:port nor a socket path", value))
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{}", address),
+            Self::Unix(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The parts of the app both sides share, as they stood after the last sync
+#[derive(Clone, Default, PartialEq)]
+struct Shared {
+    scatter: Value,
+    selection: Value,
+    pins: Value,
+}
+
+impl Shared {
+    fn of(app: &App) -> Self {
+        let (width, height) = app.scatter_size;
+        Self {
+            scatter: json!({
+                "seed": app.seed,
+                "width": width,
+                "height": height,
+                "requested": app.requested_words,
+                "words": export::layout_json(&app.scattered_words),
+            }),
+            selection: json!({ "selected": app.selected_word_index, "highlighted": app.highlighted_words }),
+            pins: app
+                .locked_words
+                .iter()
+                .map(|(word, source, voice)| json!({ "word": word, "source": source, "voice": voice.map(Voice::label) }))
+                .collect(),
+        }
+    }
+
+    /// A state message with whatever differs from `last`: everything when the scatter changed, so the
+    /// other side never pairs a new scatter with an old selection
+    fn changes_since(&self, last: &Shared) -> Option<Value> {
+        let whole = self.scatter != last.scatter;
+        let mut message = json!({ "op": "state" });
+        if whole {
+            message["scatter"] = self.scatter.clone();
+        }
+        if whole || self.selection != last.selection {
+            message["selection"] = self.selection.clone();
+        }
+        if whole || self.pins != last.pins {
+            message["pins"] = self.pins.clone();
+        }
+        (message.as_object().map_or(0, |fields| fields.len()) > 1).then_some(message)
+    }
+}
+
+/// A connection to the other instance
+pub struct Session {
+    outgoing: Sender<String>,  // Lines for the writer thread, so a partner that stops reading never stalls the canvas
+    incoming: Receiver<Value>,  // Messages from the other side; disconnected once it has gone
+    shared: Option<Shared>,  // None until the first sync, for a joiner that takes the host's canvas
+}
+
+impl Session {
+    /// Wait for a partner to join at `address`, then share this canvas with them
+    pub fn host(address: &Address) -> Result<Self, Box<dyn std::error::Error>> {
+        println!("Waiting for a partner to join on {}...", address);
+        match address {
+            Address::Tcp(address) => {
+                let (stream, _) = TcpListener::bind(address)?.accept()?;
+                Ok(Self::start(stream.try_clone()?, stream, Some(Shared::default())))
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                crate::server::remove_stale_socket(path)?;
+                let (stream, _) = std::os::unix::net::UnixListener::bind(path)?.accept()?;
+                Ok(Self::start(stream.try_clone()?, stream, Some(Shared::default())))
+            }
+            #[cfg(not(unix))]
+            Address::Unix(_) => Err("unix sockets aren't supported on this platform; use host:port".into()),
+        }
+    }
+
+    /// Join a partner hosting at `address`, taking their canvas
+    pub fn join(address: &Address) -> Result<Self, Box<dyn std::error::Error>> {
+        match address {
+            Address::Tcp(address) => {
+                let stream = TcpStream::connect(address)?;
+                Ok(Self::start(stream.try_clone()?, stream, None))
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                Ok(Self::start(stream.try_clone()?, stream, None))
+            }
+            #[cfg(not(unix))]
+            Address::Unix(_) => Err("unix sockets aren't supported on this platform; use host:port".into()),
+        }
+    }
+
+    fn start(reader: impl Read + Send + 'static, mut writer: impl Write + Send + 'static, shared: Option<Shared>) -> Self {
+        let (sender, incoming) = mpsc::channel();
+        std::thread::spawn(move || read_messages(reader, &sender));
+        let (outgoing, lines) = mpsc::channel::<String>();
+        std::thread::spawn(move || {
+            for line in lines {
+                if writeln!(writer, "{}", line).and_then(|_| writer.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { outgoing, incoming, shared }
+    }
+
+    /// Send what changed here since the last sync, then take in what the other side sent; true when the
+    /// canvas needs redrawing, and an error once the other side has gone
+    pub fn sync(&mut self, app: &mut App, generator: &mut ScattersGenerator) -> Result<bool, String> {
+        let current = Shared::of(app);
+        let last = self.shared.get_or_insert_with(|| current.clone());
+        if let Some(message) = current.changes_since(last) {
+            self.outgoing.send(message.to_string()).map_err(|_| "lost the connection to your partner")?;
+        }
+
+        let mut changed = false;
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => {
+                    apply(app, generator, &message);
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return Err("your partner left".to_string()),
+            }
+        }
+        self.shared = Some(Shared::of(app));
+        Ok(changed)
+    }
+}
+
+/// Pass each message line from the other side on to `sender` until the connection closes, breaks or
+/// sends a line longer than `MAX_LINE`; lines that aren't JSON are skipped
+fn read_messages(reader: impl Read, sender: &Sender<Value>) {
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.by_ref().take(MAX_LINE).read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if !line.ends_with('\n') && line.len() as u64 >= MAX_LINE => break,
+            Ok(_) => {}
+        }
+        if let Ok(message) = serde_json::from_str(&line) {
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Bring the canvas in line with a state message from the other side
+fn apply(app: &mut App, generator: &mut ScattersGenerator, message: &Value) {
+    if let Some(scatter) = message.get("scatter") {
+        // A canvas no instance would draw is turned away, with the selection and pins sent alongside it
+        let Some(size) = canvas_size(scatter) else {
+            logging::warn(format!("partner sent a canvas size outside 1 to {}; ignored", MAX_CANVAS));
+            return;
+        };
+        let words = scatter["words"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                Some(ScatteredWord {
+                    word: entry["word"].as_str()?.to_string(),
+                    x: entry["x"].as_u64()? as u16,
+                    y: entry["y"].as_u64()? as u16,
+                    source_file: entry["source"].as_str().unwrap_or_default().to_string(),
+                    voice: voice(&entry["voice"]),
+                })
+            })
+            .collect();
+        let requested = scatter["requested"].as_u64().unwrap_or_default() as usize;
+        app.update_words(Scatter { words, requested });
+        app.seed = scatter["seed"].as_u64().unwrap_or_default();
+        app.scatter_size = size;
+    }
+
+    if let Some(selection) = message.get("selection") {
+        let count = app.scattered_words.len();
+        app.selected_word_index = selection["selected"].as_u64().map(|index| index as usize).filter(|&index| index < count);
+        app.highlighted_words = selection["highlighted"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_u64)
+            .map(|index| index as usize)
+            .filter(|&index| index < count)
+            .collect();
+    }
+
+    if let Some(pins) = message.get("pins").and_then(Value::as_array) {
+        app.locked_words = pins
+            .iter()
+            .filter_map(|pin| {
+                let word = pin["word"].as_str()?.to_string();
+                Some((word, pin["source"].as_str().unwrap_or_default().to_string(), voice(&pin["voice"])))
+            })
+            .collect();
+        generator.set_kept_words(app.kept_words());
+    }
+}
+
+/// The width and height of a scatter message, when each is from 1 to `MAX_CANVAS` as the server requires
+fn canvas_size(scatter: &Value) -> Option<(u16, u16)> {
+    let side = |key: &str| scatter[key].as_u64().filter(|value| (1..=MAX_CANVAS as u64).contains(value)).map(|value| value as u16);
+    Some((side("width")?, side("height")?))
+}
+
+fn voice(label: &Value) -> Option<Voice> {
+    match label.as_str() {
+        Some("a") => Some(Voice::A),
+        Some("b") => Some(Voice::B),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styling::AppStyling;
+
+    fn app(words: &[(&str, u16)]) -> App {
+        let words = words
+            .iter()
+            .map(|&(word, x)| ScatteredWord { word: word.to_string(), x, y: 1, source_file: "a.txt".to_string(), voice: None })
+            .collect();
+        App::new(words, 10, AppStyling::from_theme("monochrome").unwrap(), PathBuf::from("corpus"))
+    }
+
+    #[test]
+    fn test_changes_carry_over() {
+        let mut generator = ScattersGenerator::new(Vec::new());
+        let mut ours = app(&[("lantern", 2), ("harbor", 12)]);
+        ours.seed = 42;
+        ours.scatter_size = (20, 5);
+        ours.selected_word_index = Some(1);
+        ours.highlighted_words = vec![0, 1];
+        let mut theirs = app(&[("tide", 0)]);
+
+        // A new scatter sends the whole state, and applying it leaves nothing to send back
+        let message = Shared::of(&ours).changes_since(&Shared::default()).unwrap();
+        apply(&mut theirs, &mut generator, &message);
+        assert!(Shared::of(&theirs) == Shared::of(&ours));
+        assert_eq!(theirs.highlighted_words, vec![0, 1]);
+
+        // Moving the selection sends only the selection
+        let before = Shared::of(&ours);
+        ours.selected_word_index = Some(0);
+        let message = Shared::of(&ours).changes_since(&before).unwrap();
+        assert!(message.get("scatter").is_none() && message.get("pins").is_none());
+        assert!(Shared::of(&ours).changes_since(&Shared::of(&ours)).is_none());
+    }
+
+    #[test]
+    fn test_oversized_canvas_turned_away() {
+        let mut generator = ScattersGenerator::new(Vec::new());
+        let mut theirs = app(&[("tide", 0)]);
+        theirs.scatter_size = (20, 5);
+        for (width, height) in [(0, 5), (20, MAX_CANVAS as u64 + 1)] {
+            let message = json!({ "op": "state", "scatter": { "width": width, "height": height, "words": [] }, "selection": { "selected": 0 } });
+            apply(&mut theirs, &mut generator, &message);
+            assert_eq!(theirs.scatter_size, (20, 5));
+            assert_eq!(theirs.scattered_words.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_overlong_line_ends_the_session() {
+        let mut input = b"{\"op\": \"state\"}\nnot json\n".to_vec();
+        input.extend(std::iter::repeat_n(b' ', MAX_LINE as usize));
+        input.extend(b"{\"op\": \"state\"}\n");
+        let (sender, incoming) = mpsc::channel();
+        read_messages(input.as_slice(), &sender);
+        drop(sender);
+        assert_eq!(incoming.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_address() {
+        assert!(matches!(Address::parse("localhost:7070"), Ok(Address::Tcp(_))));
+        assert!(matches!(Address::parse("/tmp/scatters.sock"), Ok(Address::Unix(_))));
+        assert!(Address::parse("localhost").is_err());
+    }
+}
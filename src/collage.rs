@@ -0,0 +1,208 @@
+//! Collages: the most recent snapshots tiled onto one sheet, as text or SVG, so a session's work
+//! prints as a single page.
+
+use crate::export::{self, escape_xml, Decorations, SVG_CELL, SVG_FONT_SIZE};
+use crate::scatters::ScatteredWord;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Blank columns and rows between neighbouring tiles
+const GUTTER: (usize, usize) = (4, 2);
+
+/// File format of a collage sheet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CollageFormat {
+    Text,
+    Svg,
+}
+
+impl CollageFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "text" | "txt" => Ok(Self::Text),
+            "svg" => Ok(Self::Svg),
+            _ => Err(format!("unknown collage format '{}', expected text or svg", value)),
+        }
+    }
+}
+
+/// The last `count` `scatter-NNNN` snapshots in `dir`, oldest first
+pub fn recent_snapshots(dir: &Path, count: usize) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut snapshots: Vec<(u32, PathBuf)> = fs::read_dir(dir)
+        .map_err(|_| format!("no snapshots in {} (save some with S)", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            // SVG and PNG snapshots are drawings rather than text to tile
+            let (number, extension) = name.strip_prefix("scatter-")?.split_once('.')?;
+            if matches!(extension, "svg" | "png") {
+                return None;
+            }
+            let number = number.parse().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    if snapshots.is_empty() {
+        return Err(format!("no snapshots in {} (save some with S)", dir.display()).into());
+    }
+
+    snapshots.sort();
+    let skip = snapshots.len().saturating_sub(count);
+    Ok(snapshots.into_iter().skip(skip).map(|(_, path)| path).collect())
+}
+
+/// A snapshot's lines of text; JSON snapshots are drawn out again with their path and title, and ANSI
+/// ones lose their colors
+pub fn snapshot_lines(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => json_lines(&content).ok_or_else(|| format!("{} isn't a scatter snapshot", path.display()).into()),
+        Some("ans") => Ok(strip_escapes(&content).lines().map(str::to_string).collect()),
+        _ => Ok(content.lines().map(str::to_string).collect()),
+    }
+}
+
+/// Text without its `ESC [ ... m` color codes
+fn strip_escapes(text: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+fn json_lines(content: &str) -> Option<Vec<String>> {
+    let snapshot: Value = serde_json::from_str(content).ok()?;
+    let width = snapshot["width"].as_u64()? as u16;
+    let height = snapshot["height"].as_u64()? as u16;
+    let words: Vec<ScatteredWord> = snapshot["words"]
+        .as_array()?
+        .iter()
+        .filter_map(|entry| {
+            Some(ScatteredWord {
+                word: entry["word"].as_str()?.to_string(),
+                x: entry["x"].as_u64()? as u16,
+                y: entry["y"].as_u64()? as u16,
+                source_file: entry["source"].as_str().unwrap_or_default().to_string(),
+                voice: None,
+            })
+        })
+        .collect();
+    let path: Vec<usize> = snapshot["path"]
+        .as_array()
+        .map(|indices| indices.iter().filter_map(|index| index.as_u64()).map(|index| index as usize).collect())
+        .unwrap_or_default();
+
+    let decorations = Decorations {
+        path: &path,
+        title: snapshot["title"].as_str(),
+        watermark: snapshot["watermark"].as_str(),
+        ..Decorations::default()
+    };
+    Some(export::render_text_decorated(&words, &decorations, width, height).lines().map(str::to_string).collect())
+}
+
+/// Cell size every tile gets: the widest and tallest snapshot
+fn tile_size(sheets: &[Vec<String>]) -> (usize, usize) {
+    let width = sheets.iter().flatten().map(|line| line.chars().count()).max().unwrap_or(0);
+    let height = sheets.iter().map(Vec::len).max().unwrap_or(0);
+    (width, height)
+}
+
+/// The snapshots side by side, `columns` to a row, in reading order
+pub fn render(format: CollageFormat, sheets: &[Vec<String>], columns: usize) -> String {
+    match format {
+        CollageFormat::Text => render_text(sheets, columns),
+        CollageFormat::Svg => render_svg(sheets, columns),
+    }
+}
+
+fn render_text(sheets: &[Vec<String>], columns: usize) -> String {
+    let (tile_width, tile_height) = tile_size(sheets);
+    let mut lines = Vec::new();
+
+    for (row, tiles) in sheets.chunks(columns.max(1)).enumerate() {
+        if row > 0 {
+            lines.extend(std::iter::repeat_n(String::new(), GUTTER.1));
+        }
+        for line in 0..tile_height {
+            let mut text = String::new();
+            for (column, tile) in tiles.iter().enumerate() {
+                if column > 0 {
+                    text.push_str(&" ".repeat(GUTTER.0));
+                }
+                let part = tile.get(line).map_or("", String::as_str);
+                text.push_str(&format!("{:width$}", part, width = tile_width));
+            }
+            lines.push(text.trim_end().to_string());
+        }
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+fn render_svg(sheets: &[Vec<String>], columns: usize) -> String {
+    let columns = columns.max(1);
+    let (tile_width, tile_height) = tile_size(sheets);
+    let (step_x, step_y) = ((tile_width + GUTTER.0) as f32 * SVG_CELL.0, (tile_height + GUTTER.1) as f32 * SVG_CELL.1);
+    let rows = sheets.len().div_ceil(columns);
+    let width = columns.min(sheets.len()) as f32 * step_x;
+    let height = rows as f32 * step_y;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n\
+         <g font-family=\"monospace\" font-size=\"{}\" fill=\"black\">\n",
+        SVG_FONT_SIZE,
+        w = width,
+        h = height,
+    );
+    for (index, tile) in sheets.iter().enumerate() {
+        let (left, top) = ((index % columns) as f32 * step_x, (index / columns) as f32 * step_y);
+        for (line, text) in tile.iter().enumerate().filter(|(_, text)| !text.trim().is_empty()) {
+            let y = top + (line as f32 + 0.8) * SVG_CELL.1;
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\" xml:space=\"preserve\">{}</text>\n", left, y, escape_xml(text)));
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_text_collage_tiles_in_rows() {
+        let sheets = [sheet(&["ab", "  c"]), sheet(&["dd"]), sheet(&["e"])];
+        let text = render(CollageFormat::Text, &sheets, 2);
+        assert_eq!(text, "ab     dd\n  c\n\n\ne\n");
+    }
+
+    #[test]
+    fn test_ansi_snapshot_loses_colors() {
+        assert_eq!(strip_escapes("ab \x1b[38;5;6m\x1b[3mcd\x1b[0m\n"), "ab cd\n");
+    }
+
+    #[test]
+    fn test_json_snapshot_drawn_out() {
+        let lines = json_lines(r#"{"width":8,"height":2,"words":[{"word":"tide","x":2,"y":1,"source":"a.txt"}]}"#).unwrap();
+        assert_eq!(lines, vec!["", "  tide"]);
+        assert!(render(CollageFormat::Svg, &[lines], 3).contains(">  tide</text>"));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::export::{ExportFormat, PagePreset};
+use crate::palette;
+use crate::raster;
+use crate::stats;
+use crate::stop_words::ListMode;
+use crate::ui::{SelectionCue, Transition};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Get the config directory for text-scatters
+pub fn get_config_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Could not determine config directory")?
+        .join("text-scatters");
+
+    // Create the config directory if it doesn't exist
+    fs::create_dir_all(&config_dir)?;
+
+    Ok(config_dir)
+}
+
+/// Save the last used path to config file
+pub fn save_last_path(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = get_config_dir()?;
+    let config_file = config_dir.join("last_path.txt");
+    fs::write(config_file, path.to_string_lossy().as_bytes())?;
+    Ok(())
+}
+
+/// Append this session's statistics to the stats log in the config directory
+pub fn save_session_stats(stats: &stats::SessionStats) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = get_config_dir()?;
+    let stats_file = config_dir.join("session_stats.log");
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(stats_file)?;
+    writeln!(file, "{}", stats.to_record())?;
+    Ok(())
+}
+
+/// Seeds marked as favorites, with the canvas size each scatter was made at, oldest first
+pub fn load_favorite_seeds() -> Vec<(u64, (u16, u16))> {
+    get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("favorite_seeds.txt")).ok())
+        .map(|content| parse_favorite_seeds(&content))
+        .unwrap_or_default()
+}
+
+/// `SEED WxH` lines, as `:replay` takes them
+fn parse_favorite_seeds(content: &str) -> Vec<(u64, (u16, u16))> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter_map(|(seed, size)| palette::parse_replay(seed.trim(), size.trim()).ok())
+        .collect()
+}
+
+pub fn save_favorite_seeds(favorites: &[(u64, (u16, u16))]) -> Result<(), Box<dyn std::error::Error>> {
+    let content: String = favorites.iter().map(|(seed, (width, height))| format!("{} {}x{}\n", seed, width, height)).collect();
+    fs::write(get_config_dir()?.join("favorite_seeds.txt"), content)?;
+    Ok(())
+}
+
+/// Whether a path has ever been saved, i.e. this isn't the first run
+pub fn has_last_path() -> bool {
+    get_config_dir().is_ok_and(|dir| dir.join("last_path.txt").exists())
+}
+
+/// Load the last used path from config file
+pub fn load_last_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let config_dir = get_config_dir()?;
+    let config_file = config_dir.join("last_path.txt");
+
+    if !config_file.exists() {
+        return Err("No previous path saved. Please provide a file or directory path.".into());
+    }
+
+    let path_str = fs::read_to_string(config_file)?;
+    let path = PathBuf::from(path_str.trim());
+
+    if !path.exists() {
+        return Err(format!("Previously saved path '{}' no longer exists", path.display()).into());
+    }
+
+    Ok(path)
+}
+
+/// Preferences read from `settings.conf` in the config directory
+pub struct Settings {
+    pub export_dir: PathBuf,  // Where snapshots are written
+    pub export_format: ExportFormat,  // Format of snapshots
+    pub export_page: Option<PagePreset>,  // Page SVG and PNG snapshots are fitted to
+    pub png_font: Option<PathBuf>,  // TrueType or OpenType font for PNG snapshots, instead of the built-in one
+    pub png_font_size: f32,  // Size PNG snapshots are drawn at, in pixels
+    pub typewriter_sound: bool,  // Ring the terminal bell for each character the typewriter types
+    pub selection_cue: SelectionCue,  // How a newly selected word is pointed out
+    pub transition: Transition,  // How words arrive after a reroll
+    pub journal: bool,  // Add an entry to the monthly journal for every save
+    pub anthology: Option<PathBuf>,  // Markdown file :anthology adds poems to when no other is named
+    pub transform: Option<String>,  // Shell command that :transform pipes words through
+    pub suggest: Option<String>,  // Shell command that answers a prompt for suggested lines
+    pub stop_words: Option<PathBuf>,  // Stop-word list used alongside or instead of the built-in one
+    pub stop_words_mode: ListMode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            export_dir: get_config_dir().map(|dir| dir.join("exports")).unwrap_or_else(|_| PathBuf::from("exports")),
+            export_format: ExportFormat::Text,
+            export_page: None,
+            png_font: None,
+            png_font_size: raster::DEFAULT_FONT_SIZE,
+            typewriter_sound: false,
+            selection_cue: SelectionCue::default(),
+            transition: Transition::default(),
+            journal: false,
+            anthology: None,
+            transform: None,
+            suggest: None,
+            stop_words: None,
+            stop_words_mode: ListMode::default(),
+        }
+    }
+}
+
+/// Load `settings.conf` (`key = value` lines), using defaults when the file doesn't exist
+pub fn load_settings() -> Result<Settings, Box<dyn std::error::Error>> {
+    let path = get_config_dir()?.join("settings.conf");
+    match fs::read_to_string(&path) {
+        Ok(content) => parse_settings(&content).map_err(|e| format!("Settings file '{}': {}", path.display(), e).into()),
+        Err(_) => Ok(Settings::default()),
+    }
+}
+
+fn parse_switch(value: &str) -> Result<bool, String> {
+    match value {
+        "on" | "true" | "yes" => Ok(true),
+        "off" | "false" | "no" => Ok(false),
+        _ => Err(format!("expected on or off, got '{}'", value)),
+    }
+}
+
+fn parse_settings(content: &str) -> Result<Settings, String> {
+    let mut settings = Settings::default();
+
+    for (number, line) in content.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .ok_or_else(|| format!("line {}: expected 'key = value'", number))?;
+
+        match key {
+            "export_dir" => settings.export_dir = expand_home(value),
+            "export_format" => {
+                settings.export_format = ExportFormat::parse(value).map_err(|e| format!("line {}: {}", number, e))?
+            }
+            "export_page" => {
+                settings.export_page = Some(PagePreset::parse(value).map_err(|e| format!("line {}: {}", number, e))?)
+            }
+            "png_font" => settings.png_font = (!value.is_empty()).then(|| expand_home(value)),
+            "png_font_size" => {
+                settings.png_font_size = value
+                    .parse()
+                    .ok()
+                    .filter(|size: &f32| (4.0..=200.0).contains(size))
+                    .ok_or_else(|| format!("line {}: png_font_size should be a size in pixels from 4 to 200", number))?
+            }
+            "selection_cue" => {
+                settings.selection_cue = SelectionCue::parse(value).map_err(|e| format!("line {}: {}", number, e))?
+            }
+            "transition" => {
+                settings.transition = Transition::parse(value).map_err(|e| format!("line {}: {}", number, e))?
+            }
+            "typewriter_sound" => settings.typewriter_sound = parse_switch(value).map_err(|e| format!("line {}: {}", number, e))?,
+            "journal" => settings.journal = parse_switch(value).map_err(|e| format!("line {}: {}", number, e))?,
+            "anthology" => settings.anthology = (!value.is_empty()).then(|| expand_home(value)),
+            "transform" => settings.transform = (!value.is_empty()).then(|| value.to_string()),
+            "suggest" => settings.suggest = (!value.is_empty()).then(|| value.to_string()),
+            "stop_words" => settings.stop_words = (!value.is_empty()).then(|| expand_home(value)),
+            "stop_words_mode" => {
+                settings.stop_words_mode = ListMode::parse(value).map_err(|e| format!("line {}: {}", number, e))?
+            }
+            _ => return Err(format!("line {}: unknown key '{}'", number, key)),
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Next free `scatter-NNNN.EXT` path in `dir`, numbered after the highest snapshot already there
+pub fn next_snapshot_path(dir: &Path, extension: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    next_numbered_path(dir, "scatter", extension)
+}
+
+/// Next free `STEM-NNNN.EXT` path in `dir`, such as `poem-0003.txt`
+pub fn next_numbered_path(dir: &Path, stem: &str, extension: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    fs::create_dir_all(dir)?;
+
+    let prefix = format!("{}-", stem);
+    let highest = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let number = name.strip_prefix(&prefix)?.split('.').next()?;
+            number.parse::<u32>().ok()
+        })
+        .max()
+        .unwrap_or(0);
+
+    Ok(dir.join(format!("{}{:04}.{}", prefix, highest + 1, extension)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_favorite_seeds() {
+        assert_eq!(parse_favorite_seeds("4127 80x24\nnot a seed\n9 120x40\n"), vec![(4127, (80, 24)), (9, (120, 40))]);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let settings = parse_settings("# snapshots\nexport_dir = /tmp/scatters\nexport_format = json\n").unwrap();
+        assert_eq!(settings.export_dir, PathBuf::from("/tmp/scatters"));
+        assert_eq!(settings.export_format, ExportFormat::Json);
+        assert!(!settings.typewriter_sound);
+        assert!(parse_settings("typewriter_sound = on").unwrap().typewriter_sound);
+        assert!(parse_settings("typewriter_sound = loud").is_err());
+        assert_eq!(settings.selection_cue, SelectionCue::Flash);
+        assert!(parse_settings("journal = yes").unwrap().journal);
+        assert_eq!(parse_settings("anthology = /tmp/poems.md").unwrap().anthology, Some(PathBuf::from("/tmp/poems.md")));
+        assert_eq!(parse_settings("transform = tr a-z A-Z").unwrap().transform.as_deref(), Some("tr a-z A-Z"));
+        assert_eq!(parse_settings("selection_cue = marker").unwrap().selection_cue, SelectionCue::Marker);
+        assert_eq!(parse_settings("transition = rainfall").unwrap().transition, Transition::Rainfall);
+        assert!(parse_settings("transition = confetti").is_err());
+        assert_eq!(parse_settings("export_page = A4").unwrap().export_page, Some(PagePreset::A4));
+        assert_eq!(parse_settings("png_font_size = 24").unwrap().png_font_size, 24.0);
+        assert!(parse_settings("png_font_size = huge").is_err());
+        let settings = parse_settings("stop_words = /tmp/es.txt\nstop_words_mode = replace").unwrap();
+        assert_eq!((settings.stop_words, settings.stop_words_mode), (Some(PathBuf::from("/tmp/es.txt")), ListMode::Replace));
+        assert!(parse_settings("stop_words_mode = merge").is_err());
+
+        assert!(parse_settings("export_format = gif").is_err());
+        assert!(parse_settings("colour = red").is_err());
+    }
+}
@@ -0,0 +1,518 @@
+use crate::builtin;
+use crate::cache;
+use crate::logging;
+use crate::parser;
+use crate::translate::Translation;
+use crate::word_bank::{BankEntry, FoldDisplay, WordBank};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use rand::seq::{IteratorRandom, SliceRandom};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Hashes kept per file when comparing texts for duplicates
+const SKETCH_SIZE: usize = 128;
+/// Estimated share of distinct words two files must have in common to count as the same text
+const DUPLICATE_SIMILARITY: f64 = 0.9;
+
+/// Source files read for watermark sentences, so a huge library doesn't slow startup
+const SENTENCE_FILES: usize = 20;
+/// Shortest and longest sentence, in words, worth laying across the canvas
+const SENTENCE_WORDS: (usize, usize) = (6, 30);
+/// Longest sentence, in words, quoted as an epigraph
+const EPIGRAPH_WORDS: usize = 40;
+
+/// Settings that control how a corpus is read into a word bank
+#[derive(Clone, Default)]
+pub struct LoadOptions {
+    pub quiet: bool,     // Suppress progress messages (stdout is reserved in embed/fortune modes)
+    pub quiet_warnings: bool, // Suppress warnings about skipped files too, when the TUI owns the terminal
+    pub use_cache: bool, // Reuse a previously parsed bank when the files are unchanged
+    pub use_pandoc: bool, // Convert rst and textile files through pandoc
+    pub skip_hidden: bool, // Skip dot files and dot directories when scanning
+    pub respect_gitignore: bool, // Skip files matched by .gitignore rules when scanning
+    pub follow_symlinks: bool, // Descend into symlinked directories (loops are detected and skipped)
+    pub recursive: bool, // Scan subdirectories at every depth, not just the directory's own files
+    pub include: Vec<String>, // Globs a file must match one of to be read, when there are any
+    pub exclude: Vec<String>, // Globs for files and directories to leave out
+    pub fold: Option<FoldDisplay>, // Merge possessives and plurals into their base word after loading
+    pub translation: Option<Translation>, // Show words with their translations, after any folding
+    pub keep_duplicates: bool, // Read every file, even one that repeats a text already read in another format
+}
+
+fn lowercase_extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase())
+}
+
+/// Whether the file extension is one the parser understands (including pandoc formats when enabled)
+pub fn is_supported_file(path: &Path, options: &LoadOptions) -> bool {
+    match lowercase_extension(path) {
+        Some(extension) => {
+            parser::NATIVE_EXTENSIONS.contains(&extension.as_str())
+                || (options.use_pandoc && parser::PANDOC_EXTENSIONS.contains(&extension.as_str()))
+        }
+        None => false,
+    }
+}
+
+/// Parse with the native parser when possible, falling back to pandoc for other formats
+pub fn parse_corpus_file(path: &Path) -> Result<Vec<parser::ParsedWord>, Box<dyn std::error::Error>> {
+    match lowercase_extension(path) {
+        Some(extension) if parser::PANDOC_EXTENSIONS.contains(&extension.as_str()) => {
+            parser::parse_with_pandoc(path)
+        }
+        _ => parser::parse_file(path),
+    }
+}
+
+/// Supported files that make up the corpus: the file itself, or the directory's entries (and with
+/// `recursive` its subdirectories') in path order, narrowed by the include and exclude globs
+pub fn corpus_files(input_path: &Path, options: &LoadOptions) -> Result<Vec<PathBuf>, ignore::Error> {
+    if input_path.is_file() {
+        return Ok(vec![input_path.to_path_buf()]);
+    }
+
+    // Symlinked files are always read; following links adds the contents of symlinked directories
+    let mut builder = WalkBuilder::new(input_path);
+    let max_depth = match (options.recursive, options.follow_symlinks) {
+        (true, _) => None,
+        (false, true) => Some(2),
+        (false, false) => Some(1),
+    };
+    builder
+        .max_depth(max_depth)
+        .follow_links(options.follow_symlinks)
+        .standard_filters(false)
+        .hidden(options.skip_hidden)
+        .git_ignore(options.respect_gitignore)
+        .git_exclude(options.respect_gitignore)
+        .parents(options.respect_gitignore)
+        .require_git(false);
+
+    // Globs are matched against paths relative to the directory, the way .gitignore patterns are
+    if !options.include.is_empty() || !options.exclude.is_empty() {
+        let mut overrides = OverrideBuilder::new(input_path);
+        for glob in &options.include {
+            overrides.add(glob)?;
+        }
+        for glob in &options.exclude {
+            overrides.add(&format!("!{}", glob))?;
+        }
+        builder.overrides(overrides.build()?);
+    }
+
+    if options.follow_symlinks && !options.recursive {
+        // The scan is single-level, so only look inside directories that are themselves symlinks
+        builder.filter_entry(|entry| {
+            entry.depth() < 2
+                || entry
+                    .path()
+                    .parent()
+                    .and_then(|parent| parent.symlink_metadata().ok())
+                    .is_some_and(|metadata| metadata.file_type().is_symlink())
+        });
+    }
+
+    let mut files = Vec::new();
+    for entry in builder.build() {
+        let path = match entry {
+            Ok(entry) => entry.into_path(),
+            Err(e) if e.is_io() => return Err(e),
+            // Symlink loops and other per-entry problems shouldn't abort the whole scan
+            Err(e) => {
+                logging::warn(format!("skipping entry: {}", e));
+                if !options.quiet_warnings {
+                    eprintln!("Warning: Skipping entry: {}", e);
+                }
+                continue;
+            }
+        };
+
+        if path.is_file() && is_supported_file(&path, options) {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// The smallest hashes of a file's distinct words, a small fingerprint of its vocabulary, with the same
+/// hashes as a set for comparing against other files
+struct Sketch {
+    hashes: Vec<u64>,  // Ascending, at most SKETCH_SIZE
+    set: HashSet<u64>,
+}
+
+impl Sketch {
+    fn new(words: &[parser::ParsedWord]) -> Self {
+        let hashes = word_sketch(words);
+        let set = hashes.iter().copied().collect();
+        Self { hashes, set }
+    }
+
+    /// Whether the file had enough distinct words to fill the sketch, so a match means a whole text in common
+    fn is_full(&self) -> bool {
+        self.hashes.len() == SKETCH_SIZE
+    }
+}
+
+/// The smallest SKETCH_SIZE hashes of the distinct words, ascending
+fn word_sketch(words: &[parser::ParsedWord]) -> Vec<u64> {
+    let distinct: HashSet<&str> = words.iter().map(|parsed| parsed.word.as_str()).collect();
+    let mut hashes: Vec<u64> = distinct
+        .into_iter()
+        .map(|word| {
+            let mut hasher = DefaultHasher::new();
+            word.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    hashes.sort_unstable();
+    hashes.truncate(SKETCH_SIZE);
+    hashes
+}
+
+/// Estimated share of distinct words two sketched files have in common (Jaccard similarity), taken over
+/// the smallest hashes of both files together
+fn sketch_similarity(a: &Sketch, b: &Sketch) -> f64 {
+    let (mut next_a, mut next_b) = (a.hashes.iter().peekable(), b.hashes.iter().peekable());
+    let (mut union, mut shared) = (0, 0);
+    while union < SKETCH_SIZE {
+        let hash = match (next_a.peek(), next_b.peek()) {
+            (Some(&&x), Some(&&y)) if x <= y => x,
+            (_, Some(&&y)) => y,
+            (Some(&&x), None) => x,
+            (None, None) => break,
+        };
+        next_a.next_if_eq(&&hash);
+        next_b.next_if_eq(&&hash);
+        if a.set.contains(&hash) && b.set.contains(&hash) {
+            shared += 1;
+        }
+        union += 1;
+    }
+    if union == 0 {
+        return 0.0;
+    }
+    shared as f64 / union as f64
+}
+
+/// Whether a file repeats one read earlier: the same name in another format with nearly the same words,
+/// or, for texts long enough to fill their sketches, nearly the same words under any name
+fn is_duplicate((stem, sketch): (&str, &Sketch), (other_stem, other_sketch): (&str, &Sketch)) -> bool {
+    (stem == other_stem || (sketch.is_full() && other_sketch.is_full()))
+        && sketch_similarity(sketch, other_sketch) >= DUPLICATE_SIMILARITY
+}
+
+/// Whole sentences from a random sample of the source files, read again from the files themselves
+/// (or the built-in texts) since the word bank only keeps words; `roots` are the files and directories
+/// the corpus was loaded from, which source names are relative to
+pub fn sample_sentences<'a>(sources: impl IntoIterator<Item = &'a str>, roots: &[PathBuf]) -> Vec<String> {
+    let mut sources: Vec<&str> = sources.into_iter().collect::<HashSet<_>>().into_iter().collect();
+    sources.sort_unstable();
+    sources.shuffle(&mut rand::thread_rng());
+
+    sources
+        .into_iter()
+        .take(SENTENCE_FILES)
+        .flat_map(|source| parser::sentences(&source_text(source, roots), SENTENCE_WORDS.0, SENTENCE_WORDS.1))
+        .collect()
+}
+
+/// A whole sentence of `source` with `word` in it, at random when there are several
+pub fn sentence_with(word: &str, source: &str, roots: &[PathBuf]) -> Option<String> {
+    let word = word.to_lowercase();
+    parser::sentences(&source_text(source, roots), 1, EPIGRAPH_WORDS)
+        .into_iter()
+        .filter(|sentence| parser::extract_words(sentence).contains(&word))
+        .choose(&mut rand::thread_rng())
+}
+
+/// The running text of a source file, found under one of `roots`, or of a built-in text
+fn source_text(source: &str, roots: &[PathBuf]) -> String {
+    let path = roots
+        .iter()
+        .find_map(|root| match root.is_dir() {
+            true => Some(root.join(source)).filter(|path| path.is_file()),
+            false => root.file_name().is_some_and(|name| name.to_string_lossy() == source).then(|| root.clone()),
+        })
+        .unwrap_or_else(|| PathBuf::from(source));
+    match builtin::source_text(source) {
+        Some(text) if !path.exists() => text.to_string(),
+        _ => parser::read_text(&path).unwrap_or_default(),
+    }
+}
+
+/// Parse a single file or every supported file in a directory into a word bank, folding word forms and
+/// translating if asked (after caching, so the cache holds the words as written), then pruning it as the
+/// pruning panel decided for this corpus before
+pub fn load_word_bank(input_path: &Path, options: &LoadOptions) -> Result<WordBank, Box<dyn std::error::Error>> {
+    let mut word_bank = read_word_bank(input_path, options)?;
+    adapt_word_bank(&mut word_bank, options);
+    let pruning = cache::load_pruning(input_path);
+    if !pruning.is_empty() {
+        pruning.iter().for_each(|decision| word_bank.prune(decision));
+        logging::info(format!("Applied {} pruning decisions to {}", pruning.len(), input_path.display()));
+    }
+    Ok(word_bank)
+}
+
+/// The words and phrases of an extra-words file as a bank of their own, every one kept whatever its length,
+/// under the source `extra words: FILE` so they can be told apart from the corpus
+pub fn load_extra_words(path: &Path) -> Result<WordBank, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string());
+    let word_bank = WordBank::from_entries(extra_word_entries(&content, &format!("extra words: {}", name)));
+    if word_bank.word_count() == 0 {
+        return Err(format!("{} has no words in it (one word or phrase per line)", path.display()).into());
+    }
+    Ok(word_bank)
+}
+
+/// One word or phrase per line; blank lines and `#` comments are skipped
+fn extra_word_entries(content: &str, source: &str) -> Vec<BankEntry> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|word| BankEntry {
+            word: word.to_string(),
+            source_file: source.to_string(),
+            location: parser::Location::default(),
+            count: 1,
+            contexts: Vec::new(),
+        })
+        .collect()
+}
+
+/// Fold word forms and translate words in an already loaded bank, as the options ask
+pub fn adapt_word_bank(word_bank: &mut WordBank, options: &LoadOptions) {
+    if let Some(display) = options.fold {
+        word_bank.fold_forms(display);
+    }
+    if let Some(translation) = &options.translation {
+        word_bank.translate(translation);
+    }
+}
+
+fn read_word_bank(input_path: &Path, options: &LoadOptions) -> Result<WordBank, Box<dyn std::error::Error>> {
+    let progress = |message: String| {
+        logging::info(&message);
+        if !options.quiet {
+            println!("{}", message);
+        }
+    };
+
+    if options.use_pandoc && !parser::pandoc_available() {
+        return Err("--use-pandoc was given but pandoc could not be run (is it installed and on PATH?)".into());
+    }
+
+    if options.use_cache {
+        if let Some(word_bank) = cache::load(input_path, options) {
+            progress(format!("Loaded {} unique words from cache", word_bank.word_count()));
+            return Ok(word_bank);
+        }
+        logging::detail(|| format!("no up-to-date cache for {}, parsing", input_path.display()));
+    }
+
+    let mut word_bank = WordBank::new();
+    let mut file_count = 0;
+
+    // Handle both single file and directory
+    if input_path.is_file() {
+        // Process single file
+        if !is_supported_file(input_path, options) {
+            let mut extensions = parser::NATIVE_EXTENSIONS.to_vec();
+            if options.use_pandoc {
+                extensions.extend_from_slice(parser::PANDOC_EXTENSIONS);
+            }
+            return Err(format!("'{}' is not a supported file type ({})", input_path.display(), extensions.join(", ")).into());
+        }
+
+        progress(format!("Parsing file: {}", input_path.display()));
+        let words = parse_corpus_file(input_path)
+            .map_err(|e| format!("Failed to parse {}: {}", input_path.display(), e))?;
+        let file_name = input_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        if let Some(title) = parser::epub_title(input_path) {
+            word_bank.set_title(file_name.clone(), title);
+        }
+        logging::detail(|| format!("{}: {} words", file_name, words.len()));
+        word_bank.add_words(words, file_name);
+        file_count += 1;
+    } else if input_path.is_dir() {
+        // Process directory
+        progress(format!("Scanning directory: {}", input_path.display()));
+
+        // The same book in two formats (e.g. .txt and .epub) is read once, so it isn't counted twice
+        let mut sketches: Vec<(String, String, Sketch)> = Vec::new();
+
+        for path in corpus_files(input_path, options)? {
+            progress(format!("Parsing: {}", path.display()));
+            match parse_corpus_file(&path) {
+                Ok(words) => {
+                    // Compute relative path from base directory
+                    let relative_path = path
+                        .strip_prefix(input_path)
+                        .unwrap_or(&path)
+                        .to_string_lossy()
+                        .replace('\\', "/"); // Normalize path separators
+
+                    if !options.keep_duplicates {
+                        let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_lowercase();
+                        let sketch = Sketch::new(&words);
+                        if let Some((original, _, _)) = sketches
+                            .iter()
+                            .find(|(_, other_stem, other)| is_duplicate((&stem, &sketch), (other_stem, other)))
+                        {
+                            progress(format!("Skipping {}: same text as {}", relative_path, original));
+                            continue;
+                        }
+                        sketches.push((relative_path.clone(), stem, sketch));
+                    }
+
+                    if let Some(title) = parser::epub_title(&path) {
+                        word_bank.set_title(relative_path.clone(), title);
+                    }
+                    logging::detail(|| format!("{}: {} words", relative_path, words.len()));
+
+                    word_bank.add_words(words, relative_path);
+                    file_count += 1;
+                }
+                Err(e) => {
+                    logging::warn(format!("failed to parse {}: {}", path.display(), e));
+                    if !options.quiet_warnings {
+                        eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+    } else {
+        return Err(format!("'{}' is neither a file nor a directory", input_path.display()).into());
+    }
+
+    progress(format!("Parsed {} files", file_count));
+    progress(format!("Collected {} unique words", word_bank.word_count()));
+
+    if word_bank.word_count() == 0 {
+        return Err("No words found".into());
+    }
+
+    if options.use_cache {
+        if let Err(e) = cache::save(input_path, options, &word_bank) {
+            logging::warn(format!("could not cache word bank: {}", e));
+            if !options.quiet_warnings {
+                eprintln!("Warning: Could not cache word bank: {}", e);
+            }
+        }
+    }
+
+    Ok(word_bank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{Location, ParsedWord};
+
+    fn parsed(text: &str) -> Vec<ParsedWord> {
+        text.split_whitespace()
+            .map(|word| ParsedWord { word: word.to_string(), location: Location::default() })
+            .collect()
+    }
+
+    #[test]
+    fn test_sketch_similarity_spots_duplicates() {
+        let book: String = (0..500).map(|i| format!("word{} ", i)).collect();
+        let with_front_matter = format!("contents copyright publisher {}", book);
+        let other: String = (300..800).map(|i| format!("word{} ", i)).collect();
+
+        let sketch = Sketch::new(&parsed(&book));
+        assert!(sketch.is_full());
+        assert!(sketch_similarity(&sketch, &Sketch::new(&parsed(&with_front_matter))) >= DUPLICATE_SIMILARITY);
+        assert!(sketch_similarity(&sketch, &Sketch::new(&parsed(&other))) < DUPLICATE_SIMILARITY);
+        assert_eq!(sketch_similarity(&Sketch::new(&[]), &Sketch::new(&[])), 0.0);
+    }
+
+    #[test]
+    fn test_short_files_kept_unless_same_name() {
+        let dir = std::env::temp_dir().join(format!("scatters-duplicates-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // Two short notes sharing their few words are different notes; the same name in two formats is one text
+        for (path, text) in [("monday.txt", "tide moon harbor"), ("tuesday.txt", "harbor moon tide"), ("tuesday.md", "harbor tide moon")] {
+            std::fs::write(dir.join(path), text).unwrap();
+        }
+        // Each file read counts "tide" once
+        let files_read = |options: LoadOptions| {
+            load_word_bank(&dir, &LoadOptions { quiet: true, ..options }).unwrap().frequencies()["tide"]
+        };
+        let checked = files_read(LoadOptions::default());
+        let unchecked = files_read(LoadOptions { keep_duplicates: true, ..Default::default() });
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(checked, 2);
+        assert_eq!(unchecked, 3);
+    }
+
+    #[test]
+    fn test_recursive_scan_with_globs() {
+        let dir = std::env::temp_dir().join(format!("scatters-scan-{}", std::process::id()));
+        for (path, text) in [("top.txt", "tide"), ("melville/whale.txt", "whale"), ("melville/drafts/old.txt", "old"), ("dickinson/bee.md", "bee")] {
+            std::fs::create_dir_all(dir.join(path).parent().unwrap()).unwrap();
+            std::fs::write(dir.join(path), text).unwrap();
+        }
+        let names = |options: LoadOptions| -> Vec<String> {
+            let files = corpus_files(&dir, &options).unwrap();
+            files.iter().map(|path| path.strip_prefix(&dir).unwrap().to_string_lossy().replace('\\', "/")).collect()
+        };
+
+        let flat = names(LoadOptions::default());
+        let recursive = names(LoadOptions { recursive: true, ..Default::default() });
+        let filtered = names(LoadOptions {
+            recursive: true,
+            include: vec!["*.txt".to_string()],
+            exclude: vec!["drafts".to_string()],
+            ..Default::default()
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(flat, vec!["top.txt"]);
+        assert_eq!(recursive, vec!["dickinson/bee.md", "melville/drafts/old.txt", "melville/whale.txt", "top.txt"]);
+        assert_eq!(filtered, vec!["melville/whale.txt", "top.txt"]);
+    }
+
+    #[test]
+    fn test_sentences_read_back_from_a_directory() {
+        let dir = std::env::temp_dir().join(format!("scatters-sentences-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sea")).unwrap();
+        std::fs::write(dir.join("sea/tide.txt"), "The lantern glowed over the harbor all night long.").unwrap();
+        let options = LoadOptions { quiet: true, recursive: true, ..LoadOptions::default() };
+        let bank = load_word_bank(&dir, &options).unwrap();
+        let sources: Vec<String> = bank.get_words().into_iter().map(|(_, source)| source).collect();
+        let sentences = sample_sentences(sources.iter().map(String::as_str), std::slice::from_ref(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(sources[0], "sea/tide.txt");
+        assert_eq!(sentences, vec!["The lantern glowed over the harbor all night long."]);
+    }
+
+    #[test]
+    fn test_extra_words_keep_every_line() {
+        let entries = extra_word_entries("# mine
+Ow
+
+  paper moon  
+", "extra words: mine.txt");
+        let words: Vec<&str> = entries.iter().map(|entry| entry.word.as_str()).collect();
+        assert_eq!(words, vec!["Ow", "paper moon"]);
+        assert!(entries.iter().all(|entry| entry.source_file == "extra words: mine.txt"));
+    }
+}
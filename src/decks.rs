@@ -0,0 +1,81 @@
+//! Named decks of favourite words, saved in the config directory so they outlast the session.
+
+use crate::config;
+use crate::parser::{Location, ParsedWord};
+use crate::word_bank::WordBank;
+use std::fs;
+use std::path::PathBuf;
+
+/// Deck the `f` key adds to until another is chosen
+pub const DEFAULT_DECK: &str = "favorites";
+
+/// `decks/NAME.txt` in the config directory
+fn deck_path(name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("'{}' isn't a usable deck name", name).into());
+    }
+    let dir = config::get_config_dir()?.join("decks");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.txt", name)))
+}
+
+/// One `word<TAB>source` line per word
+fn parse(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(word, source)| (word.trim().to_string(), source.trim().to_string()))
+        .filter(|(word, _)| !word.is_empty())
+        .collect()
+}
+
+/// The (word, source_file) pairs saved in a deck; an unknown deck is an error
+pub fn load(name: &str) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let path = deck_path(name)?;
+    let content = fs::read_to_string(&path).map_err(|_| format!("no deck named '{}' (add words to it with f)", name))?;
+    Ok(parse(&content))
+}
+
+/// Add a word to a deck, creating the deck if needed; returns false if it was already there
+pub fn add(name: &str, word: &str, source_file: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let path = deck_path(name)?;
+    let mut words = fs::read_to_string(&path).map(|content| parse(&content)).unwrap_or_default();
+    if words.iter().any(|(existing, _)| existing == word) {
+        return Ok(false);
+    }
+    words.push((word.to_string(), source_file.to_string()));
+
+    let content: String = words.iter().map(|(word, source)| format!("{}\t{}\n", word, source)).collect();
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+/// A deck as a word bank of its own, for scattering nothing but saved words
+pub fn word_bank(name: &str) -> Result<WordBank, Box<dyn std::error::Error>> {
+    let mut word_bank = WordBank::new();
+    for (word, source) in load(name)? {
+        word_bank.add_words(vec![ParsedWord { word, location: Location::default() }], source);
+    }
+    if word_bank.word_count() == 0 {
+        return Err(format!("deck '{}' is empty", name).into());
+    }
+    Ok(word_bank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deck() {
+        let words = parse("lantern\tpoems/a.txt\nbroken line\n\t b.txt\nharbor\tb.txt\n");
+        assert_eq!(
+            words,
+            vec![
+                ("lantern".to_string(), "poems/a.txt".to_string()),
+                ("harbor".to_string(), "b.txt".to_string()),
+            ]
+        );
+        assert!(deck_path("../elsewhere").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Editor embed mode: commands on stdin, one per line, set the size, seed, density and format, and each
+//! `generate` writes a scatter to stdout as JSON or as text ended by a lone `.` line.
+
+use crate::export;
+use crate::scatters::{ScattersGenerator, MAX_CANVAS};
+use serde_json::json;
+use std::io::{self, BufRead, Write};
+
+/// Line that terminates each response in text format, so plugins know where a block ends
+const TEXT_TERMINATOR: &str = ".";
+
+#[derive(Debug, PartialEq)]
+enum EmbedCommand {
+    Generate(Option<(u16, u16)>),
+    Seed(Option<u64>),
+    Density(f32),
+    Format(OutputFormat),
+    Quit,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum OutputFormat {
+    Json,
+    Text,
+}
+
+/// Parse one stdin line, e.g. `generate 80x24`, `seed 42`, `seed off`, `density 2.5`, `format text`
+fn parse_command(line: &str) -> Result<EmbedCommand, String> {
+    let mut parts = line.split_whitespace();
+    let command = parts.next().ok_or("empty command")?;
+    let argument = parts.next();
+
+    match (command, argument) {
+        ("generate", None) => Ok(EmbedCommand::Generate(None)),
+        ("generate", Some(size)) => parse_size(size).map(|size| EmbedCommand::Generate(Some(size))),
+        ("seed", Some("off")) => Ok(EmbedCommand::Seed(None)),
+        ("seed", Some(seed)) => seed
+            .parse()
+            .map(|seed| EmbedCommand::Seed(Some(seed)))
+            .map_err(|_| format!("invalid seed '{}'", seed)),
+        ("density", Some(density)) => density
+            .parse::<f32>()
+            .map(|density| EmbedCommand::Density(density.clamp(0.1, 6.0)))
+            .map_err(|_| format!("invalid density '{}'", density)),
+        ("format", Some("json")) => Ok(EmbedCommand::Format(OutputFormat::Json)),
+        ("format", Some("text")) => Ok(EmbedCommand::Format(OutputFormat::Text)),
+        ("quit", None) | ("exit", None) => Ok(EmbedCommand::Quit),
+        _ => Err(format!("unknown command '{}'", line.trim())),
+    }
+}
+
+/// Parse a `WIDTHxHEIGHT` canvas size, each side from 1 to `MAX_CANVAS` cells
+pub fn parse_size(size: &str) -> Result<(u16, u16), String> {
+    let (width, height) = size
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("invalid size '{}', expected WIDTHxHEIGHT", size))?;
+
+    let side = |name: &str, value: &str| match value.parse::<u16>() {
+        Ok(cells) if (1..=MAX_CANVAS).contains(&cells) => Ok(cells),
+        _ => Err(format!("invalid {} '{}', expected 1 to {}", name, value, MAX_CANVAS)),
+    };
+    Ok((side("width", width)?, side("height", height)?))
+}
+
+/// Serve embed commands from stdin until EOF or `quit`
+pub fn run(generator: &ScattersGenerator) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    let mut size = (80, 24);
+    let mut seed: Option<u64> = None;
+    let mut density = 1.0;
+    let mut format = OutputFormat::Json;
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_command(&line) {
+            Ok(EmbedCommand::Generate(new_size)) => {
+                size = new_size.unwrap_or(size);
+                let (width, height) = size;
+
+                // A fixed seed re-seeds every generate, so the same request gives the same layout
+                let scatter = match seed {
+                    Some(seed) => generator.generate_with_seed(seed, width, height, density),
+                    None => generator.generate_with_density(width, height, density),
+                };
+
+                match format {
+                    OutputFormat::Json => {
+                        let response = json!({
+                            "ok": true,
+                            "width": width,
+                            "height": height,
+                            "seed": seed,
+                            "requested": scatter.requested,
+                            "words": export::layout_json(&scatter.words),
+                        });
+                        writeln!(stdout, "{}", response)?;
+                    }
+                    OutputFormat::Text => {
+                        write!(stdout, "{}", export::render_text(&scatter.words, width, height))?;
+                        writeln!(stdout, "{}", TEXT_TERMINATOR)?;
+                    }
+                }
+            }
+            Ok(EmbedCommand::Seed(new_seed)) => seed = new_seed,
+            Ok(EmbedCommand::Density(new_density)) => density = new_density,
+            Ok(EmbedCommand::Format(new_format)) => format = new_format,
+            Ok(EmbedCommand::Quit) => break,
+            Err(message) => match format {
+                OutputFormat::Json => writeln!(stdout, "{}", json!({ "ok": false, "error": message }))?,
+                OutputFormat::Text => writeln!(stdout, "error: {}\n{}", message, TEXT_TERMINATOR)?,
+            },
+        }
+
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse_command("generate 80x24"), Ok(EmbedCommand::Generate(Some((80, 24)))));
+        assert_eq!(parse_command("seed 42"), Ok(EmbedCommand::Seed(Some(42))));
+        assert_eq!(parse_command("seed off"), Ok(EmbedCommand::Seed(None)));
+        assert_eq!(parse_command("format text"), Ok(EmbedCommand::Format(OutputFormat::Text)));
+        assert!(parse_command("generate 80").is_err());
+        assert!(parse_size("0x24").is_err());
+        assert!(parse_size("80x0").is_err());
+        assert!(parse_size("1001x24").is_err());
+        assert!(parse_size("65535x65535").is_err());
+        assert_eq!(parse_size("1000x1000"), Ok((1000, 1000)));
+        assert!(parse_command("dance").is_err());
+    }
+}
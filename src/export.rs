@@ -0,0 +1,603 @@
+//! Snapshots of the canvas as text, JSON, ANSI, SVG or PNG, with the title, epigraph, watermark and
+//! reading path drawn in, shared by the TUI, the server and embed mode.
+
+use crate::raster::{self, Typeface};
+use crate::reading_path;
+use crate::scatters::{ScatteredWord, Voice};
+use crossterm::style::{Attribute, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::BorderType;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// File format for snapshots of the canvas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Text,  // Words at their canvas positions
+    Json,  // Canvas size and the positioned words
+    Ansi,  // The text layout in the terminal's colors, with a legend for them
+    Svg,  // A drawing in a monospace font, optionally fitted to a page
+    Png,  // The drawing as an image, in the built-in font or the user's
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "text" | "txt" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "ansi" => Ok(Self::Ansi),
+            "svg" => Ok(Self::Svg),
+            "png" => Ok(Self::Png),
+            _ => Err(format!("unknown export format '{}', expected text, json, ansi, svg or png", value)),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Json => "json",
+            Self::Ansi => "ans",
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+}
+
+/// SVG font size, and the cell it takes in a monospace font, in pixels
+pub const SVG_FONT_SIZE: f32 = 16.0;
+pub const SVG_CELL: (f32, f32) = (9.6, 20.0);
+
+/// Resolution PNG exports of a printed page are drawn at
+const PAGE_DPI: f32 = 150.0;
+
+/// Standard page an SVG or PNG export is laid out on, so it lands ready-sized in a print layout
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PagePreset {
+    A4,  // 210 x 297 mm with 20 mm margins
+    Letter,  // 8.5 x 11 in with 1 in margins
+    Square,  // 1080 x 1080 px social image with 60 px margins
+}
+
+impl PagePreset {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "a4" => Ok(Self::A4),
+            "letter" => Ok(Self::Letter),
+            "square" => Ok(Self::Square),
+            _ => Err(format!("unknown page '{}', expected a4, letter or square", value)),
+        }
+    }
+
+    /// Portrait width and height, the margin on every side, and the unit they're measured in
+    fn dimensions(self) -> (f32, f32, f32, &'static str) {
+        match self {
+            Self::A4 => (210.0, 297.0, 20.0, "mm"),
+            Self::Letter => (8.5, 11.0, 1.0, "in"),
+            Self::Square => (1080.0, 1080.0, 60.0, ""),
+        }
+    }
+
+    /// Portrait width, height and margin in pixels, with printed pages at 150 dpi
+    pub fn pixel_size(self) -> (f32, f32, f32) {
+        let (width, height, margin, unit) = self.dimensions();
+        let pixels = match unit {
+            "mm" => PAGE_DPI / 25.4,
+            "in" => PAGE_DPI,
+            _ => 1.0,
+        };
+        (width * pixels, height * pixels, margin * pixels)
+    }
+}
+
+/// Names and colors of the two voices in dialogue mode, for the legend
+pub struct VoiceLegend<'a> {
+    pub names: &'a [String; 2],
+    pub styles: [Style; 2],
+}
+
+impl VoiceLegend<'_> {
+    pub fn style(&self, voice: Voice) -> Style {
+        match voice {
+            Voice::A => self.styles[0],
+            Voice::B => self.styles[1],
+        }
+    }
+}
+
+/// Extras drawn over the words in an export
+#[derive(Default)]
+pub struct Decorations<'a> {
+    pub path: &'a [usize],  // Reading path through these word indices, in visit order
+    pub title: Option<&'a str>,  // Title block for the bottom right corner
+    pub frame: Option<BorderType>,  // Border drawn around text exports, matching the canvas
+    pub source_titles: Option<&'a HashMap<String, String>>,  // Names to use instead of file paths in JSON exports
+    pub watermark: Option<&'a str>,  // Sentence laid faintly across the background, behind the words
+    pub epigraph: Option<&'a str>,  // Source sentence pinned along the bottom, over the words
+    pub voices: Option<VoiceLegend<'a>>,  // Voice colors and names, so exports can say which is which
+    pub page: Option<PagePreset>,  // Page SVG and PNG exports are fitted to, inside its margins
+    pub typeface: Option<&'a Typeface>,  // Font PNG exports are drawn in, the built-in one when None
+}
+
+/// The canvas in the given format, including its decorations, as the contents of a file; only a PNG too
+/// big to draw fails
+pub fn render(format: ExportFormat, words: &[ScatteredWord], decorations: &Decorations, width: u16, height: u16) -> Result<Vec<u8>, String> {
+    let text = match format {
+        ExportFormat::Text => render_text_decorated(words, decorations, width, height),
+        ExportFormat::Ansi => render_ansi(words, decorations, width, height),
+        ExportFormat::Svg => render_svg(words, decorations, width, height),
+        ExportFormat::Png => return raster::render(words, decorations, width, height),
+        ExportFormat::Json => {
+            let mut layout = layout_json(words);
+            if let (Some(titles), Some(entries)) = (decorations.source_titles, layout.as_array_mut()) {
+                for entry in entries {
+                    if let Some(title) = entry["source"].as_str().and_then(|source| titles.get(source)) {
+                        entry["source"] = json!(title);
+                    }
+                }
+            }
+            let mut snapshot = json!({ "width": width, "height": height, "words": layout });
+            if !decorations.path.is_empty() {
+                snapshot["path"] = json!(decorations.path);
+            }
+            if let Some(title) = decorations.title {
+                snapshot["title"] = json!(title);
+            }
+            if let Some(watermark) = decorations.watermark {
+                snapshot["watermark"] = json!(watermark);
+            }
+            if let Some(epigraph) = decorations.epigraph {
+                snapshot["epigraph"] = json!(epigraph);
+            }
+            if let Some(voices) = &decorations.voices {
+                snapshot["voices"] = json!({ "a": voices.names[0], "b": voices.names[1] });
+            }
+            format!("{}\n", snapshot)
+        }
+    };
+    Ok(text.into_bytes())
+}
+
+/// Where a title block starts: right-aligned on the bottom row
+pub fn title_origin(title: &str, width: u16, height: u16) -> (u16, u16) {
+    (width.saturating_sub(title.chars().count() as u16), height.saturating_sub(1))
+}
+
+/// Where an epigraph goes: quoted, wrapped to two thirds of the canvas width and right-aligned on the
+/// bottom rows, ending just above the title block when there is one
+pub fn epigraph_lines(epigraph: &str, width: u16, height: u16, titled: bool) -> Vec<(u16, u16, String)> {
+    let rows = height.saturating_sub(titled as u16) as usize;
+    let mut lines = wrap_words(&format!("“{}”", epigraph), (width as usize * 2 / 3).max(1));
+    lines.drain(..lines.len().saturating_sub(rows));
+
+    let first = rows - lines.len();
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line: String = line.chars().take(width as usize).collect();
+            let x = width as usize - line.chars().count();
+            (x as u16, (first + i) as u16, line)
+        })
+        .collect()
+}
+
+/// Text broken into lines of at most `width` characters at spaces; a longer word gets a line of its own
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Where a watermark sentence goes: wrapped to most of the canvas width, each line centred, and the
+/// lines spread evenly down the canvas
+pub fn watermark_lines(sentence: &str, width: u16, height: u16) -> Vec<(u16, u16, String)> {
+    let mut lines = wrap_words(sentence, (width as usize * 4 / 5).max(1));
+    lines.truncate(height as usize);
+
+    let count = lines.len();
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line: String = line.chars().take(width as usize).collect();
+            let x = (width as usize - line.chars().count()) / 2;
+            let y = (i + 1) * height as usize / (count + 1);
+            (x as u16, y as u16, line)
+        })
+        .collect()
+}
+
+/// Lay the words out on a width x height character grid, matching their canvas positions
+pub fn render_text(words: &[ScatteredWord], width: u16, height: u16) -> String {
+    render_text_decorated(words, &Decorations::default(), width, height)
+}
+
+/// Like `render_text`, with the reading path and title block drawn in
+pub fn render_text_decorated(words: &[ScatteredWord], decorations: &Decorations, width: u16, height: u16) -> String {
+    let mut grid = vec![vec![' '; width as usize]; height as usize];
+
+    // The watermark goes underneath, with a blank cell kept either side of each word so words stay legible
+    let mut watermarked = vec![vec![false; width as usize]; height as usize];
+    for (x, y, line) in decorations.watermark.map(|sentence| watermark_lines(sentence, width, height)).unwrap_or_default() {
+        for (offset, c) in line.chars().enumerate() {
+            grid[y as usize][x as usize + offset] = c;
+            watermarked[y as usize][x as usize + offset] = true;
+        }
+    }
+    for scattered in words {
+        let (row, start) = (scattered.y as usize, scattered.x as usize);
+        let end = start + scattered.word.chars().count();
+        for x in [start.checked_sub(1), Some(end)].into_iter().flatten() {
+            if watermarked.get(row).and_then(|cells| cells.get(x)) == Some(&true) {
+                grid[row][x] = ' ';
+            }
+        }
+    }
+
+    for (x, y, symbol) in reading_path::path_cells(words, decorations.path, width, height) {
+        grid[y as usize][x as usize] = symbol;
+    }
+
+    for scattered in words {
+        if let Some(row) = grid.get_mut(scattered.y as usize) {
+            for (offset, c) in scattered.word.chars().enumerate() {
+                if let Some(cell) = row.get_mut(scattered.x as usize + offset) {
+                    *cell = c;
+                }
+            }
+        }
+    }
+
+    for (x, y, line) in decorations.epigraph.map(|epigraph| epigraph_lines(epigraph, width, height, decorations.title.is_some())).unwrap_or_default() {
+        for (cell, c) in grid[y as usize][x as usize..].iter_mut().zip(line.chars()) {
+            *cell = c;
+        }
+    }
+
+    if let Some(title) = decorations.title.filter(|_| height > 0) {
+        let (x, y) = title_origin(title, width, height);
+        for (cell, c) in grid[y as usize][x as usize..].iter_mut().zip(title.chars()) {
+            *cell = c;
+        }
+    }
+
+    if let Some(border_type) = decorations.frame {
+        return framed(grid, border_type);
+    }
+
+    // Trailing spaces carry no layout information, so drop them from each row
+    let mut lines: Vec<String> = grid
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect();
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// The text layout with each voice's words in its color, and a legend naming the voices underneath
+fn render_ansi(words: &[ScatteredWord], decorations: &Decorations, width: u16, height: u16) -> String {
+    let text = render_text_decorated(words, decorations, width, height);
+    let Some(voices) = &decorations.voices else {
+        return text;
+    };
+
+    // A frame moves every word one cell right and down
+    let offset = decorations.frame.is_some() as usize;
+    let rows: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+    let mut styles: Vec<Vec<Option<Style>>> = rows.iter().map(|row| vec![None; row.len()]).collect();
+    for scattered in words {
+        let Some(voice) = scattered.voice else { continue };
+        let row = styles.get_mut(scattered.y as usize + offset);
+        let start = scattered.x as usize + offset;
+        for cell in row.into_iter().flat_map(|row| row.iter_mut().skip(start).take(scattered.word.chars().count())) {
+            *cell = Some(voices.style(voice));
+        }
+    }
+
+    let mut ansi = String::new();
+    for (row, row_styles) in rows.into_iter().zip(styles) {
+        let mut current = String::new();
+        for (c, style) in row.into_iter().zip(row_styles) {
+            let codes = style.map(sgr).unwrap_or_default();
+            if codes != current {
+                ansi.push_str(&reset_after(&current));
+                ansi.push_str(&codes);
+                current = codes;
+            }
+            ansi.push(c);
+        }
+        ansi.push_str(&reset_after(&current));
+        ansi.push('\n');
+    }
+
+    ansi.push('\n');
+    for (name, style) in voices.names.iter().zip(voices.styles) {
+        let codes = sgr(style);
+        ansi.push_str(&format!("{}■{} {}\n", codes, reset_after(&codes), name));
+    }
+    ansi
+}
+
+/// The canvas drawn in a monospace font, at its own size or scaled to fit a page preset's margins (turned
+/// to landscape for a wide canvas) and centred there; in dialogue mode a legend names each voice's color
+fn render_svg(words: &[ScatteredWord], decorations: &Decorations, width: u16, height: u16) -> String {
+    let legend_rows = decorations.voices.as_ref().map_or(0, |_| 3);
+    let (canvas_width, canvas_height) = (width as f32 * SVG_CELL.0, height as f32 * SVG_CELL.1);
+    let drawing_height = canvas_height + legend_rows as f32 * SVG_CELL.1;
+
+    let (page_width, page_height, unit, scale) = match decorations.page {
+        None => (canvas_width, drawing_height, "", 1.0),
+        Some(page) => {
+            let (mut page_width, mut page_height, margin, unit) = page.dimensions();
+            if canvas_width > drawing_height {
+                std::mem::swap(&mut page_width, &mut page_height);
+            }
+            let scale = ((page_width - 2.0 * margin) / canvas_width).min((page_height - 2.0 * margin) / drawing_height);
+            (page_width, page_height, unit, scale)
+        }
+    };
+    let (left, top) = ((page_width - canvas_width * scale) / 2.0, (page_height - drawing_height * scale) / 2.0);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}{unit}\" height=\"{h}{unit}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n\
+         <g transform=\"translate({} {}) scale({})\" font-family=\"monospace\" font-size=\"{}\" fill=\"black\">\n",
+        left,
+        top,
+        scale,
+        SVG_FONT_SIZE,
+        w = page_width,
+        h = page_height,
+        unit = unit,
+    );
+    let text = |x: f32, y: f32, fill: Option<String>, content: &str| {
+        let fill = fill.map(|fill| format!(" fill=\"{}\"", fill)).unwrap_or_default();
+        let baseline = (y + 0.8) * SVG_CELL.1;
+        format!("<text x=\"{}\" y=\"{}\"{} xml:space=\"preserve\">{}</text>\n", x * SVG_CELL.0, baseline, fill, escape_xml(content))
+    };
+
+    if decorations.frame.is_some() {
+        svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>\n", canvas_width, canvas_height));
+    }
+    for (x, y, line) in decorations.watermark.map(|sentence| watermark_lines(sentence, width, height)).unwrap_or_default() {
+        svg.push_str(&text(x as f32, y as f32, Some("#c8c8c8".to_string()), &line));
+    }
+    let centres: Vec<String> = decorations
+        .path
+        .iter()
+        .filter_map(|&index| words.get(index))
+        .map(|word| {
+            let x = (word.x as f32 + word.word.chars().count() as f32 / 2.0) * SVG_CELL.0;
+            format!("{},{}", x, (word.y as f32 + 0.5) * SVG_CELL.1)
+        })
+        .collect();
+    if centres.len() > 1 {
+        svg.push_str(&format!("<polyline points=\"{}\" fill=\"none\" stroke=\"#999999\"/>\n", centres.join(" ")));
+    }
+
+    for scattered in words {
+        let fill = match (&decorations.voices, scattered.voice) {
+            (Some(voices), Some(voice)) => svg_color(voices.style(voice)),
+            _ => None,
+        };
+        svg.push_str(&text(scattered.x as f32, scattered.y as f32, fill, &scattered.word));
+    }
+    for (x, y, line) in decorations.epigraph.map(|epigraph| epigraph_lines(epigraph, width, height, decorations.title.is_some())).unwrap_or_default() {
+        svg.push_str(&text(x as f32, y as f32, None, &line));
+    }
+    if let Some(title) = decorations.title.filter(|_| height > 0) {
+        let (x, y) = title_origin(title, width, height);
+        svg.push_str(&text(x as f32, y as f32, None, title));
+    }
+    if let Some(voices) = &decorations.voices {
+        for (row, (name, style)) in voices.names.iter().zip(voices.styles).enumerate() {
+            let y = (height as usize + 1 + row) as f32;
+            svg.push_str(&text(0.0, y, svg_color(style), "■"));
+            svg.push_str(&text(2.0, y, None, name));
+        }
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// A style's text color as `#rrggbb`, for SVG
+fn svg_color(style: Style) -> Option<String> {
+    print_rgb(style).map(|[r, g, b]| format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// A style's text color for a white page; white and the terminal's default print black, so give None
+pub fn print_rgb(style: Style) -> Option<[u8; 3]> {
+    match style.fg? {
+        Color::Rgb(r, g, b) => Some([r, g, b]),
+        Color::Red => Some([139, 0, 0]),
+        Color::Green => Some([0, 128, 0]),
+        Color::Yellow => Some([128, 128, 0]),
+        Color::Blue => Some([0, 0, 128]),
+        Color::Magenta => Some([128, 0, 128]),
+        Color::Cyan => Some([0, 128, 128]),
+        Color::Gray => Some([192, 192, 192]),
+        Color::DarkGray => Some([128, 128, 128]),
+        Color::LightRed => Some([255, 0, 0]),
+        Color::LightGreen => Some([50, 205, 50]),
+        Color::LightYellow => Some([218, 165, 32]),
+        Color::LightBlue => Some([0, 0, 255]),
+        Color::LightMagenta => Some([255, 0, 255]),
+        Color::LightCyan => Some([0, 206, 209]),
+        _ => None,
+    }
+}
+
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// The reset that ends text drawn with these escape codes, if there are any
+fn reset_after(codes: &str) -> String {
+    if codes.is_empty() {
+        String::new()
+    } else {
+        SetAttribute(Attribute::Reset).to_string()
+    }
+}
+
+/// Escape codes for a style's colors and text attributes
+fn sgr(style: Style) -> String {
+    let mut codes = style.fg.map(|color| SetForegroundColor(color.into()).to_string()).unwrap_or_default();
+    codes.extend(style.bg.map(|color| SetBackgroundColor(color.into()).to_string()));
+    for (modifier, attribute) in [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::REVERSED, Attribute::Reverse),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push_str(&SetAttribute(attribute).to_string());
+        }
+    }
+    codes
+}
+
+/// The full grid inside a box drawn with the canvas border's characters
+fn framed(grid: Vec<Vec<char>>, border_type: BorderType) -> String {
+    let symbols = border_type.to_border_set();
+    let width = grid.first().map_or(0, Vec::len);
+
+    let mut text = format!("{}{}{}\n", symbols.top_left, symbols.horizontal_top.repeat(width), symbols.top_right);
+    for row in grid {
+        let row: String = row.into_iter().collect();
+        text.push_str(&format!("{}{}{}\n", symbols.vertical_left, row, symbols.vertical_right));
+    }
+    text.push_str(&format!(
+        "{}{}{}\n",
+        symbols.bottom_left,
+        symbols.horizontal_bottom.repeat(width),
+        symbols.bottom_right
+    ));
+    text
+}
+
+/// Positioned words as a JSON array of {word, x, y, source} objects, plus "voice" in dialogue mode
+pub fn layout_json(words: &[ScatteredWord]) -> Value {
+    words
+        .iter()
+        .map(|w| {
+            let mut entry = json!({ "word": w.word, "x": w.x, "y": w.y, "source": w.source_file });
+            if let Some(voice) = w.voice {
+                entry["voice"] = json!(voice.label());
+            }
+            entry
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, x: u16, y: u16) -> ScatteredWord {
+        ScatteredWord {
+            word: word.to_string(),
+            x,
+            y,
+            source_file: "test.txt".to_string(),
+            voice: None,
+        }
+    }
+
+    #[test]
+    fn test_render_text_positions() {
+        let words = vec![word("hello", 2, 0), word("world", 0, 2)];
+        let text = render_text(&words, 10, 4);
+        assert_eq!(text, "  hello\n\nworld\n");
+    }
+
+    #[test]
+    fn test_render_text_decorated() {
+        let words = vec![word("ab", 0, 0), word("cd", 0, 2)];
+        let decorations = Decorations { path: &[0, 1], title: Some("[ x ]"), ..Decorations::default() };
+        let text = render_text_decorated(&words, &decorations, 8, 3);
+        assert_eq!(text, "ab\n │\ncd [ x ]\n");
+    }
+
+    #[test]
+    fn test_json_uses_source_titles() {
+        let titles = HashMap::from([("test.txt".to_string(), "Moby-Dick — Herman Melville".to_string())]);
+        let decorations = Decorations { source_titles: Some(&titles), ..Decorations::default() };
+        let json = String::from_utf8(render(ExportFormat::Json, &[word("whale", 0, 0)], &decorations, 10, 2).unwrap()).unwrap();
+        assert!(json.contains("\"source\":\"Moby-Dick — Herman Melville\""));
+    }
+
+    #[test]
+    fn test_watermark_under_words() {
+        let decorations = Decorations { watermark: Some("the sea was calm"), ..Decorations::default() };
+        let text = render_text_decorated(&[word("ab", 7, 1)], &decorations, 20, 3);
+        assert_eq!(text, "\n  the  ab was calm\n");
+    }
+
+    #[test]
+    fn test_epigraph_above_title() {
+        let decorations = Decorations { epigraph: Some("the sea was calm"), title: Some("[ x ]"), ..Decorations::default() };
+        let text = render_text_decorated(&[word("ab", 0, 0)], &decorations, 15, 4);
+        assert_eq!(text, "ab\n       “the sea\n      was calm”\n          [ x ]\n");
+        assert_eq!(epigraph_lines("the sea was calm", 15, 1, true), vec![]);
+    }
+
+    #[test]
+    fn test_ansi_colors_voices_with_legend() {
+        let names = ["rumi".to_string(), "manuals".to_string()];
+        let voices = VoiceLegend { names: &names, styles: [Style::default(), Style::default().add_modifier(Modifier::ITALIC)] };
+        let words = vec![
+            ScatteredWord { voice: Some(Voice::A), ..word("ab", 0, 0) },
+            ScatteredWord { voice: Some(Voice::B), ..word("cd", 3, 0) },
+        ];
+        let decorations = Decorations { voices: Some(voices), ..Decorations::default() };
+        let ansi = String::from_utf8(render(ExportFormat::Ansi, &words, &decorations, 6, 1).unwrap()).unwrap();
+        assert_eq!(ansi, "ab \x1b[3mcd\x1b[0m\n\n■ rumi\n\x1b[3m■\x1b[0m manuals\n");
+
+        // Without voices there's nothing to color, so it's the text layout
+        assert_eq!(render(ExportFormat::Ansi, &words, &Decorations::default(), 6, 1).unwrap(), b"ab cd\n");
+    }
+
+    #[test]
+    fn test_render_text_framed() {
+        let words = vec![word("ab", 1, 0)];
+        let decorations = Decorations { frame: Some(BorderType::Plain), ..Decorations::default() };
+        let text = render_text_decorated(&words, &decorations, 4, 2);
+        assert_eq!(text, "┌────┐\n│ ab │\n│    │\n└────┘\n");
+    }
+
+    #[test]
+    fn test_svg_fitted_to_page() {
+        let words = vec![word("a&b", 1, 0)];
+        let svg = String::from_utf8(render(ExportFormat::Svg, &words, &Decorations::default(), 10, 5).unwrap()).unwrap();
+        assert!(svg.contains("width=\"96\" height=\"100\""));
+        assert!(svg.contains("<text x=\"9.6\" y=\"16\" xml:space=\"preserve\">a&amp;b</text>"));
+
+        // A wide canvas turns the page to landscape and is scaled to the width inside the margins
+        let decorations = Decorations { page: Some(PagePreset::A4), ..Decorations::default() };
+        let svg = String::from_utf8(render(ExportFormat::Svg, &words, &decorations, 100, 5).unwrap()).unwrap();
+        assert!(svg.contains("width=\"297mm\" height=\"210mm\" viewBox=\"0 0 297 210\""));
+        assert!(svg.contains("translate(20 "));
+        assert!(PagePreset::parse("tabloid").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Poems brought back onto the canvas: the words of a saved snapshot or poem, read with the positions
+//! they were saved at, so `:import` can pin them in place while fresh words are scattered around them.
+
+use crate::scatters::{ScatteredWord, Voice, MAX_CANVAS};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Box-drawing characters, which the canvas border and reading path are drawn with
+const BOX_DRAWING: std::ops::RangeInclusive<char> = '\u{2500}'..='\u{257F}';
+
+/// The words saved in the file at `path`: a JSON snapshot, or a text snapshot or poem read off its grid
+pub fn load(path: &Path) -> Result<Vec<ScatteredWord>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("can't read {}: {}", path.display(), e))?;
+    let source = path.display().to_string();
+    let words = if path.extension().is_some_and(|extension| extension == "json") {
+        from_json(&content, &source)?
+    } else {
+        from_text(&content, &source)
+    };
+    if words.is_empty() {
+        return Err(format!("no words in {}", path.display()));
+    }
+    Ok(words)
+}
+
+/// Words of a JSON snapshot, or of a bare layout array, at their saved positions; a word off the
+/// snapshot's canvas, or off the largest canvas for a bare array, is an error
+fn from_json(content: &str, source: &str) -> Result<Vec<ScatteredWord>, String> {
+    let snapshot: Value = serde_json::from_str(content).map_err(|e| format!("not a JSON snapshot: {}", e))?;
+    let entries = snapshot.get("words").unwrap_or(&snapshot).as_array().ok_or("a JSON snapshot has a list of words")?;
+    let size = |key: &str| snapshot.get(key).and_then(Value::as_u64).map_or(MAX_CANVAS as u64, |size| size.min(MAX_CANVAS as u64));
+    let (width, height) = (size("width"), size("height"));
+    let words: Vec<ScatteredWord> = entries
+        .iter()
+        .filter_map(|entry| {
+            let position = |key: &str| entry[key].as_u64().and_then(|value| u16::try_from(value).ok());
+            Some(ScatteredWord {
+                word: entry["word"].as_str()?.to_string(),
+                x: position("x")?,
+                y: position("y")?,
+                source_file: entry["source"].as_str().unwrap_or(source).to_string(),
+                voice: match entry["voice"].as_str() {
+                    Some("a") => Some(Voice::A),
+                    Some("b") => Some(Voice::B),
+                    _ => None,
+                },
+            })
+        })
+        .collect();
+    match words.iter().find(|word| word.x as u64 + word.word.chars().count() as u64 > width || word.y as u64 >= height) {
+        Some(word) => Err(format!("'{}' at {}, {} is off the {}x{} canvas", word.word, word.x, word.y, width, height)),
+        None => Ok(words),
+    }
+}
+
+/// Words of a text snapshot or poem at the column and row they're written at; a canvas border is taken
+/// off first, and the reading path and title block are left behind
+fn from_text(content: &str, source: &str) -> Vec<ScatteredWord> {
+    let mut lines: Vec<Vec<char>> = content.lines().map(|line| line.chars().collect()).collect();
+    let framed = lines.first().and_then(|line| line.first()).is_some_and(|c| matches!(c, '┌' | '╭' | '╔' | '┏'));
+    if framed && lines.len() >= 2 {
+        lines = lines[1..lines.len() - 1]
+            .iter()
+            .map(|line| line.get(1..line.len().saturating_sub(1)).unwrap_or_default().to_vec())
+            .collect();
+    }
+
+    let mut words = Vec::new();
+    for (y, line) in lines.iter_mut().enumerate() {
+        // The title block, `[ Harbor Songs · 2026-10-15 ]`
+        if let Some(open) = line.iter().position(|&c| c == '[') {
+            if let Some(close) = line[open..].iter().position(|&c| c == ']') {
+                line[open..=open + close].fill(' ');
+            }
+        }
+        let mut x = 0;
+        while x < line.len() {
+            if line[x].is_whitespace() || BOX_DRAWING.contains(&line[x]) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < line.len() && !line[x].is_whitespace() && !BOX_DRAWING.contains(&line[x]) {
+                x += 1;
+            }
+            let word: String = line[start..x].iter().collect();
+            if let (Ok(x), Ok(y)) = (u16::try_from(start), u16::try_from(y)) {
+                words.push(ScatteredWord { word, x, y, source_file: source.to_string(), voice: None });
+            }
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_read_at_saved_positions() {
+        let snapshot = "┌──────────────────┐\n│  tide            │\n│  │               │\n│  harbor [ x · 1 ]│\n└──────────────────┘\n";
+        let words: Vec<(String, u16, u16)> =
+            from_text(snapshot, "s.txt").into_iter().map(|word| (word.word, word.x, word.y)).collect();
+        assert_eq!(words, vec![("tide".to_string(), 2, 0), ("harbor".to_string(), 2, 2)]);
+
+        let poem = from_text("the lantern\n\ndrowns\n", "poem.txt");
+        assert_eq!((poem[1].x, poem[2].y), (4, 2));
+
+        let json = r#"{"width":10,"height":2,"words":[{"word":"whale","x":3,"y":1,"source":"a.txt","voice":"b"}]}"#;
+        let words = from_json(json, "s.json").unwrap();
+        assert_eq!((words[0].x, words[0].y, words[0].voice), (3, 1, Some(Voice::B)));
+        assert!(from_json("[1, 2", "s.json").is_err());
+        assert!(from_json(r#"{"width":10,"height":2,"words":[{"word":"whale","x":6,"y":1}]}"#, "s.json").is_err());
+        assert!(from_json(r#"[{"word":"whale","x":65534,"y":0}]"#, "s.json").is_err());
+    }
+}
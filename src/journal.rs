@@ -0,0 +1,71 @@
+//! The practice journal: with `journal = on` in settings.conf, every save also adds an entry to the
+//! month's Markdown file under `journal/` in the config directory.
+
+use crate::config;
+use crate::timeline;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// What one save put on record
+pub struct Entry<'a> {
+    pub saved: &'a Path,  // File the save wrote
+    pub seed: u64,
+    pub size: (u16, u16),  // Canvas size the scatter was generated for
+    pub sources: Vec<&'a str>,  // Names of the files the scatter's words came from
+    pub text: &'a str,  // The poem, couplets or scatter as saved
+}
+
+impl Entry<'_> {
+    fn to_markdown(&self, timestamp: &str) -> String {
+        let name = self.saved.file_name().map_or_else(|| self.saved.display().to_string(), |name| name.to_string_lossy().to_string());
+        format!(
+            "## {} · {}\n\n- Seed: {} ({}x{})\n- Sources: {}\n\n```text\n{}\n```\n\n",
+            timestamp,
+            name,
+            self.seed,
+            self.size.0,
+            self.size.1,
+            self.sources.join(", "),
+            self.text.trim_end_matches('\n'),
+        )
+    }
+}
+
+/// `journal/YYYY-MM.md` in the config directory, for the month of `date`
+fn journal_path(date: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = config::get_config_dir()?.join("journal");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.md", &date[..7])))
+}
+
+/// Append an entry to this month's journal, starting the file with a heading if it's new
+pub fn append(entry: &Entry) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let timestamp = timeline::now();
+    let path = journal_path(&timestamp)?;
+    let heading = (!path.exists()).then(|| format!("# Journal {}\n\n", &timestamp[..7]));
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(format!("{}{}", heading.unwrap_or_default(), entry.to_markdown(&timestamp)).as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_markdown() {
+        let entry = Entry {
+            saved: Path::new("/tmp/exports/poem-0002.txt"),
+            seed: 4127,
+            size: (80, 24),
+            sources: vec!["Moby-Dick — Herman Melville", "poems/a.txt"],
+            text: "lantern\nharbor\n",
+        };
+        assert_eq!(
+            entry.to_markdown("2026-10-15 14:03 UTC"),
+            "## 2026-10-15 14:03 UTC · poem-0002.txt\n\n- Seed: 4127 (80x24)\n- Sources: Moby-Dick — Herman Melville, poems/a.txt\n\n```text\nlantern\nharbor\n```\n\n"
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! The session log: what loading the corpus and generating scatters decided, kept in memory for the
+//! `:log` panel and, with `--verbose`, written to `scatters.log` in the config directory as it happens.
+
+use crate::config;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lines the `:log` panel can scroll back through
+const RECENT_LINES: usize = 500;
+
+/// How much is said, on the terminal and in the log
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Level {
+    Quiet,  // No progress or warnings on the terminal; the log still keeps them
+    #[default]
+    Normal,
+    Verbose,  // Every parsing and generation decision too, written to the log file as well
+}
+
+struct Log {
+    level: Level,
+    recent: VecDeque<String>,
+    file: Option<File>,
+}
+
+static LOG: Mutex<Log> = Mutex::new(Log { level: Level::Normal, recent: VecDeque::new(), file: None });
+
+/// Set how much is said; verbose logging starts a fresh `scatters.log` and returns its path
+pub fn init(level: Level) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    let path = match level {
+        Level::Verbose => Some(config::get_config_dir()?.join("scatters.log")),
+        _ => None,
+    };
+    let file = path.as_ref().map(File::create).transpose()?;
+    let mut log = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    log.level = level;
+    log.file = file;
+    Ok(path)
+}
+
+fn level() -> Level {
+    LOG.lock().map_or(Level::Normal, |log| log.level)
+}
+
+/// Keep a line in the log
+pub fn info(message: impl AsRef<str>) {
+    record(message.as_ref());
+}
+
+/// Keep a warning in the log
+pub fn warn(message: impl AsRef<str>) {
+    record(&format!("warning: {}", message.as_ref()));
+}
+
+/// Keep a line in the log when it's verbose; `message` is only built then
+pub fn detail(message: impl FnOnce() -> String) {
+    if level() == Level::Verbose {
+        record(&message());
+    }
+}
+
+fn record(message: &str) {
+    let Ok(mut log) = LOG.lock() else { return };
+    let line = format!("{} {}", clock(), message);
+    if let Some(file) = &mut log.file {
+        let _ = writeln!(file, "{}", line);
+    }
+    if log.recent.len() == RECENT_LINES {
+        log.recent.pop_front();
+    }
+    log.recent.push_back(line);
+}
+
+/// The lines kept so far, oldest first
+pub fn recent() -> Vec<String> {
+    LOG.lock().map(|log| log.recent.iter().cloned().collect()).unwrap_or_default()
+}
+
+/// The time of day in UTC, `14:03:07`
+fn clock() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    format!("{:02}:{:02}:{:02}", seconds / 3600 % 24, seconds / 60 % 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_kept_with_time() {
+        info("parsed test-log-line.txt");
+        detail(|| "never built unless verbose".to_string());
+        let recent = recent();
+        let line = recent.iter().find(|line| line.ends_with(" parsed test-log-line.txt")).unwrap();
+        assert_eq!(line.len(), "14:03:07 parsed test-log-line.txt".len());
+        assert!(!recent.iter().any(|line| line.contains("never built")));
+    }
+}
@@ -1,19 +1,72 @@
+mod anthology;
+mod bench;
+mod builtin;
+mod cache;
+mod clipboard;
+mod collab;
+mod collage;
+mod config;
+mod corpus;
+mod decks;
+mod embed;
+mod export;
+mod import;
+mod journal;
+mod logging;
+mod palette;
 mod parser;
+mod pos;
+mod prompts;
+mod raster;
+mod reading_path;
 mod scatters;
+mod server;
+mod spelling;
+mod stats;
+mod stop_words;
+mod suggest;
 mod styling;
+mod templates;
+mod timeline;
+mod transform;
+mod translate;
 mod ui;
 mod word_bank;
+mod wordplay;
 
-use clap::Parser as ClapParser;
+use clap::{Parser as ClapParser, Subcommand};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
-    execute,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind},
+    cursor, execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::fs;
+use std::borrow::Borrow;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// How often the screensaver rerolls once it is running
+const SCREENSAVER_REROLL_INTERVAL: Duration = Duration::from_secs(20);
+/// Time between ticks of the event loop, which drive animation frames and every other timed update
+const TICK_RATE: Duration = Duration::from_millis(60);
+/// How long a reroll waits for its scatter before leaving it to come in on a later tick, so a quick one
+/// goes straight up without the "generating…" indicator flashing
+const REROLL_WAIT: Duration = Duration::from_millis(30);
+/// Refresh rate for the elapsed time shown in the stats panel
+const STATS_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the typewriter cursor stays on, then off
+const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(530);
+
+/// Canvas and word count range for `--fortune` snippets
+const FORTUNE_WIDTH: u16 = 60;
+const FORTUNE_HEIGHT: u16 = 6;
+const FORTUNE_MIN_WORDS: usize = 3;
+const FORTUNE_MAX_WORDS: usize = 6;
 
 #[derive(ClapParser, Debug)]
 #[command(name = "text-scatters")]
@@ -30,57 +83,648 @@ struct Args {
         default_value = "monochrome"
     )]
     theme: String,
+
+    #[arg(
+        long = "color",
+        value_name = "WHEN",
+        value_parser = styling::ColorChoice::parse,
+        default_value = "auto",
+        help = "Use colors always, never, or auto (unless NO_COLOR is set); without them states are shown in bold and reverse"
+    )]
+    color: styling::ColorChoice,
+
+    #[arg(
+        long = "idle-minutes",
+        value_name = "MINUTES",
+        help = "Start the screensaver after this many minutes without input"
+    )]
+    idle_minutes: Option<u64>,
+
+    #[arg(
+        long = "embed",
+        help = "Read generate/seed/density commands on stdin and write layouts to stdout (for editor plugins)"
+    )]
+    embed: bool,
+
+    #[arg(
+        long = "fortune",
+        help = "Print a tiny scatter to stdout and exit (for shell startup files)"
+    )]
+    fortune: bool,
+
+    #[arg(
+        long = "verbose",
+        conflicts_with = "quiet",
+        help = "Log every parsing and generation decision to scatters.log in the config directory (:log shows recent lines)"
+    )]
+    verbose: bool,
+
+    #[arg(long = "quiet", help = "Print no progress or warnings while loading (:log still shows them)")]
+    quiet: bool,
+
+    #[arg(long = "no-cache", help = "Re-parse every file instead of using the cached word bank")]
+    no_cache: bool,
+
+    #[arg(
+        long = "use-pandoc",
+        help = "Convert rst and textile files to text with pandoc (must be installed)"
+    )]
+    use_pandoc: bool,
+
+    #[arg(long = "skip-hidden", help = "Skip hidden (dot) files and directories when scanning")]
+    skip_hidden: bool,
+
+    #[arg(
+        long = "keep-duplicates",
+        help = "Read every file in a directory, even one that repeats a text already read in another format"
+    )]
+    keep_duplicates: bool,
+
+    #[arg(long = "gitignore", help = "Skip files matched by .gitignore rules when scanning")]
+    gitignore: bool,
+
+    #[arg(
+        long = "follow-symlinks",
+        help = "Also scan symlinked directories (symlink loops are detected and skipped)"
+    )]
+    follow_symlinks: bool,
+
+    #[arg(long = "recursive", help = "Scan subdirectories at every depth, such as a folder per author")]
+    recursive: bool,
+
+    #[arg(
+        long = "include",
+        value_name = "GLOB",
+        help = "Only read files matching GLOB, relative to the directory (repeatable, e.g. '*.txt' or 'melville/**')"
+    )]
+    include: Vec<String>,
+
+    #[arg(
+        long = "exclude",
+        value_name = "GLOB",
+        help = "Leave out files and directories matching GLOB, relative to the directory (repeatable, e.g. drafts)"
+    )]
+    exclude: Vec<String>,
+
+    #[arg(
+        long = "margins",
+        value_name = "T,R,B,L",
+        value_parser = scatters::Margins::parse,
+        help = "Keep words this many cells from the canvas edges (one value, vertical,horizontal, or all four)"
+    )]
+    margins: Option<scatters::Margins>,
+
+    #[arg(
+        long = "avoid-corners",
+        value_name = "WxH",
+        value_parser = embed::parse_size,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "16x2",
+        help = "Never place words in the corners, leaving room for a title or signature (default 16x2)"
+    )]
+    avoid_corners: Option<(u16, u16)>,
+
+    #[arg(
+        long = "words-per-row",
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..),
+        help = "Put at most N words on any one row, for an airier vertical rhythm at high densities"
+    )]
+    words_per_row: Option<u16>,
+
+    #[arg(
+        long = "builtin",
+        value_name = "NAME",
+        value_parser = clap::builder::PossibleValuesParser::new(builtin::names()),
+        conflicts_with_all = ["path", "voice_a"],
+        help = "Scatter a corpus that ships with the tool instead of your own files"
+    )]
+    builtin: Option<String>,
+
+    #[arg(
+        long = "deck",
+        value_name = "NAME",
+        conflicts_with_all = ["path", "voice_a", "builtin"],
+        help = "Scatter only the words saved to a deck with the f key"
+    )]
+    deck: Option<String>,
+
+    #[arg(
+        long = "voice-a",
+        value_name = "PATH",
+        requires = "voice_b",
+        conflicts_with = "path",
+        help = "First corpus of a two-voice dialogue (use with --voice-b instead of PATH)"
+    )]
+    voice_a: Option<PathBuf>,
+
+    #[arg(
+        long = "voice-b",
+        value_name = "PATH",
+        requires = "voice_a",
+        help = "Second corpus of a two-voice dialogue, shown in its own color"
+    )]
+    voice_b: Option<PathBuf>,
+
+    #[arg(
+        long = "time-range",
+        value_name = "RANGE",
+        value_parser = timeline::DateSpan::parse,
+        help = "Draw words from files dated in this range (e.g. 2019, 2019-03, 2019-03..2019-06), by dates in file names"
+    )]
+    time_range: Option<timeline::DateSpan>,
+
+    #[arg(
+        long = "time-sweep",
+        conflicts_with = "time_range",
+        help = "Move through the corpus by year (or month) on each reroll, by dates in file names"
+    )]
+    time_sweep: bool,
+
+    #[arg(
+        long = "recency",
+        value_name = "CURVE",
+        value_parser = timeline::Recency::parse,
+        conflicts_with_all = ["time_range", "time_sweep"],
+        help = "Draw words from newer files more readily, by dates in file names: a half-life (90d, 6w, 3m, 1y) or linear"
+    )]
+    recency: Option<timeline::Recency>,
+
+    #[arg(
+        long = "slice",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100),
+        help = "Start with a random PERCENT of the bank active (the a key draws a new slice; default 10)"
+    )]
+    slice: Option<u8>,
+
+    #[arg(
+        long = "zones",
+        value_name = "LAYOUT[:DENSITIES]",
+        value_parser = parse_zones,
+        help = "Split the canvas into rows, columns or quadrants with their own densities, e.g. rows:4,0.5"
+    )]
+    zones: Option<(scatters::ZoneLayout, Vec<f32>)>,
+
+    #[arg(
+        long = "topology",
+        value_name = "LAYOUT",
+        value_parser = scatters::Topology::parse,
+        help = "Arrange words by part of speech: centre (nouns in the middle, verbs and adjectives at the edges) or rows"
+    )]
+    topology: Option<scatters::Topology>,
+
+    #[arg(
+        long = "cell-aspect",
+        value_name = "RATIO",
+        value_parser = parse_cell_aspect,
+        help = "Height of a terminal cell over its width, so the grid, clumps and centre topology look even on screen; 1 treats cells as square (default 2)"
+    )]
+    cell_aspect: Option<f32>,
+
+    #[arg(
+        long = "density",
+        value_name = "D|auto",
+        value_parser = scatters::StartDensity::parse,
+        help = "Density to start at, 0.1-6.0, or auto to suit the size of the corpus and the length of its words (default 1)"
+    )]
+    density: Option<scatters::StartDensity>,
+
+    #[arg(
+        long = "chaos",
+        value_name = "0-1",
+        value_parser = parse_chaos,
+        help = "How unruly scatters are: 0 for common words on an even grid, 1 for rare words in clumps (default 0.5)"
+    )]
+    chaos: Option<f32>,
+
+    #[arg(
+        long = "sampling",
+        value_name = "MODE",
+        value_parser = scatters::Sampling::parse,
+        help = "How often words are drawn by their count in the corpus: chaos (default), even, frequent or rare"
+    )]
+    sampling: Option<scatters::Sampling>,
+
+    #[arg(
+        long = "replay",
+        value_names = ["SEED", "WxH"],
+        num_args = 2,
+        help = "Start with the scatter made earlier from SEED at canvas size WxH (same corpus and settings)"
+    )]
+    replay: Option<Vec<String>>,
+
+    #[arg(
+        long = "seed",
+        value_name = "SEED",
+        conflicts_with = "replay",
+        help = "Seed the first scatter, so the same corpus, seed and canvas size give the same layout (the sidebar shows each scatter's seed)"
+    )]
+    seed: Option<u64>,
+
+    #[arg(
+        long = "width",
+        value_name = "CELLS",
+        value_parser = clap::value_parser!(u16).range(1..=scatters::MAX_CANVAS as i64),
+        help = "Canvas width, whatever the terminal's size; shift+arrows pan across a canvas wider than the screen"
+    )]
+    width: Option<u16>,
+
+    #[arg(
+        long = "height",
+        value_name = "CELLS",
+        value_parser = clap::value_parser!(u16).range(1..=scatters::MAX_CANVAS as i64),
+        help = "Canvas height, whatever the terminal's size; shift+arrows pan across a canvas taller than the screen"
+    )]
+    height: Option<u16>,
+
+    #[arg(long = "frameless", help = "Start with the canvas border hidden (the B key toggles it)")]
+    frameless: bool,
+
+    #[arg(long = "mouse", help = "Capture the mouse all session, so the density bar can be clicked and dragged")]
+    mouse: bool,
+
+    #[arg(long = "font-styles", help = "Start with random bold, italic and dim words (the o key toggles it)")]
+    font_styles: bool,
+
+    #[arg(
+        long = "timer",
+        value_name = "MINUTES",
+        value_parser = clap::value_parser!(u32).range(1..),
+        num_args = 0..=1,
+        require_equals = true,
+        help = "Show a writing timer in the sidebar, counting up, or down from MINUTES with a flash at zero"
+    )]
+    timer: Option<Option<u32>>,
+
+    #[arg(
+        long = "host",
+        value_name = "ADDRESS",
+        value_parser = collab::Address::parse,
+        conflicts_with = "join",
+        help = "Experimental: wait for a partner to --join at ADDRESS (host:port or a socket path) and share one canvas"
+    )]
+    host: Option<collab::Address>,
+
+    #[arg(
+        long = "join",
+        value_name = "ADDRESS",
+        value_parser = collab::Address::parse,
+        help = "Experimental: join a partner's canvas, shared with --host at ADDRESS"
+    )]
+    join: Option<collab::Address>,
+
+    #[arg(
+        long = "fold",
+        value_name = "FORM",
+        value_parser = word_bank::FoldDisplay::parse,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "base",
+        help = "Merge possessives and plurals into one word (poet's, poets -> poet), shown as the base form or =surface for the most common form"
+    )]
+    fold: Option<word_bank::FoldDisplay>,
+
+    #[arg(
+        long = "translate",
+        value_name = "DICT",
+        help = "Show words with their translations from a tab-separated dictionary: a file, or NAME for dictionaries/NAME.tsv in the config directory"
+    )]
+    translate: Option<String>,
+
+    #[arg(
+        long = "translation",
+        value_name = "DISPLAY",
+        value_parser = translate::TranslationDisplay::parse,
+        requires = "translate",
+        default_value = "alongside",
+        help = "How translated words appear: alongside (harbor/port) or replace (port)"
+    )]
+    translation: translate::TranslationDisplay,
+
+    #[arg(
+        long = "lipogram",
+        value_name = "LETTERS",
+        value_parser = palette::parse_lipogram,
+        help = "Leave out every word containing one of LETTERS (--lipogram e scatters no word with an e)"
+    )]
+    lipogram: Option<String>,
+
+    #[arg(
+        long = "syllables",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Fill each scatter with words adding up to N syllables instead of following the density"
+    )]
+    syllables: Option<u32>,
+
+    #[arg(
+        long = "extra-words",
+        value_name = "FILE",
+        help = "Add the words and phrases in FILE, one per line, to the bank under a source of their own"
+    )]
+    extra_words: Option<PathBuf>,
+
+    #[arg(
+        long = "stop-words",
+        value_name = "FILE",
+        help = "Leave out the words in FILE, one per line, as well as the built-in English stop words"
+    )]
+    stop_words: Option<PathBuf>,
+
+    #[arg(
+        long = "stop-words-mode",
+        value_name = "MODE",
+        value_parser = stop_words::ListMode::parse,
+        help = "How the --stop-words list is used: extend (with the built-in English list) or replace"
+    )]
+    stop_words_mode: Option<stop_words::ListMode>,
+
+    #[arg(
+        long = "keep-numerals",
+        help = "Keep Roman numerals (xvii, iii) and words like \"chapter\" that usually come from chapter headings"
+    )]
+    keep_numerals: bool,
+
+    #[arg(
+        long = "watermark",
+        help = "Lay a whole sentence from the corpus faintly across the canvas behind each scatter, and in snapshots"
+    )]
+    watermark: bool,
+
+    #[arg(
+        long = "min-sources",
+        value_name = "K",
+        help = "Draw each scatter from at least K different source files, when the corpus has that many"
+    )]
+    min_sources: Option<usize>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-/// Get the config directory for text-scatters
-fn get_config_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let config_dir = dirs::config_dir()
-        .ok_or("Could not determine config directory")?
-        .join("text-scatters");
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Serve generate/export/bank-query requests as JSON lines over a unix socket
+    Serve {
+        #[arg(long = "socket", value_name = "PATH", help = "Unix socket path to listen on")]
+        socket: PathBuf,
+
+        #[arg(help = "File or directory containing text files to parse (optional - uses last path if omitted)")]
+        path: Option<PathBuf>,
+    },
+
+    /// Tile the most recent snapshots from the export directory into one printable sheet
+    Collage {
+        #[arg(long = "count", value_name = "N", default_value_t = 6, help = "How many of the latest snapshots to include")]
+        count: usize,
 
-    // Create the config directory if it doesn't exist
-    fs::create_dir_all(&config_dir)?;
+        #[arg(
+            long = "columns",
+            value_name = "N",
+            default_value_t = 3,
+            value_parser = clap::value_parser!(u16).range(1..),
+            help = "Snapshots per row of the sheet"
+        )]
+        columns: u16,
 
-    Ok(config_dir)
+        #[arg(
+            long = "format",
+            value_name = "FORMAT",
+            default_value = "text",
+            value_parser = collage::CollageFormat::parse,
+            help = "Sheet format: text or svg"
+        )]
+        format: collage::CollageFormat,
+
+        #[arg(long = "output", short = 'o', value_name = "PATH", help = "Write the sheet to a file instead of stdout")]
+        output: Option<PathBuf>,
+    },
+
+    /// Time parsing per file format and placement at several densities and canvas sizes on a corpus
+    Bench {
+        #[arg(
+            long = "rounds",
+            value_name = "N",
+            default_value_t = 20,
+            value_parser = clap::value_parser!(u32).range(1..),
+            help = "Scatters timed at each density and size"
+        )]
+        rounds: u32,
+
+        #[arg(help = "File or directory containing text files to parse (optional - uses last path if omitted)")]
+        path: Option<PathBuf>,
+    },
 }
 
-/// Save the last used path to config file
-fn save_last_path(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    let config_dir = get_config_dir()?;
-    let config_file = config_dir.join("last_path.txt");
-    fs::write(config_file, path.to_string_lossy().as_bytes())?;
-    Ok(())
+/// A 3-6 word scatter sized for a shell greeting, with surrounding blank lines trimmed
+fn fortune(generator: &scatters::ScattersGenerator) -> String {
+    let mut rng = rand::thread_rng();
+    let count = rng.gen_range(FORTUNE_MIN_WORDS..=FORTUNE_MAX_WORDS);
+    let scatter = generator.generate_count_with_rng(&mut rng, FORTUNE_WIDTH, FORTUNE_HEIGHT, count);
+    let text = export::render_text(&scatter.words, FORTUNE_WIDTH, FORTUNE_HEIGHT);
+    format!("{}\n", text.trim_start_matches('\n').trim_end())
+}
+
+/// A loaded corpus: the bank behind every word (both voices merged in dialogue mode) and its generator
+struct Corpus {
+    word_bank: word_bank::WordBank,
+    generator: scatters::ScattersGenerator,
+    display_path: PathBuf, // Shown in the Path box; both voice paths in dialogue mode
+    source_roots: Vec<PathBuf>, // Paths the word sources are named relative to; both voices' in dialogue mode
+    voice_names: Option<[String; 2]>,
+    stop_words: stop_words::StopWords,
+    demo: bool, // The built-in demo poems, used on a first run without a path
+    bundled: bool, // A built-in corpus or a deck rather than a path
+}
+
+/// Load the single corpus at `path`, the two dialogue voices, or an already built bank with its display
+/// name (a built-in corpus or a deck), plus any extra words, leaving out `stop_words`, exiting on failure
+fn load_corpus(
+    path: Option<PathBuf>,
+    voices: Option<(PathBuf, PathBuf)>,
+    bundled: Option<(String, word_bank::WordBank)>,
+    extra_words: Option<&Path>,
+    stop_words: stop_words::StopWords,
+    options: &corpus::LoadOptions,
+    rules: scatters::PlacementRules,
+) -> Corpus {
+    let mut corpus = match (voices, bundled) {
+        (Some((voice_a, voice_b)), _) => {
+            let voice_a = resolve_input_path(Some(voice_a), options.quiet);
+            let voice_b = resolve_input_path(Some(voice_b), options.quiet);
+            let mut word_bank = exit_on_error(corpus::load_word_bank(&voice_a, options));
+            let second_bank = exit_on_error(corpus::load_word_bank(&voice_b, options));
+            let generator = scatters::ScattersGenerator::with_dialogue(word_bank.get_words(), second_bank.get_words());
+            word_bank.merge(second_bank);
+
+            Corpus {
+                word_bank,
+                generator,
+                display_path: PathBuf::from(format!("{} & {}", voice_a.display(), voice_b.display())),
+                voice_names: Some([voice_name(&voice_a), voice_name(&voice_b)]),
+                source_roots: vec![voice_a, voice_b],
+                stop_words,
+                demo: false,
+                bundled: false,
+            }
+        }
+        (None, Some((name, mut word_bank))) => {
+            corpus::adapt_word_bank(&mut word_bank, options);
+            let generator = scatters::ScattersGenerator::new(word_bank.get_words());
+            Corpus {
+                word_bank,
+                generator,
+                display_path: PathBuf::from(name),
+                voice_names: None,
+                source_roots: Vec::new(),
+                stop_words,
+                demo: false,
+                bundled: true,
+            }
+        }
+        // Nothing to scatter yet on a first run, so show what the tool does with some poems
+        (None, None) if path.is_none() && !config::has_last_path() => {
+            if !options.quiet {
+                println!("No path given, starting with the built-in demo poems");
+            }
+            let mut word_bank = builtin::word_bank("poetry").expect("the demo corpus is built in");
+            corpus::adapt_word_bank(&mut word_bank, options);
+            let generator = scatters::ScattersGenerator::new(word_bank.get_words());
+
+            Corpus {
+                word_bank,
+                generator,
+                display_path: PathBuf::from("built-in demo (run text-scatters PATH for your own files)"),
+                voice_names: None,
+                source_roots: Vec::new(),
+                stop_words,
+                demo: true,
+                bundled: true,
+            }
+        }
+        (None, None) => {
+            let input_path = resolve_input_path(path, options.quiet);
+            let word_bank = exit_on_error(corpus::load_word_bank(&input_path, options));
+            let generator = scatters::ScattersGenerator::new(word_bank.get_words());
+
+            Corpus {
+                word_bank,
+                generator,
+                source_roots: vec![input_path.clone()],
+                display_path: input_path,
+                voice_names: None,
+                stop_words,
+                demo: false,
+                bundled: false,
+            }
+        }
+    };
+
+    if let Some(path) = extra_words {
+        join_bank(&mut corpus.word_bank, &mut corpus.generator, exit_on_error(corpus::load_extra_words(path)));
+    }
+
+    corpus.generator.set_placement_rules(rules);
+    corpus.generator.set_stop_words(corpus.stop_words.words());
+    corpus.generator.set_frequencies(corpus.word_bank.frequencies());
+    corpus
+}
+
+/// Display name for a dialogue voice: its file or directory name
+fn voice_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Apply the placement and sampling flags every mode shares to a freshly loaded generator
+fn configure_generator(generator: &mut scatters::ScattersGenerator, args: &Args, syllable_budget: Option<usize>) {
+    if let Some(min_sources) = args.min_sources {
+        generator.set_min_sources(min_sources);
+    }
+    if let Some(topology) = args.topology {
+        generator.set_topology(topology);
+    }
+    generator.set_keep_numerals(args.keep_numerals);
+    generator.set_lipogram(args.lipogram.as_deref().unwrap_or_default());
+    generator.set_syllable_budget(syllable_budget);
+    if let Some(cell_aspect) = args.cell_aspect {
+        generator.set_cell_aspect(cell_aspect);
+    }
+    if let Some(chaos) = args.chaos {
+        generator.set_chaos(chaos);
+    }
+    if let Some(sampling) = args.sampling {
+        generator.set_sampling(sampling);
+    }
 }
 
-/// Load the last used path from config file
-fn load_last_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let config_dir = get_config_dir()?;
-    let config_file = config_dir.join("last_path.txt");
+fn parse_chaos(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(chaos) if (0.0..=1.0).contains(&chaos) => Ok(chaos),
+        _ => Err(format!("chaos must be a number from 0 to 1, got '{}'", value)),
+    }
+}
 
-    if !config_file.exists() {
-        return Err("No previous path saved. Please provide a file or directory path.".into());
+fn parse_cell_aspect(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(ratio) if (0.5..=4.0).contains(&ratio) => Ok(ratio),
+        _ => Err(format!("cell aspect must be a number from 0.5 to 4, got '{}'", value)),
     }
+}
+
+/// Parse `LAYOUT` or `LAYOUT:D1,D2,...` with one density (0.1-6.0) per zone
+fn parse_zones(value: &str) -> Result<(scatters::ZoneLayout, Vec<f32>), String> {
+    let (layout, densities) = match value.split_once(':') {
+        Some((layout, densities)) => (layout, Some(densities)),
+        None => (value, None),
+    };
+    let layout = scatters::ZoneLayout::parse(layout)?;
 
-    let path_str = fs::read_to_string(config_file)?;
-    let path = PathBuf::from(path_str.trim());
+    let densities = match densities {
+        Some(densities) => densities
+            .split(',')
+            .map(|density| {
+                density
+                    .trim()
+                    .parse::<f32>()
+                    .map(|density| density.clamp(0.1, 6.0))
+                    .map_err(|_| format!("invalid density '{}'", density.trim()))
+            })
+            .collect::<Result<Vec<f32>, String>>()?,
+        None => vec![1.0; layout.zone_count()],
+    };
 
-    if !path.exists() {
-        return Err(format!("Previously saved path '{}' no longer exists", path.display()).into());
+    if densities.len() != layout.zone_count() {
+        return Err(format!("expected {} densities for this layout, got {}", layout.zone_count(), densities.len()));
     }
 
-    Ok(path)
+    Ok((layout, densities))
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+/// Unwrap a startup result, printing the error and exiting if it failed
+fn exit_on_error<T>(result: Result<T, Box<dyn std::error::Error>>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
 
-    // Determine which path to use
-    let input_path = match args.path {
+/// Use the given path, or fall back to the last saved one, exiting if neither is usable
+fn resolve_input_path(path: Option<PathBuf>, quiet: bool) -> PathBuf {
+    let input_path = match path {
         Some(p) => p,
         None => {
-            match load_last_path() {
+            match config::load_last_path() {
                 Ok(path) => {
-                    println!("Using last path: {}", path.display());
+                    if !quiet {
+                        println!("Using last path: {}", path.display());
+                    }
                     path
                 }
                 Err(e) => {
@@ -99,161 +743,352 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
-    let mut word_bank = word_bank::WordBank::new();
-    let mut file_count = 0;
-    let display_path: PathBuf;
-
-    // Handle both single file and directory
-    if input_path.is_file() {
-        // Process single file
-        let extension = input_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_lowercase());
-
-        match extension.as_deref() {
-            Some("txt") | Some("md") | Some("markdown") | Some("epub") => {
-                println!("Parsing file: {}", input_path.display());
-                match parser::parse_file(&input_path) {
-                    Ok(words) => {
-                        let file_name = input_path
-                            .file_name()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .to_string();
-                        word_bank.add_words(words, file_name);
-                        file_count += 1;
-                    }
-                    Err(e) => {
-                        eprintln!("Error: Failed to parse {}: {}", input_path.display(), e);
-                        std::process::exit(1);
-                    }
-                }
+    input_path
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let log_level = match (args.quiet, args.verbose) {
+        (true, _) => logging::Level::Quiet,
+        (_, true) => logging::Level::Verbose,
+        _ => logging::Level::Normal,
+    };
+    if let Some(path) = exit_on_error(logging::init(log_level)) {
+        if !(args.embed || args.fortune) {
+            println!("Logging to {}", path.display());
+        }
+    }
+
+    let load_options = corpus::LoadOptions {
+        quiet: args.embed || args.fortune || args.quiet,
+        quiet_warnings: args.quiet,
+        use_cache: !args.no_cache,
+        use_pandoc: args.use_pandoc,
+        skip_hidden: args.skip_hidden,
+        keep_duplicates: args.keep_duplicates,
+        respect_gitignore: args.gitignore,
+        follow_symlinks: args.follow_symlinks,
+        recursive: args.recursive,
+        include: args.include.clone(),
+        exclude: args.exclude.clone(),
+        fold: args.fold,
+        translation: args.translate.as_deref().map(|name| translate::Translation {
+            dictionary: exit_on_error(translate::load_dictionary(name)),
+            display: args.translation,
+        }),
+    };
+
+    let syllable_budget = args.syllables.map(|budget| budget as usize);
+
+    let placement_rules = scatters::PlacementRules {
+        margins: args.margins.unwrap_or_default(),
+        corner_badge: args.avoid_corners,
+        words_per_row: args.words_per_row,
+    };
+
+    let settings = exit_on_error(config::load_settings());
+    let mut stop_words = stop_words::StopWords::load();
+    if let Some(path) = args.stop_words.as_ref().or(settings.stop_words.as_ref()) {
+        let mode = args.stop_words_mode.unwrap_or(settings.stop_words_mode);
+        exit_on_error(stop_words.use_list(path, mode).map_err(Into::into));
+    }
+
+    if let Some(Command::Collage { count, columns, format, output }) = &args.command {
+        let snapshots = exit_on_error(collage::recent_snapshots(&settings.export_dir, *count));
+        let sheets: Vec<Vec<String>> = snapshots.iter().map(|path| exit_on_error(collage::snapshot_lines(path))).collect();
+        let sheet = collage::render(*format, &sheets, *columns as usize);
+        match output {
+            Some(path) => {
+                exit_on_error(std::fs::write(path, sheet).map_err(Into::into));
+                println!("Tiled {} snapshots into {}", sheets.len(), path.display());
             }
-            _ => {
-                eprintln!("Error: '{}' is not a supported file type (txt, md, markdown, epub)", input_path.display());
+            None => print!("{}", sheet),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Bench { rounds, path }) = &args.command {
+        let input_path = resolve_input_path(path.clone(), false);
+        print!("{}", exit_on_error(bench::run(&input_path, &load_options, *rounds)));
+        return Ok(());
+    }
+
+    if let Some(Command::Serve { socket, path }) = &args.command {
+        let mut corpus =
+            load_corpus(path.clone(), None, None, args.extra_words.as_deref(), stop_words, &load_options, placement_rules);
+        configure_generator(&mut corpus.generator, &args, syllable_budget);
+        let word_count = corpus.generator.usable_count();
+        if let Err(e) = server::serve(socket, corpus.generator, word_count) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let voices = args.voice_a.clone().zip(args.voice_b.clone());
+    let bundled = match (&args.builtin, &args.deck) {
+        (Some(name), _) => Some((
+            format!("built-in {}", name),
+            builtin::word_bank(name).expect("built-in corpus names are checked by clap"),
+        )),
+        (None, Some(name)) => Some((format!("deck {}", name), exit_on_error(decks::word_bank(name)))),
+        (None, None) => None,
+    };
+    let mut corpus =
+        load_corpus(args.path.clone(), voices, bundled, args.extra_words.as_deref(), stop_words, &load_options, placement_rules);
+    configure_generator(&mut corpus.generator, &args, syllable_budget);
+
+    if let Some(range) = args.time_range {
+        let weight = timeline::weight_within(range);
+        if !corpus.generator.word_pool().any(|(word, source)| weight(word, source) > 0.0) {
+            eprintln!("Error: No files in the corpus are dated within {}", range);
+            std::process::exit(1);
+        }
+        corpus.generator.set_weights(weight);
+    }
+
+    if let Some(recency) = args.recency {
+        let weight = timeline::weight_by_recency(recency, corpus.generator.word_pool().map(|(_, source)| source.as_str()));
+        match weight {
+            Some(weight) => corpus.generator.set_weights(weight),
+            None => {
+                eprintln!("Error: --recency needs files with dates in their names (e.g. 2019-03-04.txt)");
                 std::process::exit(1);
             }
         }
-        display_path = input_path.clone();
-    } else if input_path.is_dir() {
-        // Process directory
-        println!("Scanning directory: {}", input_path.display());
-
-        for entry in fs::read_dir(&input_path)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_file() {
-                let extension = path
-                    .extension()
-                    .and_then(|s| s.to_str())
-                    .map(|s| s.to_lowercase());
-
-                match extension.as_deref() {
-                    Some("txt") | Some("md") | Some("markdown") | Some("epub") => {
-                        println!("Parsing: {}", path.display());
-                        match parser::parse_file(&path) {
-                            Ok(words) => {
-                                // Compute relative path from base directory
-                                let relative_path = path
-                                    .strip_prefix(&input_path)
-                                    .unwrap_or(&path)
-                                    .to_string_lossy()
-                                    .replace('\\', "/"); // Normalize path separators
-                                word_bank.add_words(words, relative_path);
-                                file_count += 1;
-                            }
-                            Err(e) => {
-                                eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
-                            }
-                        }
-                    }
-                    _ => {}
-                }
-            }
+    }
+
+    let time_sweep = if args.time_sweep {
+        let sweep = timeline::TimeSweep::new(corpus.generator.word_pool().map(|(_, source)| source.as_str()));
+        if sweep.is_none() {
+            eprintln!("Error: --time-sweep needs files with dates in their names (e.g. 2019-03-04.txt)");
+            std::process::exit(1);
         }
-        display_path = input_path.clone();
+        sweep
     } else {
-        eprintln!("Error: '{}' is neither a file nor a directory", input_path.display());
-        std::process::exit(1);
-    }
+        None
+    };
 
-    println!("Parsed {} files", file_count);
-    println!("Collected {} unique words", word_bank.word_count());
+    if args.embed {
+        embed::run(&corpus.generator)?;
+        return Ok(());
+    }
 
-    if word_bank.word_count() == 0 {
-        eprintln!("Error: No words found");
-        std::process::exit(1);
+    if args.fortune {
+        print!("{}", fortune(&corpus.generator));
+        return Ok(());
     }
 
-    // Save the successfully used path for next time
-    if let Err(e) = save_last_path(&display_path) {
-        eprintln!("Warning: Could not save path for next time: {}", e);
+    let Corpus { word_bank, mut generator, display_path, source_roots, voice_names, stop_words, demo, bundled } = corpus;
+
+    // Save the successfully used path for next time (a dialogue, built-in corpus or deck isn't a path to return to)
+    let corpus_path = (voice_names.is_none() && !bundled).then(|| display_path.clone());
+    if let Some(path) = &corpus_path {
+        if let Err(e) = config::save_last_path(path) {
+            eprintln!("Warning: Could not save path for next time: {}", e);
+        }
     }
 
-    println!("Starting TUI...");
-    std::thread::sleep(std::time::Duration::from_secs(1));
+    // Initialize styling based on theme first (needed for canvas calculation)
+    // Resolved before entering raw mode so theme errors print to a normal terminal
+    let styling = exit_on_error(styling::AppStyling::from_theme(&args.theme).map_err(Into::into));
+    let color = args.color.use_color(std::env::var_os("NO_COLOR").as_deref());
+    let styling = if color { styling } else { styling.without_color() };
+    let replay = args
+        .replay
+        .as_ref()
+        .map(|values| exit_on_error(palette::parse_replay(&values[0], &values[1]).map_err(Into::into)));
+
+    let collab = match (&args.host, &args.join) {
+        (Some(address), _) => Some(exit_on_error(collab::Session::host(address))),
+        (None, Some(address)) => Some(exit_on_error(collab::Session::join(address))),
+        (None, None) => None,
+    };
+
+    if !args.quiet {
+        println!("Starting TUI...");
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
 
+    install_panic_hook();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if args.mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let words = word_bank.get_words();
-    let word_count = words.len();
-    let generator = scatters::ScattersGenerator::new(words);
+    let word_count = generator.usable_count();
 
     let size = terminal.size()?;
 
-    // Initialize styling based on theme first (needed for canvas calculation)
-    let styling = match styling::AppStyling::from_theme(&args.theme) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        }
-    };
-
     // Create temporary app to calculate sidebar width
-    let temp_app = ui::App::new(Vec::new(), word_count, styling.clone(), display_path.clone());
-    let sidebar_width = ui::calculate_sidebar_width_for_app(&temp_app);
+    let mut temp_app = ui::App::new(Vec::new(), word_count, styling.clone(), display_path.clone());
+    temp_app.show_canvas_border = !args.frameless;
+    temp_app.canvas_override = (args.width, args.height);
 
     // Calculate actual canvas area based on dynamic sidebar
-    let canvas_width = size.width.saturating_sub(sidebar_width).saturating_sub(2);
-    let canvas_height = size.height.saturating_sub(2);
-    let scattered_words = generator.generate_with_density(canvas_width, canvas_height, 1.0);
+    let (canvas_width, canvas_height) = ui::canvas_size(&temp_app, size.width, size.height);
+    let time_window = match &time_sweep {
+        Some(sweep) => {
+            let (label, span) = sweep.current();
+            generator.set_weights(timeline::weight_within(span));
+            Some(label.to_string())
+        }
+        None => args.time_range.map(|range| range.to_string()).or(args.recency.map(|recency| recency.to_string())),
+    };
+    if let Some(percent) = args.slice {
+        generator.resample_active(&mut rand::thread_rng(), percent as f64 / 100.0);
+    }
+    let mut app = ui::App::new(Vec::new(), word_count, styling, display_path);
+    app.source_roots = source_roots;
+    app.show_canvas_border = !args.frameless;
+    app.canvas_override = (args.width, args.height);
+    app.color = color;
+    app.theme = args.theme.clone();
+    app.mouse = args.mouse;
+    app.show_help = demo;
+    app.selection_cue = settings.selection_cue;
+    app.transition = settings.transition;
+    app.anthology = settings.anthology.clone();
+    app.font_styles = args.font_styles;
+    app.prompts = prompts::load();
+    app.lipogram = args.lipogram;
+    app.syllable_budget = syllable_budget;
+    app.timer = args.timer.map(|minutes| minutes.map_or_else(stats::WritingTimer::count_up, stats::WritingTimer::countdown));
+    if args.watermark {
+        app.watermark_sentences = corpus::sample_sentences(generator.word_pool().map(|(_, source)| source.as_str()), &app.source_roots);
+    }
+    match args.density {
+        Some(scatters::StartDensity::Fixed(density)) => app.density = density,
+        Some(scatters::StartDensity::Auto) => {
+            app.density = generator.auto_density();
+            logging::info(format!("auto density {:.2}", app.density));
+        }
+        None => {}
+    }
+    if let Some((layout, densities)) = args.zones {
+        app.set_zone_layout(layout, Some(densities));
+    }
 
-    let mut app = ui::App::new(scattered_words, word_count, styling, display_path);
+    match replay {
+        Some((seed, (width, height))) => generate_seeded(&mut app, &generator, seed, width, height),
+        None => generate_seeded(&mut app, &generator, args.seed.unwrap_or_else(new_seed), canvas_width, canvas_height),
+    }
+    app.word_locations = word_bank.locations();
+    app.word_contexts = word_bank.contexts();
+    app.source_titles = word_bank.titles().clone();
+    app.favorite_seeds = config::load_favorite_seeds();
+    app.set_word_frequencies(word_bank.frequencies());
+    app.voice_names = voice_names;
+    app.stop_words = stop_words;
+    app.set_sources(generator.word_pool().map(|(_, source)| source.as_str()));
+    app.time_window = time_window;
+    app.time_sweep = time_sweep;
+    if let Some(percent) = args.slice {
+        app.slice_percent = percent;
+        app.active_word_count = Some(generator.active_count());
+    }
 
-    let res = run_app(&mut terminal, &mut app, &generator);
+    let idle_timeout = args
+        .idle_minutes
+        .filter(|&minutes| minutes > 0)
+        .map(|minutes| Duration::from_secs(minutes * 60));
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen
-    )?;
-    terminal.show_cursor()?;
+    let library = Library { word_bank, path: corpus_path, options: load_options, loading: None };
+    let res = run_app(&mut terminal, &mut app, &mut generator, &settings, idle_timeout, collab, library);
+
+    restore_terminal()?;
 
     if let Err(err) = res {
         eprintln!("Error: {:?}", err);
     }
 
+    if let Err(e) = config::save_session_stats(&app.stats) {
+        eprintln!("Warning: Could not save session stats: {}", e);
+    }
+
     Ok(())
 }
 
+/// Leave raw mode, mouse capture and the alternate screen, and show the cursor again
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen, cursor::Show)
+}
+
+/// Restore the terminal before a panic message is printed, so a crash doesn't leave the terminal unusable
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut ui::App,
-    generator: &scatters::ScattersGenerator,
+    generator: &mut scatters::ScattersGenerator,
+    settings: &config::Settings,
+    idle_timeout: Option<Duration>,
+    mut collab: Option<collab::Session>,
+    mut library: Library,
 ) -> io::Result<()> {
     // Draw initial UI
     terminal.draw(|f| ui::ui(f, app))?;
 
+    let mut timers = Timers::new();
+    let mut last_tick = Instant::now();
+    let mut pending_suggestions: Option<std::sync::mpsc::Receiver<suggest::Suggestions>> = None;
+
     loop {
-        // Read event (blocking)
+        // Timed updates happen on every tick, whether or not any input arrives
+        if last_tick.elapsed() >= TICK_RATE {
+            last_tick = Instant::now();
+            let mut changed = tick(terminal, app, generator, &mut timers, idle_timeout, settings.typewriter_sound)?;
+
+            // Suggested lines arrive whenever the command answers, unless the panel was closed meanwhile
+            if let Some(Ok(suggestions)) = pending_suggestions.as_ref().map(|receiver| receiver.try_recv()) {
+                if app.suggestions.is_some() {
+                    app.suggestions = Some(suggestions);
+                    changed = true;
+                }
+                pending_suggestions = None;
+            }
+
+            // Transformed words go in whenever the command answers
+            changed |= app.finish_transform();
+
+            // A corpus loaded with :open or :add comes in as soon as it's parsed
+            changed |= library.finish_loading(app, generator);
+
+            // A shared canvas trades changes with the partner once a tick
+            if let Some(session) = &mut collab {
+                match session.sync(app, generator) {
+                    Ok(synced) => changed |= synced,
+                    Err(message) => {
+                        app.notice = Some(format!("{}; carrying on alone", message));
+                        collab = None;
+                        changed = true;
+                    }
+                }
+            }
+
+            if changed {
+                terminal.draw(|f| ui::ui(f, app))?;
+            }
+        }
+
+        // Wait for input until the next tick is due
+        if !event::poll(TICK_RATE.saturating_sub(last_tick.elapsed()))? {
+            continue;
+        }
         let event = event::read()?;
+        app.note_changes();
 
         match event {
             Event::Key(key) => {
@@ -263,32 +1098,335 @@ fn run_app<B: ratatui::backend::Backend>(
                     continue;
                 }
 
+                timers.last_input = Instant::now();
+                app.notice = None;
+
+                // Any key wakes the screensaver without triggering its usual action
+                if app.screensaver_active {
+                    app.stop_screensaver();
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
                 // Handle Ctrl+C
                 if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
                     return Ok(());
                 }
 
+                // Any key closes the help overlay
+                if app.show_help {
+                    app.show_help = false;
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The save prompt takes every key until the file is written or the prompt dismissed
+                if let Some(input) = &mut app.save_prompt {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter if !input.trim().is_empty() => {
+                            let path = save_path(settings, input.trim());
+                            match save_canvas_text(app, &path) {
+                                Ok(text) => {
+                                    app.save_prompt = None;
+                                    app.notice = Some(after_save(app, settings, &path, &text));
+                                }
+                                Err(e) => app.notice = Some(e.to_string()),
+                            }
+                        }
+                        KeyCode::Esc => app.save_prompt = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The command prompt takes every key until it's run or dismissed
+                if let Some(input) = &mut app.palette {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace if !input.is_empty() => {
+                            input.pop();
+                        }
+                        KeyCode::Enter => {
+                            let command = palette::Command::parse(input);
+                            app.palette = None;
+                            match command {
+                                Ok(command) => run_command(app, generator, settings, &mut library, command),
+                                Err(e) => app.notice = Some(e),
+                            }
+                        }
+                        KeyCode::Backspace | KeyCode::Esc => app.palette = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The suggestion panel takes the arrow keys until it's closed
+                if app.suggestions.is_some() {
+                    let count = match &app.suggestions {
+                        Some(suggest::Suggestions::Lines(lines)) => lines.len(),
+                        _ => 0,
+                    };
+                    match key.code {
+                        KeyCode::Up => app.suggestion_choice = app.suggestion_choice.saturating_sub(1),
+                        KeyCode::Down => app.suggestion_choice = (app.suggestion_choice + 1).min(count.saturating_sub(1)),
+                        KeyCode::Enter => app.accept_suggestion(),
+                        KeyCode::Esc | KeyCode::Char('D') => {
+                            app.suggestions = None;
+                            pending_suggestions = None;
+                        }
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The stop-word list takes typing until it's closed
+                if let Some(input) = &mut app.stop_word_editor {
+                    match key.code {
+                        KeyCode::Char(c) => input.push(c),
+                        KeyCode::Backspace => {
+                            input.pop();
+                        }
+                        KeyCode::Enter if !input.trim().is_empty() => match app.toggle_stop_word() {
+                            Ok(()) => {
+                                generator.set_stop_words(app.stop_words.words());
+                                app.word_count = generator.usable_count();
+                                if app.active_word_count.is_some() {
+                                    app.active_word_count = Some(generator.active_count());
+                                }
+                            }
+                            Err(e) => app.notice = Some(format!("could not save stop words: {}", e)),
+                        },
+                        KeyCode::Esc => app.stop_word_editor = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The compose panel takes the arrow and editing keys until it's closed
+                if app.compose.is_some() {
+                    let shifted = key.modifiers.contains(KeyModifiers::SHIFT);
+                    match key.code {
+                        KeyCode::Up if shifted => app.move_draft_entry(-1),
+                        KeyCode::Down if shifted => app.move_draft_entry(1),
+                        KeyCode::Up => app.move_compose_cursor(-1),
+                        KeyCode::Down => app.move_compose_cursor(1),
+                        KeyCode::Delete | KeyCode::Backspace => app.remove_draft_entry(),
+                        KeyCode::Char('/') | KeyCode::Enter => app.break_draft_line(),
+                        KeyCode::Char('w') if app.draft.is_empty() => app.notice = Some("nothing to save yet".to_string()),
+                        KeyCode::Char('w') => {
+                            let poem = format!("{}\n", app.draft_text().trim_end());
+                            app.notice = Some(match save_text(settings, "poem", &poem) {
+                                Ok(path) => after_save(app, settings, &path, &poem),
+                                Err(e) => format!("poem not saved: {}", e),
+                            });
+                        }
+                        KeyCode::Char('d') | KeyCode::Esc => app.compose = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // So does the concordance panel
+                if let Some(scrolled) = &mut app.concordance {
+                    match key.code {
+                        KeyCode::Up => *scrolled = scrolled.saturating_sub(1),
+                        KeyCode::Down => *scrolled += 1,
+                        KeyCode::PageUp => *scrolled = scrolled.saturating_sub(10),
+                        KeyCode::PageDown => *scrolled += 10,
+                        KeyCode::Enter | KeyCode::Esc | KeyCode::Char('N') => app.concordance = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The log panel takes the arrow keys until it's closed
+                if let Some(scrolled) = &mut app.log_panel {
+                    let oldest = logging::recent().len();
+                    match key.code {
+                        KeyCode::Up => *scrolled = (*scrolled + 1).min(oldest),
+                        KeyCode::Down => *scrolled = scrolled.saturating_sub(1),
+                        KeyCode::PageUp => *scrolled = (*scrolled + 10).min(oldest),
+                        KeyCode::PageDown => *scrolled = scrolled.saturating_sub(10),
+                        KeyCode::Enter | KeyCode::Esc => app.log_panel = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The seed panel takes the arrow keys until it's closed
+                if app.seed_panel.is_some() {
+                    match key.code {
+                        KeyCode::Up => app.move_seed_selection(-1),
+                        KeyCode::Down => app.move_seed_selection(1),
+                        KeyCode::Enter => {
+                            if let Some((seed, (width, height))) = app.selected_seed() {
+                                app.seed_panel = None;
+                                generate_seeded(app, generator, seed, width, height);
+                            }
+                        }
+                        KeyCode::Char('f') => {
+                            if let Some(favorite) = app.toggle_favorite_seed() {
+                                app.notice = Some(match config::save_favorite_seeds(&app.favorite_seeds) {
+                                    Ok(()) if favorite => "seed saved to favorites".to_string(),
+                                    Ok(()) => "seed taken off favorites".to_string(),
+                                    Err(e) => format!("could not save favorite seeds: {}", e),
+                                });
+                            }
+                        }
+                        KeyCode::Esc => app.seed_panel = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The pruning panel takes the arrow keys until it's closed, changing the bank as it goes
+                if app.prune_panel.is_some() {
+                    let decisions = match key.code {
+                        KeyCode::Up => {
+                            app.move_prune_selection(-1, 0);
+                            Vec::new()
+                        }
+                        KeyCode::Down => {
+                            app.move_prune_selection(1, 0);
+                            Vec::new()
+                        }
+                        KeyCode::Left => {
+                            app.move_prune_selection(0, -1);
+                            Vec::new()
+                        }
+                        KeyCode::Right => {
+                            app.move_prune_selection(0, 1);
+                            Vec::new()
+                        }
+                        KeyCode::Char('m') | KeyCode::Enter => app.prune_merge(false),
+                        KeyCode::Char('M') => app.prune_merge(true),
+                        KeyCode::Char('x') | KeyCode::Delete => app.prune_delete(false),
+                        KeyCode::Char('X') => app.prune_delete(true),
+                        KeyCode::Esc => {
+                            app.prune_panel = None;
+                            Vec::new()
+                        }
+                        _ => Vec::new(),
+                    };
+                    if !decisions.is_empty() {
+                        library.prune(app, generator, &decisions);
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The theme picker takes the arrow keys until it's closed, switching theme as the selection moves
+                if app.theme_panel.is_some() {
+                    match key.code {
+                        KeyCode::Up => app.move_theme_selection(-1),
+                        KeyCode::Down => app.move_theme_selection(1),
+                        KeyCode::Enter | KeyCode::Char('t') => app.close_theme_picker(true),
+                        KeyCode::Esc => app.close_theme_picker(false),
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The source weights panel takes the arrow and number keys until it's closed
+                if app.source_panel.is_some() {
+                    let weight = app.selected_source_weight().unwrap_or(1);
+                    match key.code {
+                        KeyCode::Up => app.move_source_selection(-1),
+                        KeyCode::Down => app.move_source_selection(1),
+                        KeyCode::Left => app.set_source_weight(weight.saturating_sub(1)),
+                        KeyCode::Right => app.set_source_weight(weight + 1),
+                        KeyCode::Char(c @ '0'..='5') => app.set_source_weight(c as u8 - b'0'),
+                        KeyCode::Char('m') | KeyCode::Char('M') | KeyCode::Enter | KeyCode::Esc => app.source_panel = None,
+                        _ => {}
+                    }
+                    generator.set_source_weights(app.changed_source_weights());
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // The ghost overlay takes the arrow keys until it's closed
+                if app.ghost.is_some() {
+                    match key.code {
+                        KeyCode::Left => app.move_ghost_cursor(-1),
+                        KeyCode::Right => app.move_ghost_cursor(1),
+                        KeyCode::Enter => app.notice = Some(app.resurrect_ghost()),
+                        KeyCode::Char('T') | KeyCode::Esc => app.ghost = None,
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
+                // Drawing exclusion zones takes over the arrow keys until it's finished
+                if app.exclusion_editor.is_some() {
+                    let step = if key.modifiers.contains(KeyModifiers::SHIFT) { 5 } else { 1 };
+                    match key.code {
+                        KeyCode::Left => app.move_exclusion_cursor(-step, 0),
+                        KeyCode::Right => app.move_exclusion_cursor(step, 0),
+                        KeyCode::Up => app.move_exclusion_cursor(0, -step),
+                        KeyCode::Down => app.move_exclusion_cursor(0, step),
+                        KeyCode::Char(' ') | KeyCode::Enter => {
+                            app.mark_exclusion_corner();
+                            generator.set_exclusions(app.exclusions.clone());
+                        }
+                        KeyCode::Backspace | KeyCode::Delete => {
+                            app.undo_exclusion();
+                            generator.set_exclusions(app.exclusions.clone());
+                        }
+                        KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Esc => {
+                            app.exclusion_editor = None;
+                            if !app.mouse {
+                                execute!(io::stdout(), DisableMouseCapture)?;
+                            }
+                        }
+                        _ => {}
+                    }
+                    terminal.draw(|f| ui::ui(f, app))?;
+                    continue;
+                }
+
                 // Process the key event
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.notice = Some(if app.redo() { "redone" } else { "nothing to redo" }.to_string());
+                    }
+                    KeyCode::Char('u') => {
+                        app.notice = Some(if app.undo() { "undone (ctrl+r to redo)" } else { "nothing to undo" }.to_string());
+                    }
                     KeyCode::Char('r') | KeyCode::Char('R') => {
-                        let size = terminal.size()?;
-                        let canvas_width = if app.fullscreen_mode {
-                            size.width.saturating_sub(2)
-                        } else {
-                            let sidebar_width = ui::calculate_sidebar_width_for_app(app);
-                            size.width.saturating_sub(sidebar_width).saturating_sub(2)
-                        };
-                        let canvas_height = size.height.saturating_sub(2);
-                        let new_scattered = generator.generate_with_density(canvas_width, canvas_height, app.density);
-                        app.update_words(new_scattered);
+                        let started = reroll(terminal, app, generator, ui::Entrance::Arrive)?;
+                        if started {
+                            app.stats.record_reroll();
+                        }
                     }
+                    KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => app.pan_by(-10, 0),
+                    KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => app.pan_by(10, 0),
+                    KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => app.pan_by(0, -5),
+                    KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => app.pan_by(0, 5),
                     KeyCode::Right | KeyCode::Tab | KeyCode::Char('n') => {
                         app.select_next_word();
                     }
                     KeyCode::Left | KeyCode::BackTab | KeyCode::Char('p') => {
                         app.select_prev_word();
                     }
+                    KeyCode::Char('h') => app.select_toward((-1, 0)),
+                    KeyCode::Char('j') => app.select_toward((0, 1)),
+                    KeyCode::Char('k') => app.select_toward((0, -1)),
+                    KeyCode::Char('l') => app.select_toward((1, 0)),
                     KeyCode::Up => {
                         app.increase_density(app.actual_bar_width);
                     }
@@ -298,9 +1436,198 @@ fn run_app<B: ratatui::backend::Backend>(
                     KeyCode::Char(' ') => {
                         app.toggle_current_highlight();
                     }
+                    KeyCode::Enter => {
+                        app.type_selected_word();
+                    }
+                    KeyCode::Char('/') => {
+                        app.break_typed_line();
+                    }
+                    KeyCode::Backspace => {
+                        app.delete_typed_word();
+                    }
+                    KeyCode::Delete => {
+                        app.typed_words.clear();
+                        app.typed_shown = 0;
+                    }
                     KeyCode::Char('v') | KeyCode::Char('V') => {
                         app.fullscreen_mode = !app.fullscreen_mode;
                     }
+                    KeyCode::Char('i') | KeyCode::Char('I') => {
+                        app.show_stats = !app.show_stats;
+                    }
+                    KeyCode::Char('g') | KeyCode::Char('G') => {
+                        app.show_ruler = !app.show_ruler;
+                    }
+                    KeyCode::Char('w') | KeyCode::Char('W') => {
+                        app.cloud_mode = !app.cloud_mode;
+                    }
+                    KeyCode::Char('o') | KeyCode::Char('O') => {
+                        app.font_styles = !app.font_styles;
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C') => {
+                        app.show_path = !app.show_path;
+                    }
+                    KeyCode::Char(c @ '1'..='9') => {
+                        app.set_density(ui::DENSITY_PRESETS[c as usize - '1' as usize]);
+                    }
+                    KeyCode::Char('L') => {
+                        app.toggle_lock();
+                        generator.set_kept_words(app.kept_words());
+                    }
+                    KeyCode::Char('f') | KeyCode::Char('F') => {
+                        app.add_to_deck();
+                    }
+                    KeyCode::Char('D') => match (&settings.suggest, app.highlighted_word_texts()) {
+                        (None, _) => app.notice = Some("no suggest command (set suggest = COMMAND in settings.conf)".to_string()),
+                        (_, words) if words.is_empty() => app.notice = Some("highlight some words first".to_string()),
+                        (Some(command), words) => {
+                            pending_suggestions = Some(suggest::request(command.clone(), words));
+                            app.suggestions = Some(suggest::Suggestions::Waiting);
+                            app.suggestion_choice = 0;
+                        }
+                    },
+                    KeyCode::Char('P') => {
+                        let showing = app.prompt_card.as_ref().map(|(index, _)| *index);
+                        app.prompt_card = prompts::deal(&mut rand::thread_rng(), &app.prompts, showing);
+                    }
+                    KeyCode::Esc => app.prompt_card = None,
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        app.pair_selected_word();
+                    }
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        app.open_stop_word_editor();
+                    }
+                    KeyCode::Char('H') => {
+                        app.spelling = app.spelling.next();
+                        app.notice = Some(format!("{} spelling", app.spelling.label()));
+                    }
+                    KeyCode::Char('"') => quote_selected_word(app),
+                    KeyCode::Char('t') => app.open_theme_picker(),
+                    KeyCode::Char('T') => app.open_ghost(),
+                    KeyCode::Char('N') => app.open_concordance(),
+                    KeyCode::Char('m') | KeyCode::Char('M') => {
+                        app.source_panel = (!app.source_weights.is_empty()).then_some(0);
+                    }
+                    KeyCode::Char('?') => {
+                        app.show_help = true;
+                    }
+                    KeyCode::Char(':') => {
+                        app.palette = Some(String::new());
+                    }
+                    KeyCode::Char('s') => {
+                        app.notice = None;
+                        app.save_prompt = Some(String::new());
+                    }
+                    KeyCode::Char('d') => app.open_compose(),
+                    KeyCode::Char('K') => {
+                        // The whole canvas as a text snapshot would have it, without the border
+                        let (width, height) = app.page_size();
+                        let text = export::render_text_decorated(&app.shown_words(), &snapshot_decorations(app), width, height);
+                        let lines = text.trim_matches('\n').lines().count();
+                        app.notice = Some(match clipboard::copy(&text) {
+                            Ok(()) => format!("copied the canvas to the clipboard ({} lines)", lines),
+                            Err(e) => format!("copy failed: {}", e),
+                        });
+                    }
+                    KeyCode::Char('S') => {
+                        app.notice = Some(match save_snapshot(app, settings) {
+                            Ok(path) => {
+                                let decorations = snapshot_decorations(app);
+                                let (width, height) = app.page_size();
+                                let text = export::render_text_decorated(&app.shown_words(), &decorations, width, height);
+                                after_save(app, settings, &path, &text)
+                            }
+                            Err(e) => format!("snapshot failed: {}", e),
+                        });
+                    }
+                    KeyCode::Char('[') | KeyCode::Char(']') => {
+                        let step = if key.code == KeyCode::Char(']') { 0.1 } else { -0.1 };
+                        generator.set_chaos(generator.chaos() + step);
+                        app.notice = Some(format!("chaos {:.1}", generator.chaos()));
+                    }
+                    KeyCode::Char('b') => {
+                        app.styling.cycle_border_type();
+                    }
+                    KeyCode::Char('B') => {
+                        app.show_canvas_border = !app.show_canvas_border;
+                    }
+                    KeyCode::Char('x') | KeyCode::Char('X') => {
+                        // Mouse capture only while drawing, so normal text selection keeps working otherwise
+                        app.start_exclusion_edit();
+                        execute!(io::stdout(), EnableMouseCapture)?;
+                    }
+                    KeyCode::Char('z') => {
+                        // Split the canvas differently (whole, rows, columns, quadrants) and show it
+                        app.set_zone_layout(app.zone_layout.next(), None);
+                        if reroll(terminal, app, generator, ui::Entrance::Appear)? {
+                            app.stats.record_reroll();
+                        }
+                    }
+                    KeyCode::Char('Z') => {
+                        app.focus_next_zone();
+                    }
+                    KeyCode::Char('a') => {
+                        // Draw a new active slice of the bank and scatter from it
+                        generator.resample_active(&mut rand::thread_rng(), app.slice_percent as f64 / 100.0);
+                        app.active_word_count = Some(generator.active_count());
+                        if reroll(terminal, app, generator, ui::Entrance::Appear)? {
+                            app.stats.record_reroll();
+                        }
+                    }
+                    KeyCode::Char('A') => {
+                        generator.clear_active();
+                        app.active_word_count = None;
+                        if reroll(terminal, app, generator, ui::Entrance::Appear)? {
+                            app.stats.record_reroll();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Mouse(mouse) if app.exclusion_editor.is_some() => {
+                // Drag out a rectangle: press at one corner, release at the other
+                if let Some(cell) = app.canvas_cell(mouse.column, mouse.row) {
+                    let Some(editor) = &mut app.exclusion_editor else { continue };
+                    let released = match mouse.kind {
+                        MouseEventKind::Down(MouseButton::Left) => {
+                            editor.cursor = cell;
+                            editor.anchor = Some(cell);
+                            false
+                        }
+                        MouseEventKind::Drag(MouseButton::Left) => {
+                            editor.cursor = cell;
+                            false
+                        }
+                        MouseEventKind::Up(MouseButton::Left) => {
+                            editor.cursor = cell;
+                            editor.anchor.is_some()
+                        }
+                        _ => false,
+                    };
+
+                    if released {
+                        app.mark_exclusion_corner();
+                        generator.set_exclusions(app.exclusions.clone());
+                    }
+                }
+            }
+            Event::Mouse(mouse) => {
+                // Press on the density bar to set the density there, and drag to slide it
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(density) = app.density_at(mouse.column, mouse.row) {
+                            app.set_density(density);
+                            app.dragging_density = true;
+                        }
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) if app.dragging_density => {
+                        let row = app.density_bar.y;
+                        let column = mouse.column.clamp(app.density_bar.x, app.density_bar.right().saturating_sub(1));
+                        if let Some(density) = app.density_at(column, row) {
+                            app.set_density(density);
+                        }
+                    }
+                    MouseEventKind::Up(MouseButton::Left) => app.dragging_density = false,
                     _ => {}
                 }
             }
@@ -314,3 +1641,684 @@ fn run_app<B: ratatui::backend::Backend>(
         terminal.draw(|f| ui::ui(f, app))?;
     }
 }
+
+/// Merge another bank into the session's; words the session already has stay under their first source, so
+/// the generator only gets the new ones and no word is in its pool twice
+fn join_bank(word_bank: &mut word_bank::WordBank, generator: &mut scatters::ScattersGenerator, other: word_bank::WordBank) {
+    let known = word_bank.frequencies();
+    generator.add_words(other.get_words().into_iter().filter(|(word, _)| !known.contains_key(word)).collect());
+    word_bank.merge(other);
+}
+
+/// The session's word bank and how it was loaded, so `:open` and `:add` can load more the same way
+struct Library {
+    word_bank: word_bank::WordBank,
+    path: Option<PathBuf>,  // The corpus pruning decisions are kept for; none for a built-in corpus, deck or dialogue
+    options: corpus::LoadOptions,
+    loading: Option<Loading>,
+}
+
+/// A corpus being parsed on a background thread for `:open` or `:add`
+struct Loading {
+    path: PathBuf,
+    merge: bool,  // Join the current bank instead of replacing it
+    receiver: Receiver<Result<word_bank::WordBank, String>>,
+}
+
+impl Library {
+    /// Start parsing `path` in the background, returning the notice to show meanwhile
+    fn start_loading(&mut self, path: &str, merge: bool) -> String {
+        let path = config::expand_home(path);
+        if !path.exists() {
+            return format!("'{}' does not exist", path.display());
+        }
+
+        // Warnings printed over the TUI would garble it
+        let options = corpus::LoadOptions { quiet: true, quiet_warnings: true, ..self.options.clone() };
+        let (sender, receiver) = mpsc::channel();
+        let thread_path = path.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(corpus::load_word_bank(&thread_path, &options).map_err(|e| e.to_string()));
+        });
+
+        let notice = format!("loading {}...", path.display());
+        self.loading = Some(Loading { path, merge, receiver });
+        notice
+    }
+
+    /// Switch to a corpus that has finished loading; true when a load finished, whether or not it worked
+    fn finish_loading(&mut self, app: &mut ui::App, generator: &mut scatters::ScattersGenerator) -> bool {
+        let result = match self.loading.as_ref().map(|loading| loading.receiver.try_recv()) {
+            None | Some(Err(TryRecvError::Empty)) => return false,
+            Some(Ok(result)) => result,
+            Some(Err(TryRecvError::Disconnected)) => Err("loading stopped unexpectedly".to_string()),
+        };
+        let Some(Loading { path, merge, .. }) = self.loading.take() else {
+            return false;
+        };
+
+        match result {
+            Ok(word_bank) => self.switch_to(app, generator, word_bank, &path, merge),
+            Err(e) => app.notice = Some(format!("could not load {}: {}", path.display(), e)),
+        }
+        true
+    }
+
+    /// Draw from a loaded bank from the next reroll, alone or joined with the current one; the canvas keeps
+    /// its words until then
+    fn switch_to(
+        &mut self,
+        app: &mut ui::App,
+        generator: &mut scatters::ScattersGenerator,
+        word_bank: word_bank::WordBank,
+        path: &Path,
+        merge: bool,
+    ) {
+        let loaded = word_bank.word_count();
+        if merge {
+            join_bank(&mut self.word_bank, generator, word_bank);
+            app.directory = PathBuf::from(format!("{} + {}", app.directory.display(), path.display()));
+            app.source_roots.push(path.to_path_buf());
+        } else {
+            generator.set_words(word_bank.get_words());
+            self.word_bank = word_bank;
+            self.path = Some(path.to_path_buf());
+            app.directory = path.to_path_buf();
+            app.source_roots = vec![path.to_path_buf()];
+            app.voice_names = None;
+        }
+        self.refresh(app, generator);
+
+        let verb = if merge { "added" } else { "loaded" };
+        app.notice = Some(format!("{} {} words from {} (r to reroll)", verb, loaded, path.display()));
+    }
+
+    /// Bring the generator and the app up to date with a changed bank
+    fn refresh(&self, app: &mut ui::App, generator: &mut scatters::ScattersGenerator) {
+        generator.set_frequencies(self.word_bank.frequencies());
+        app.word_locations = self.word_bank.locations();
+        app.word_contexts = self.word_bank.contexts();
+        app.source_titles = self.word_bank.titles().clone();
+        app.set_word_frequencies(self.word_bank.frequencies());
+        app.set_sources(generator.word_pool().map(|(_, source)| source.as_str()));
+        generator.set_source_weights(app.changed_source_weights());
+
+        // Date and recency weighting and the active slice were worked out word by word for the old pool
+        app.time_sweep = None;
+        app.time_window = None;
+        if app.active_word_count.is_some() {
+            generator.resample_active(&mut rand::thread_rng(), app.slice_percent as f64 / 100.0);
+            app.active_word_count = Some(generator.active_count());
+        }
+        if !app.watermark_sentences.is_empty() {
+            app.watermark_sentences = corpus::sample_sentences(generator.word_pool().map(|(_, source)| source.as_str()), &app.source_roots);
+        }
+        app.word_count = generator.usable_count();
+    }
+
+    /// The bank's near-duplicates as spellings and counts
+    fn near_duplicates(&self) -> Vec<Vec<(String, usize)>> {
+        self.word_bank
+            .near_duplicates()
+            .into_iter()
+            .map(|forms| forms.into_iter().map(|form| (form.word, form.count)).collect())
+            .collect()
+    }
+
+    /// Open the pruning panel, if the corpus is one decisions can be kept for and has near-duplicates
+    fn open_pruning(&self, app: &mut ui::App) {
+        if self.path.is_none() {
+            app.notice = Some("pruning needs a corpus path to keep decisions for (not a built-in corpus, deck or dialogue)".to_string());
+            return;
+        }
+        let clusters = self.near_duplicates();
+        if clusters.is_empty() {
+            app.notice = Some("no near-duplicates in the bank".to_string());
+            return;
+        }
+        app.show_near_duplicates(clusters);
+    }
+
+    /// Merge or delete words in the bank, keep the decisions for the next time this corpus is loaded, and
+    /// list the near-duplicates left
+    fn prune(&mut self, app: &mut ui::App, generator: &mut scatters::ScattersGenerator, decisions: &[word_bank::PruneDecision]) {
+        let Some(path) = &self.path else { return };
+        decisions.iter().for_each(|decision| self.word_bank.prune(decision));
+        app.notice = Some(match cache::save_pruning(path, decisions) {
+            Ok(()) => format!("pruned {} words from the bank (r to reroll)", decisions.len()),
+            Err(e) => format!("pruned {} words, but could not keep the decisions: {}", decisions.len(), e),
+        });
+        generator.set_words(self.word_bank.get_words());
+        self.refresh(app, generator);
+        app.show_near_duplicates(self.near_duplicates());
+    }
+}
+
+/// When the timed updates last happened
+struct Timers {
+    last_input: Instant,  // Last key press, for the idle timeout
+    last_screensaver_reroll: Instant,
+    last_stats_refresh: Instant,  // Last redraw of the elapsed time in the stats panel
+    last_cursor_blink: Instant,
+    timer_face: Option<(String, bool)>,  // Writing timer reading and flash as last drawn
+}
+
+impl Timers {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self { last_input: now, last_screensaver_reroll: now, last_stats_refresh: now, last_cursor_blink: now, timer_face: None }
+    }
+}
+
+/// One tick's timed updates: reveal frames, screensaver rerolls, the idle check, fading emphasis, the
+/// typewriter, the writing timer and the stats clock; returns whether the screen needs redrawing
+fn tick<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut ui::App,
+    generator: &mut scatters::ScattersGenerator,
+    timers: &mut Timers,
+    idle_timeout: Option<Duration>,
+    typewriter_sound: bool,
+) -> io::Result<bool> {
+    // Words carried over from the last scatter lose their emphasis after a moment
+    let mut changed = app.recurring_remaining().is_some_and(|remaining| remaining.is_zero());
+    app.expire_recurring();
+
+    // A reroll generated in the background goes up as soon as it's ready
+    changed |= finish_generating(app, Duration::ZERO);
+
+    if app.arrival.is_some() {
+        app.advance_arrival();
+        changed = true;
+    }
+
+    if app.reveal_count.is_some() {
+        app.advance_reveal();
+        changed = true;
+    } else if (app.screensaver_active && timers.last_screensaver_reroll.elapsed() >= SCREENSAVER_REROLL_INTERVAL)
+        || (!app.screensaver_active && idle_timeout.is_some_and(|idle| timers.last_input.elapsed() >= idle))
+    {
+        app.start_screensaver();
+        reroll(terminal, app, generator, ui::Entrance::Reveal)?;
+        timers.last_screensaver_reroll = Instant::now();
+        changed = true;
+    }
+
+    if app.expire_selection_cue() {
+        changed = true;
+    }
+
+    // The typewriter types a character a tick, with a bell for each one when sounds are on
+    if let Some(typed) = app.advance_typing() {
+        if typewriter_sound && !typed.is_whitespace() {
+            print!("\x07");
+            io::Write::flush(&mut io::stdout())?;
+        }
+        changed = true;
+    }
+    if !app.typed_words.is_empty() && timers.last_cursor_blink.elapsed() >= CURSOR_BLINK_INTERVAL {
+        app.cursor_visible = !app.cursor_visible;
+        timers.last_cursor_blink = Instant::now();
+        changed = true;
+    }
+
+    let timer_face = app.timer.as_ref().map(|timer| (timer.reading(), timer.flashing()));
+    if timer_face != timers.timer_face {
+        timers.timer_face = timer_face;
+        changed = true;
+    }
+
+    if app.show_stats && timers.last_stats_refresh.elapsed() >= STATS_REFRESH_INTERVAL {
+        timers.last_stats_refresh = Instant::now();
+        changed = true;
+    }
+
+    Ok(changed)
+}
+
+/// Carry out a command typed at the `:` prompt
+fn run_command(
+    app: &mut ui::App,
+    generator: &mut scatters::ScattersGenerator,
+    settings: &config::Settings,
+    library: &mut Library,
+    command: palette::Command,
+) {
+    match command {
+        palette::Command::Title(name) => app.set_title(name),
+        palette::Command::EpigraphOff => app.epigraph = None,
+        palette::Command::Density(density) => app.set_density(density),
+        palette::Command::AutoDensity => app.set_density(generator.auto_density()),
+        palette::Command::Seed => {
+            let (width, height) = app.scatter_size;
+            app.notice = Some(format!("replay with: {} {}x{}", app.seed, width, height));
+        }
+        palette::Command::Replay(seed, (width, height)) => generate_seeded(app, generator, seed, width, height),
+        palette::Command::Chaos(chaos) => {
+            generator.set_chaos(chaos);
+            app.notice = Some(format!("chaos {:.1}", generator.chaos()));
+        }
+        palette::Command::Sampling(sampling) => {
+            generator.set_sampling(sampling);
+            app.notice = Some(format!("{} sampling from the next reroll", sampling.name()));
+        }
+        palette::Command::Deck(name) => {
+            app.notice = Some(format!("f now adds words to deck {}", name));
+            app.deck = name;
+        }
+        palette::Command::Inject(Some(name)) => match decks::load(&name) {
+            Ok(words) => {
+                app.deck_words = words.into_iter().map(|(word, source)| (word, source, None)).collect();
+                generator.set_kept_words(app.kept_words());
+                app.notice = Some(format!("deck {} joins every scatter from the next reroll", name));
+            }
+            Err(e) => app.notice = Some(e.to_string()),
+        },
+        palette::Command::Inject(None) => {
+            app.deck_words.clear();
+            generator.set_kept_words(app.kept_words());
+            app.notice = Some("no deck injected".to_string());
+        }
+        palette::Command::Poem if app.typed_words.is_empty() => {
+            app.notice = Some("nothing typed yet (Enter types the selected word)".to_string());
+        }
+        palette::Command::Poem => {
+            let poem = format!("{}\n", app.typed_text().trim_end());
+            app.notice = Some(match save_text(settings, "poem", &poem) {
+                Ok(path) => after_save(app, settings, &path, &poem),
+                Err(e) => format!("poem not saved: {}", e),
+            });
+        }
+        palette::Command::Couplets if app.pairs.is_empty() => {
+            app.notice = Some("no pairs yet (y on two words pairs them)".to_string());
+        }
+        palette::Command::Couplets => {
+            let couplets = app.couplets();
+            app.notice = Some(match save_text(settings, "couplets", &couplets) {
+                Ok(path) => after_save(app, settings, &path, &couplets),
+                Err(e) => format!("couplets not saved: {}", e),
+            });
+        }
+        palette::Command::Import(Some(name)) => {
+            let given = config::expand_home(&name);
+            let path = if given.exists() { given } else { settings.export_dir.join(&name) };
+            match import::load(&path) {
+                Ok(words) => {
+                    app.pinned_words = words;
+                    generator.set_pinned_words(app.pinned_words.clone());
+                    let (width, height) = app.scatter_size;
+                    generate_seeded(app, generator, new_seed(), width, height);
+                    let shown = app.scattered_words.iter().filter(|word| app.is_pinned(word)).count();
+                    app.notice = Some(match app.pinned_words.len() - shown {
+                        0 => format!("{} words pinned from {}", shown, path.display()),
+                        missing => format!("{} words pinned from {} ({} don't fit this canvas)", shown, path.display(), missing),
+                    });
+                }
+                Err(e) => app.notice = Some(e),
+            }
+        }
+        palette::Command::Import(None) => {
+            app.pinned_words.clear();
+            generator.set_pinned_words(Vec::new());
+            app.notice = Some("no words pinned from the next reroll".to_string());
+        }
+        palette::Command::Anthology(_) if app.highlighted_words.is_empty() => {
+            app.notice = Some("no highlighted words to add (←/→ highlight words as you visit them)".to_string());
+        }
+        palette::Command::Anthology(path) => match path.map(|path| config::expand_home(&path)).or_else(|| app.anthology.clone()) {
+            Some(path) => {
+                let words = app.highlighted_word_texts();
+                let section = anthology::Section {
+                    title: app.title.as_deref(),
+                    words: &words,
+                    seed: app.seed,
+                    size: app.scatter_size,
+                    sources: source_names(app, app.highlighted_words.iter().filter_map(|&index| app.scattered_words.get(index))),
+                };
+                app.notice = Some(match anthology::append(&path, &section) {
+                    Ok(_) => format!("added {} words to {}", words.len(), path.display()),
+                    Err(e) => format!("poem not added to {}: {}", path.display(), e),
+                });
+                app.anthology = Some(path);
+            }
+            None => app.notice = Some("usage: anthology PATH (or set anthology in settings.conf)".to_string()),
+        },
+        palette::Command::SaveTemplate(name) => {
+            let template = templates::Template::from_words(&name, &app.scattered_words, app.scatter_size);
+            app.notice = Some(match templates::save(&template) {
+                Ok(_) => format!("saved template {} ({} words)", name, template.slots.len()),
+                Err(e) => e.to_string(),
+            });
+        }
+        palette::Command::Template(Some(name)) => match templates::load(&name) {
+            Ok(template) => {
+                app.notice = Some(format!("rerolls fill template {} with fresh words", name));
+                app.template = Some(template);
+            }
+            Err(e) => app.notice = Some(e.to_string()),
+        },
+        palette::Command::Template(None) => {
+            app.template = None;
+            app.notice = Some("rerolls scatter freely".to_string());
+        }
+        palette::Command::Timer(minutes) => {
+            app.timer = Some(minutes.map_or_else(stats::WritingTimer::count_up, stats::WritingTimer::countdown));
+        }
+        palette::Command::TimerOff => app.timer = None,
+        palette::Command::Transform(_) if settings.transform.is_none() => {
+            app.notice = Some("no transform command (set transform = COMMAND in settings.conf)".to_string());
+        }
+        palette::Command::Transform(_) if app.transforming.is_some() => {
+            app.notice = Some("still waiting on the last transform".to_string());
+        }
+        palette::Command::Transform(all) => {
+            let sent = app.transform_targets(all);
+            if sent.is_empty() {
+                app.notice = Some("no words to transform".to_string());
+            } else {
+                let command = settings.transform.clone().unwrap_or_default();
+                let words = sent.iter().map(|(_, word)| word.clone()).collect();
+                app.notice = Some(format!("transforming {} words...", sent.len()));
+                app.transforming = Some(ui::PendingTransform { sent, receiver: transform::request(command, words) });
+            }
+        }
+        palette::Command::Spelling(spelling) => app.spelling = spelling,
+        palette::Command::Lipogram(letters) => {
+            generator.set_lipogram(letters.as_deref().unwrap_or_default());
+            app.word_count = generator.usable_count();
+            if app.active_word_count.is_some() {
+                app.active_word_count = Some(generator.active_count());
+            }
+            app.notice = Some(match &letters {
+                Some(letters) => format!("no words with {} from the next reroll", letters.chars().map(String::from).collect::<Vec<_>>().join("/")),
+                None => "lipogram off from the next reroll".to_string(),
+            });
+            app.lipogram = letters;
+        }
+        palette::Command::Log => app.log_panel = Some(0),
+        palette::Command::Seeds => app.seed_panel = Some(0),
+        palette::Command::Open(path) => app.notice = Some(library.start_loading(&path, false)),
+        palette::Command::Add(path) => app.notice = Some(library.start_loading(&path, true)),
+        palette::Command::Prune => library.open_pruning(app),
+        palette::Command::Syllables(budget) => {
+            generator.set_syllable_budget(budget);
+            app.syllable_budget = budget;
+            app.notice = Some(match budget {
+                Some(budget) => format!("{} syllables a scatter from the next reroll", budget),
+                None => "density sets the word count again from the next reroll".to_string(),
+            });
+        }
+    }
+}
+
+/// Write text to the next numbered `STEM-NNNN.txt` in the export directory
+fn save_text(settings: &config::Settings, stem: &str, text: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = config::next_numbered_path(&settings.export_dir, stem, "txt")?;
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// What a snapshot draws over the words, apart from the frame
+fn snapshot_decorations(app: &ui::App) -> export::Decorations<'_> {
+    export::Decorations {
+        path: if app.show_path { &app.highlighted_words } else { &[] },
+        title: app.title.as_deref(),
+        frame: None,
+        source_titles: Some(&app.source_titles),
+        watermark: app.watermark.as_deref(),
+        epigraph: app.epigraph.as_deref(),
+        voices: app.voice_names.as_ref().map(|names| export::VoiceLegend {
+            names,
+            styles: [app.styling.text_style, app.styling.second_voice_style],
+        }),
+        page: None,
+        typeface: None,
+    }
+}
+
+/// "saved PATH" for the notice line, adding the save to the journal first when it's on
+fn after_save(app: &ui::App, settings: &config::Settings, path: &std::path::Path, text: &str) -> String {
+    if !settings.journal {
+        return format!("saved {}", path.display());
+    }
+
+    let entry = journal::Entry { saved: path, seed: app.seed, size: app.scatter_size, sources: source_names(app, app.scattered_words.iter()), text };
+    match journal::append(&entry) {
+        Ok(_) => format!("saved {} and added it to the journal", path.display()),
+        Err(e) => format!("saved {} (journal entry failed: {})", path.display(), e),
+    }
+}
+
+/// Where a name typed at the save prompt points: `~/` and absolute paths as given, anything else in the
+/// export directory, with `.txt` added when there's no extension
+fn save_path(settings: &config::Settings, name: &str) -> PathBuf {
+    let mut path = config::expand_home(name);
+    if path.is_relative() {
+        path = settings.export_dir.join(path);
+    }
+    if path.extension().is_none() {
+        path.set_extension("txt");
+    }
+    path
+}
+
+/// Write the words to a new text file at their canvas positions, without the border, title or reading
+/// path, returning the text written; an existing file is left alone
+fn save_canvas_text(app: &ui::App, path: &std::path::Path) -> Result<String, Box<dyn std::error::Error>> {
+    if path.exists() {
+        return Err(format!("{} already exists, choose another name", path.display()).into());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let (width, height) = app.page_size();
+    let text = export::render_text(&app.shown_words(), width, height);
+    std::fs::write(path, &text)?;
+    Ok(text)
+}
+
+/// Pin a sentence from the selected word's source that has the word in it as the canvas's epigraph
+fn quote_selected_word(app: &mut ui::App) {
+    let Some(scattered) = app.selected_word_index.and_then(|index| app.scattered_words.get(index)) else {
+        app.notice = Some("select a word to quote".to_string());
+        return;
+    };
+    match corpus::sentence_with(&scattered.word, &scattered.source_file, &app.source_roots) {
+        Some(sentence) => app.epigraph = Some(sentence),
+        None => {
+            app.notice = Some(format!("no sentence with '{}' in {}", scattered.word, app.source_name(&scattered.source_file)));
+        }
+    }
+}
+
+/// Names of the files some of the scatter's words came from, each once, in the order the words first use them
+fn source_names<'a>(app: &'a ui::App, words: impl Iterator<Item = &'a scatters::ScatteredWord>) -> Vec<&'a str> {
+    let mut sources: Vec<&str> = Vec::new();
+    for word in words {
+        let source = app.source_name(&word.source_file);
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    sources
+}
+
+/// Write the canvas to the next numbered file in the export directory
+fn save_snapshot(app: &ui::App, settings: &config::Settings) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let format = settings.export_format;
+    let path = config::next_snapshot_path(&settings.export_dir, format.extension())?;
+    let typeface = match format {
+        export::ExportFormat::Png => Some(raster::Typeface::load(settings.png_font.as_deref(), settings.png_font_size)?),
+        _ => None,
+    };
+    let decorations = export::Decorations {
+        frame: app.show_canvas_border.then_some(app.styling.border_type),
+        page: settings.export_page,
+        typeface: typeface.as_ref(),
+        ..snapshot_decorations(app)
+    };
+    let (width, height) = app.page_size();
+    let content = export::render(format, &app.shown_words(), &decorations, width, height)?;
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Generate a fresh scatter sized to the current canvas area on a worker thread, moving a time sweep on
+/// to its next period; it goes up with `entrance` once it's ready, and the canvas keeps answering keys
+/// meanwhile however big the bank is. False when a reroll is still being generated, which is left to
+/// finish instead of starting another
+fn reroll<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut ui::App,
+    generator: &mut scatters::ScattersGenerator,
+    entrance: ui::Entrance,
+) -> io::Result<bool> {
+    if app.generating.is_some() {
+        app.notice = Some("still generating the last reroll".to_string());
+        return Ok(false);
+    }
+    if let Some(sweep) = &mut app.time_sweep {
+        sweep.advance();
+        let (label, span) = sweep.current();
+        generator.set_weights(timeline::weight_within(span));
+        app.time_window = Some(label.to_string());
+    }
+
+    let size = terminal.size()?;
+    let (canvas_width, canvas_height) = ui::canvas_size(app, size.width, size.height);
+    let seed = new_seed();
+    // The worker's copy shares the bank and the drawable words, worked out here first if a change reset them
+    generator.warm_pool();
+    let job = scatter_job(app, generator.clone(), seed, canvas_width, canvas_height);
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(job());
+    });
+    app.generating = Some(ui::PendingScatter { seed, size: (canvas_width, canvas_height), entrance, receiver });
+    finish_generating(app, REROLL_WAIT);
+    Ok(true)
+}
+
+/// Put up the background reroll if it's ready within `wait`; true if the canvas changed
+fn finish_generating(app: &mut ui::App, wait: Duration) -> bool {
+    let received = match app.generating.as_ref().map(|pending| pending.receiver.recv_timeout(wait)) {
+        Some(Ok(received)) => received,
+        Some(Err(RecvTimeoutError::Disconnected)) => {
+            app.generating = None;
+            app.notice = Some("the scatter could not be generated".to_string());
+            return true;
+        }
+        Some(Err(RecvTimeoutError::Timeout)) | None => return false,
+    };
+    let Some(pending) = app.generating.take() else { return false };
+    let (scatter, rng) = received;
+    show_scatter(app, scatter, rng, pending.seed, pending.size);
+    match pending.entrance {
+        ui::Entrance::Appear => {}
+        ui::Entrance::Arrive => app.begin_arrival(),
+        ui::Entrance::Reveal => app.begin_reveal(),
+    }
+    true
+}
+
+/// A random seed for a new scatter, kept to nine digits so it's easy to note down from the sidebar
+fn new_seed() -> u64 {
+    rand::thread_rng().gen_range(0..1_000_000_000)
+}
+
+/// Generate a scatter with every random choice drawn from `seed`, so the same seed, canvas size
+/// and settings replay it exactly
+fn generate_seeded(app: &mut ui::App, generator: &scatters::ScattersGenerator, seed: u64, width: u16, height: u16) {
+    let (scatter, rng) = scatter_job(app, generator, seed, width, height)();
+    // It takes the place of any reroll still being generated
+    app.generating = None;
+    show_scatter(app, scatter, rng, seed, (width, height));
+}
+
+/// The work of generating a scatter from `seed` with the current template or zones, to run here or on a
+/// worker thread with its own copy of the generator
+fn scatter_job<G: Borrow<scatters::ScattersGenerator>>(
+    app: &ui::App,
+    generator: G,
+    seed: u64,
+    width: u16,
+    height: u16,
+) -> impl FnOnce() -> (scatters::Scatter, StdRng) {
+    let slots = app.template.as_ref().map(|template| template.slots_for(width, height));
+    let (zone_layout, densities) = (app.zone_layout, app.densities());
+    move || {
+        let generator = generator.borrow();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let scatter = match &slots {
+            Some(slots) => generator.fill_template_with_rng(&mut rng, width, height, slots),
+            None => generator.generate_zoned_with_rng(&mut rng, width, height, zone_layout, &densities),
+        };
+        (scatter, rng)
+    }
+}
+
+/// Put a generated scatter on the canvas, choosing its watermark with the RNG it was drawn from
+fn show_scatter(app: &mut ui::App, scatter: scatters::Scatter, mut rng: StdRng, seed: u64, (width, height): (u16, u16)) {
+    logging::detail(|| {
+        let template = app.template.as_ref().map(|template| format!(", template {}", template.name)).unwrap_or_default();
+        format!(
+            "scatter from seed {} at {}x{}{}: {} of {} drawn words placed",
+            seed,
+            width,
+            height,
+            template,
+            scatter.words.len(),
+            scatter.requested
+        )
+    });
+    app.update_words(scatter);
+    app.watermark = app.watermark_sentences.choose(&mut rng).cloned();
+    app.seed = seed;
+    app.scatter_size = (width, height);
+    app.seed_history.push((seed, (width, height)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generator_flags_reach_every_mode() {
+        let args = Args::try_parse_from([
+            "text-scatters", "--chaos", "0.9", "--keep-numerals", "--lipogram", "e", "--topology", "rows",
+            "--cell-aspect", "3", "--sampling", "frequent", "--min-sources", "2",
+        ])
+        .unwrap();
+        let words: Vec<(String, String)> = (0..60)
+            .map(|i| (format!("w{:02}", i), if i < 58 { "a.txt" } else { "b.txt" }.to_string()))
+            .chain([("xiv".to_string(), "a.txt".to_string()), ("tide".to_string(), "a.txt".to_string())])
+            .collect();
+        let frequencies: std::collections::HashMap<String, usize> = (0..10).map(|i| (format!("w{:02}", i), 1000)).collect();
+        let mut generator = scatters::ScattersGenerator::new(words.clone());
+        generator.set_frequencies(frequencies.clone());
+        configure_generator(&mut generator, &args, Some(12));
+        assert_eq!(generator.chaos(), 0.9);
+        assert!(!generator.is_left_out("xiv"));
+        assert!(generator.is_left_out("tide"));
+
+        // The same seed lays out the same scatter as a generator given every setting by hand, and not the
+        // same as one left at the defaults
+        let mut by_hand = scatters::ScattersGenerator::new(words.clone());
+        by_hand.set_frequencies(frequencies);
+        by_hand.set_chaos(0.9);
+        by_hand.set_keep_numerals(true);
+        by_hand.set_lipogram("e");
+        by_hand.set_topology(scatters::Topology::Rows);
+        by_hand.set_cell_aspect(3.0);
+        by_hand.set_sampling(scatters::Sampling::Frequent);
+        by_hand.set_min_sources(2);
+        by_hand.set_syllable_budget(Some(12));
+        let layout = |generator: &scatters::ScattersGenerator| -> Vec<(String, u16, u16)> {
+            let scatter = generator.generate_with_seed(7, 60, 20, 1.0);
+            scatter.words.into_iter().map(|word| (word.word, word.x, word.y)).collect()
+        };
+        assert!(!layout(&generator).is_empty());
+        assert_eq!(layout(&generator), layout(&by_hand));
+        assert_ne!(layout(&generator), layout(&scatters::ScattersGenerator::new(words)));
+    }
+}
@@ -0,0 +1,201 @@
+//! Commands typed at the `:` prompt.
+
+use crate::embed;
+use crate::scatters::Sampling;
+use crate::spelling::Spelling;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Title(Option<String>),  // Name the scatter, or clear the name
+    EpigraphOff,  // Take the quoted sentence off the bottom of the canvas
+    Density(f32),  // Set the density of the focused zone
+    AutoDensity,  // Set the density of the focused zone from the size of the bank
+    Chaos(f32),  // Set how far scatters stray from order
+    Sampling(Sampling),  // Set how a word's corpus count bears on drawing it
+    Seed,  // Show the seed and canvas size that replay the current scatter
+    Replay(u64, (u16, u16)),  // Regenerate the scatter made from a seed at a canvas size
+    Deck(String),  // Choose the deck the f key adds words to
+    Inject(Option<String>),  // Put a deck's words in every scatter, or stop
+    Poem,  // Save the typewriter line as a poem, line and stanza breaks included
+    Couplets,  // Save the paired words as couplets
+    Import(Option<String>),  // Pin a saved snapshot's or poem's words where they were in every scatter, or stop
+    Anthology(Option<String>),  // Add the highlighted words as a poem to an anthology file, or to the last one used
+    SaveTemplate(String),  // Save the current layout's word positions under a name
+    Template(Option<String>),  // Fill a saved layout with fresh words on every reroll, or stop
+    Timer(Option<u32>),  // Start the sidebar timer, counting down from some minutes or up from zero
+    TimerOff,  // Hide the sidebar timer
+    Transform(bool),  // Pipe the highlighted words, or with true every word, through the transform command
+    Spelling(Spelling),  // Spell the canvas words another way, or as written again
+    Lipogram(Option<String>),  // Leave out words containing any of some letters, or stop
+    Syllables(Option<usize>),  // Scatter words adding up to some syllables, or go back to density
+    Log,  // Show the recent lines of the session log
+    Seeds,  // List this session's seeds and the favorites to replay or mark
+    Open(String),  // Load a file or directory in place of the current corpus
+    Add(String),  // Load a file or directory into the current corpus
+    Prune,  // List near-duplicate words in the bank to merge or delete
+}
+
+impl Command {
+    /// Parse a prompt line such as `title Harbor Songs`
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let (name, argument) = input.split_once(' ').unwrap_or((input, ""));
+        let argument = argument.trim();
+
+        match name {
+            "title" => Ok(Self::Title((!argument.is_empty()).then(|| argument.to_string()))),
+            "epigraph" => match argument {
+                "off" => Ok(Self::EpigraphOff),
+                _ => Err("usage: epigraph off; press \" on a word to quote a sentence".to_string()),
+            },
+            "density" if argument == "auto" => Ok(Self::AutoDensity),
+            "density" => match argument.parse::<f32>() {
+                Ok(density) if (0.1..=6.0).contains(&density) => Ok(Self::Density(density)),
+                _ => Err(format!("density must be a number from 0.1 to 6.0, got '{}'", argument)),
+            },
+            "chaos" => match argument.parse::<f32>() {
+                Ok(chaos) if (0.0..=1.0).contains(&chaos) => Ok(Self::Chaos(chaos)),
+                _ => Err(format!("chaos must be a number from 0 to 1, got '{}'", argument)),
+            },
+            "sampling" => Sampling::parse(argument).map(Self::Sampling),
+            "seed" => Ok(Self::Seed),
+            "replay" => match argument.split_whitespace().collect::<Vec<_>>()[..] {
+                [seed, size] => {
+                    let (seed, size) = parse_replay(seed, size)?;
+                    Ok(Self::Replay(seed, size))
+                }
+                _ => Err("usage: replay SEED WxH".to_string()),
+            },
+            "deck" if !argument.is_empty() => Ok(Self::Deck(argument.to_string())),
+            "deck" => Err("usage: deck NAME".to_string()),
+            "inject" => Ok(Self::Inject((!argument.is_empty()).then(|| argument.to_string()))),
+            "poem" => Ok(Self::Poem),
+            "couplets" => Ok(Self::Couplets),
+            "import" => match argument {
+                "" => Err("usage: import PATH, or import off".to_string()),
+                "off" => Ok(Self::Import(None)),
+                _ => Ok(Self::Import(Some(argument.to_string()))),
+            },
+            "anthology" => Ok(Self::Anthology((!argument.is_empty()).then(|| argument.to_string()))),
+            "template" => match argument.split_once(' ') {
+                Some(("save", name)) if !name.trim().is_empty() => Ok(Self::SaveTemplate(name.trim().to_string())),
+                _ if argument == "save" => Err("usage: template save NAME".to_string()),
+                _ => Ok(Self::Template((!argument.is_empty()).then(|| argument.to_string()))),
+            },
+            "timer" => match argument {
+                "" => Ok(Self::Timer(None)),
+                "off" => Ok(Self::TimerOff),
+                _ => match argument.parse::<u32>() {
+                    Ok(minutes) if minutes > 0 => Ok(Self::Timer(Some(minutes))),
+                    _ => Err(format!("timer takes minutes to count down from, or off; got '{}'", argument)),
+                },
+            },
+            "transform" => match argument {
+                "" => Ok(Self::Transform(false)),
+                "all" => Ok(Self::Transform(true)),
+                _ => Err("usage: transform [all]".to_string()),
+            },
+            "spelling" => Spelling::parse(argument).map(Self::Spelling),
+            "lipogram" => match argument {
+                "" => Err("usage: lipogram LETTERS, or lipogram off".to_string()),
+                "off" => Ok(Self::Lipogram(None)),
+                _ => parse_lipogram(argument).map(|letters| Self::Lipogram(Some(letters))),
+            },
+            "syllables" => match argument {
+                "off" => Ok(Self::Syllables(None)),
+                _ => match argument.parse::<usize>() {
+                    Ok(budget) if budget > 0 => Ok(Self::Syllables(Some(budget))),
+                    _ => Err(format!("syllables takes a budget such as 60, or off; got '{}'", argument)),
+                },
+            },
+            "log" => Ok(Self::Log),
+            "seeds" => Ok(Self::Seeds),
+            "prune" => Ok(Self::Prune),
+            "open" if !argument.is_empty() => Ok(Self::Open(argument.to_string())),
+            "add" if !argument.is_empty() => Ok(Self::Add(argument.to_string())),
+            "open" | "add" => Err(format!("usage: {} PATH", name)),
+            "" => Err("no command given".to_string()),
+            _ => Err(format!("unknown command '{}'", name)),
+        }
+    }
+}
+
+/// A replay's seed and canvas size, e.g. `4127 80x24`
+pub fn parse_replay(seed: &str, size: &str) -> Result<(u64, (u16, u16)), String> {
+    let seed = seed.parse().map_err(|_| format!("invalid seed '{}'", seed))?;
+    Ok((seed, embed::parse_size(size)?))
+}
+
+/// Letters for a lipogram, such as `e` or `a, e`, lowercased and without repeats
+pub fn parse_lipogram(value: &str) -> Result<String, String> {
+    let mut letters = String::new();
+    for c in value.chars().filter(|c| !c.is_whitespace() && *c != ',') {
+        if !c.is_ascii_alphabetic() {
+            return Err(format!("a lipogram leaves out letters, got '{}'", c));
+        }
+        if !letters.contains(c.to_ascii_lowercase()) {
+            letters.push(c.to_ascii_lowercase());
+        }
+    }
+    if letters.is_empty() {
+        return Err("a lipogram needs at least one letter to leave out".to_string());
+    }
+    Ok(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::parse("title  Harbor Songs "), Ok(Command::Title(Some("Harbor Songs".to_string()))));
+        assert_eq!(Command::parse("title"), Ok(Command::Title(None)));
+        assert_eq!(Command::parse("epigraph off"), Ok(Command::EpigraphOff));
+        assert!(Command::parse("epigraph").is_err());
+        assert_eq!(Command::parse("density 3.2"), Ok(Command::Density(3.2)));
+        assert_eq!(Command::parse("density auto"), Ok(Command::AutoDensity));
+        assert!(Command::parse("density 7").is_err());
+        assert!(Command::parse("density lots").is_err());
+        assert_eq!(Command::parse("chaos 0.8"), Ok(Command::Chaos(0.8)));
+        assert_eq!(Command::parse("sampling rare"), Ok(Command::Sampling(Sampling::Rare)));
+        assert!(Command::parse("chaos 2").is_err());
+        assert_eq!(Command::parse("replay 42 80x24"), Ok(Command::Replay(42, (80, 24))));
+        assert!(Command::parse("replay 42").is_err());
+        assert_eq!(Command::parse("deck night"), Ok(Command::Deck("night".to_string())));
+        assert!(Command::parse("deck").is_err());
+        assert_eq!(Command::parse("inject"), Ok(Command::Inject(None)));
+        assert_eq!(Command::parse("poem"), Ok(Command::Poem));
+        assert_eq!(Command::parse("couplets"), Ok(Command::Couplets));
+        assert_eq!(Command::parse("anthology ~/poems/harbor.md"), Ok(Command::Anthology(Some("~/poems/harbor.md".to_string()))));
+        assert_eq!(Command::parse("anthology"), Ok(Command::Anthology(None)));
+        assert_eq!(Command::parse("import scatter-0003.json"), Ok(Command::Import(Some("scatter-0003.json".to_string()))));
+        assert_eq!(Command::parse("import off"), Ok(Command::Import(None)));
+        assert!(Command::parse("import").is_err());
+        assert_eq!(Command::parse("template save dunes"), Ok(Command::SaveTemplate("dunes".to_string())));
+        assert_eq!(Command::parse("template dunes"), Ok(Command::Template(Some("dunes".to_string()))));
+        assert!(Command::parse("template save").is_err());
+        assert_eq!(Command::parse("timer"), Ok(Command::Timer(None)));
+        assert_eq!(Command::parse("timer 25"), Ok(Command::Timer(Some(25))));
+        assert_eq!(Command::parse("timer off"), Ok(Command::TimerOff));
+        assert!(Command::parse("timer 0").is_err());
+        assert_eq!(Command::parse("transform"), Ok(Command::Transform(false)));
+        assert_eq!(Command::parse("transform all"), Ok(Command::Transform(true)));
+        assert_eq!(Command::parse("spelling phonetic"), Ok(Command::Spelling(Spelling::Phonetic)));
+        assert!(Command::parse("spelling").is_err());
+        assert_eq!(Command::parse("lipogram E, a e"), Ok(Command::Lipogram(Some("ea".to_string()))));
+        assert_eq!(Command::parse("lipogram off"), Ok(Command::Lipogram(None)));
+        assert!(Command::parse("lipogram 3").is_err());
+        assert_eq!(Command::parse("syllables 60"), Ok(Command::Syllables(Some(60))));
+        assert_eq!(Command::parse("syllables off"), Ok(Command::Syllables(None)));
+        assert!(Command::parse("syllables").is_err());
+        assert_eq!(Command::parse("log"), Ok(Command::Log));
+        assert_eq!(Command::parse("prune"), Ok(Command::Prune));
+        assert_eq!(Command::parse("seeds"), Ok(Command::Seeds));
+        assert_eq!(Command::parse("open ~/books/dune.epub"), Ok(Command::Open("~/books/dune.epub".to_string())));
+        assert_eq!(Command::parse("add notes"), Ok(Command::Add("notes".to_string())));
+        assert!(Command::parse("open").is_err());
+        assert!(Command::parse("colour red").is_err());
+        assert!(Command::parse("").is_err());
+    }
+}
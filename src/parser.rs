@@ -1,8 +1,38 @@
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use std::fs;
+use std::io::Read;
 use std::path::Path;
+use std::process::Command;
 
-pub fn parse_file(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+/// Where in its source file a word was found
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Location {
+    pub chapter: Option<usize>, // 1-based chapter for EPUBs (spine documents that contain text)
+    pub paragraph: usize,       // 1-based paragraph within the chapter, or within the file
+}
+
+impl Location {
+    pub fn describe(&self) -> String {
+        match self.chapter {
+            Some(chapter) => format!("chapter {}, paragraph {}", chapter, self.paragraph),
+            None => format!("paragraph {}", self.paragraph),
+        }
+    }
+}
+
+/// A word together with its position in the source
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedWord {
+    pub word: String,
+    pub location: Location,
+}
+
+/// Extensions the native parsers handle
+pub const NATIVE_EXTENSIONS: &[&str] = &["txt", "md", "markdown", "epub", "docx", "odt"];
+/// Extensions converted to plain text through pandoc when `--use-pandoc` is set
+pub const PANDOC_EXTENSIONS: &[&str] = &["rst", "textile"];
+
+pub fn parse_file(path: &Path) -> Result<Vec<ParsedWord>, Box<dyn std::error::Error>> {
     let extension = path
         .extension()
         .and_then(|s| s.to_str())
@@ -12,18 +42,52 @@ pub fn parse_file(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>
         Some("txt") => parse_txt(path),
         Some("md") | Some("markdown") => parse_markdown(path),
         Some("epub") => parse_epub(path),
+        Some(extension @ ("docx" | "odt")) => Ok(words_by_paragraph(&office_text(path, extension)?, None)),
         _ => Ok(Vec::new()),
     }
 }
 
-fn parse_txt(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+/// Whether a pandoc binary can be run from PATH
+pub fn pandoc_available() -> bool {
+    Command::new("pandoc")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Convert any pandoc-readable document to plain text and extract its words
+pub fn parse_with_pandoc(path: &Path) -> Result<Vec<ParsedWord>, Box<dyn std::error::Error>> {
+    let output = Command::new("pandoc")
+        .args(["--to", "plain", "--wrap", "none"])
+        .arg(path)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("pandoc failed: {}", stderr.trim()).into());
+    }
+
+    Ok(words_by_paragraph(&String::from_utf8_lossy(&output.stdout), None))
+}
+
+/// Words of text that isn't read from a file, such as a built-in corpus
+pub fn parse_text(text: &str) -> Vec<ParsedWord> {
+    words_by_paragraph(text, None)
+}
+
+fn parse_txt(path: &Path) -> Result<Vec<ParsedWord>, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
-    Ok(extract_words(&content))
+    Ok(words_by_paragraph(&content, None))
 }
 
-fn parse_markdown(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+fn parse_markdown(path: &Path) -> Result<Vec<ParsedWord>, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(path)?;
-    let parser = Parser::new(&content);
+    Ok(words_by_paragraph(&markdown_text(&content), None))
+}
+
+/// The prose of a Markdown document, without markup or code blocks, a blank line after each block
+fn markdown_text(content: &str) -> String {
+    let parser = Parser::new(content);
 
     let mut text_content = String::new();
     let mut in_code_block = false;
@@ -36,50 +100,263 @@ fn parse_markdown(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>
                 text_content.push_str(&text);
                 text_content.push(' ');
             }
+            // Blank line after each block so paragraphs can be told apart
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => {
+                text_content.push_str("\n\n");
+            }
             _ => {}
         }
     }
 
-    Ok(extract_words(&text_content))
+    text_content
 }
 
+/// An EPUB's title and author from its metadata, e.g. `Moby-Dick — Herman Melville`
+pub fn epub_title(path: &Path) -> Option<String> {
+    let doc = epub::doc::EpubDoc::new(path).ok()?;
+    let title = doc.mdata("title").map(|item| item.value.trim().to_string()).filter(|title| !title.is_empty())?;
+    match doc.mdata("creator").map(|item| item.value.trim()).filter(|author| !author.is_empty()) {
+        Some(author) => Some(format!("{} — {}", title, author)),
+        None => Some(title),
+    }
+}
+
+fn parse_epub(path: &Path) -> Result<Vec<ParsedWord>, Box<dyn std::error::Error>> {
+    let mut all_words = Vec::new();
+    let mut chapter = 0;
+
+    for text in epub_pages(path)? {
+        // Only documents with text count as chapters (skips covers and blank pages)
+        let words = words_by_paragraph(&text, Some(chapter + 1));
+        if !words.is_empty() {
+            chapter += 1;
+            all_words.extend(words);
+        }
+    }
+
+    Ok(all_words)
+}
+
+/// The text of each spine document of an EPUB, tags stripped
 //TODO: Update this when epub publishes latest git changes to crates.io
 #[allow(deprecated)]
-fn parse_epub(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+fn epub_pages(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let doc = epub::doc::EpubDoc::new(path)?;
-    let mut all_text = String::new();
+    let mut pages = Vec::new();
 
     for i in 0..doc.get_num_pages() {
         if let Ok(mut doc_copy) = epub::doc::EpubDoc::new(path) {
             doc_copy.set_current_page(i);
             if let Some((content, _)) = doc_copy.get_current_str() {
-                all_text.push_str(&strip_html_tags(&content));
-                all_text.push(' ');
+                pages.push(strip_html_tags(&content));
+            }
+        }
+    }
+
+    Ok(pages)
+}
+
+/// The text of a Word (`docx`) or OpenDocument (`odt`) file, read from the document XML inside its zip
+fn office_text(path: &Path, extension: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let (entry, paragraph_tags): (&str, &[&str]) = match extension {
+        "docx" => ("word/document.xml", &["w:p"]),
+        _ => ("content.xml", &["text:p", "text:h"]),
+    };
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut xml = String::new();
+    archive.by_name(entry)?.read_to_string(&mut xml)?;
+    Ok(document_xml_text(&xml, paragraph_tags))
+}
+
+/// Text of a document's XML with a blank line after each paragraph; tabs and line breaks become spaces,
+/// and field codes and deleted tracked changes are left out
+fn document_xml_text(xml: &str, paragraph_tags: &[&str]) -> String {
+    const SPACE_TAGS: &[&str] = &["w:tab", "w:br", "w:cr", "text:tab", "text:s", "text:line-break"];
+    const HIDDEN_TAGS: &[&str] = &["w:instrText", "w:delText"];
+
+    let mut result = String::new();
+    let mut hidden = false;
+    let mut rest = xml;
+    while let Some(open) = rest.find('<') {
+        if !hidden {
+            result.push_str(&decode_entities(&rest[..open]));
+        }
+        let Some(close) = rest[open..].find('>') else { break };
+        let tag = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/').split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or("");
+        if HIDDEN_TAGS.contains(&name) {
+            hidden = !closing && !tag.ends_with('/');
+        } else if closing && paragraph_tags.contains(&name) {
+            result.push_str("\n\n");
+        } else if SPACE_TAGS.contains(&name) {
+            result.push(' ');
+        }
+    }
+    result
+}
+
+/// Text with XML's character references and its five named entities written out
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let c = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|decimal| decimal.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
             }
         }
     }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The running text of a file the native parsers handle, for reading whole sentences
+pub fn read_text(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let extension = path.extension().and_then(|s| s.to_str()).map(|s| s.to_lowercase());
+    match extension.as_deref() {
+        Some("md") | Some("markdown") => Ok(markdown_text(&fs::read_to_string(path)?)),
+        Some("epub") => Ok(epub_pages(path)?.join("\n\n")),
+        Some(extension @ ("docx" | "odt")) => office_text(path, extension),
+        _ => Ok(fs::read_to_string(path)?),
+    }
+}
 
-    Ok(extract_words(&all_text))
+/// Sentences of `min_words` to `max_words` words, with line breaks and runs of spaces collapsed;
+/// a paragraph break ends a sentence too, so headings don't run into the text after them
+pub fn sentences(text: &str, min_words: usize, max_words: usize) -> Vec<String> {
+    let text = normalize_typography(text);
+    let mut sentences = Vec::new();
+    for paragraph in text.split("\n\n") {
+        let mut sentence: Vec<&str> = Vec::new();
+        for word in paragraph.split_whitespace() {
+            sentence.push(word);
+            let ends = word.trim_end_matches(['"', '\'', ')']).ends_with(['.', '!', '?']);
+            if ends {
+                if (min_words..=max_words).contains(&sentence.len()) {
+                    sentences.push(sentence.join(" "));
+                }
+                sentence.clear();
+            }
+        }
+    }
+    sentences
 }
 
+/// Strips tags, turning block-level closing tags into blank lines to keep paragraph breaks
 fn strip_html_tags(html: &str) -> String {
+    const BLOCK_TAGS: &[&str] = &[
+        "/p", "/div", "/li", "/blockquote", "/h1", "/h2", "/h3", "/h4", "/h5", "/h6", "br",
+    ];
+
     let mut result = String::new();
     let mut inside_tag = false;
+    let mut tag = String::new();
 
     for c in html.chars() {
         match c {
-            '<' => inside_tag = true,
-            '>' => inside_tag = false,
+            '<' => {
+                inside_tag = true;
+                tag.clear();
+            }
+            '>' => {
+                inside_tag = false;
+                let name = tag
+                    .split(|c: char| c.is_whitespace() || (c == '/' && !tag.starts_with('/')))
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                if BLOCK_TAGS.contains(&name.as_str()) {
+                    result.push_str("\n\n");
+                }
+            }
             _ if !inside_tag => result.push(c),
-            _ => {}
+            _ => tag.push(c),
         }
     }
 
     result
 }
 
-fn extract_words(text: &str) -> Vec<String> {
-    text.split_whitespace()
+/// Extract words, numbering paragraphs (runs of lines separated by blank lines) from 1
+fn words_by_paragraph(text: &str, chapter: Option<usize>) -> Vec<ParsedWord> {
+    let mut words = Vec::new();
+    let mut paragraph = 1;
+    let mut paragraph_has_words = false;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if paragraph_has_words {
+                paragraph += 1;
+                paragraph_has_words = false;
+            }
+            continue;
+        }
+
+        for word in extract_words(line) {
+            paragraph_has_words = true;
+            words.push(ParsedWord {
+                word,
+                location: Location { chapter, paragraph },
+            });
+        }
+    }
+
+    words
+}
+
+/// Plain equivalents of typographic variants, so `ﬁre`, `ｆｉｒｅ` and `fire` are one word
+/// and `don’t` matches `don't`
+fn normalize_typography(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            'ﬀ' => normalized.push_str("ff"),
+            'ﬁ' => normalized.push_str("fi"),
+            'ﬂ' => normalized.push_str("fl"),
+            'ﬃ' => normalized.push_str("ffi"),
+            'ﬄ' => normalized.push_str("ffl"),
+            'ﬅ' | 'ﬆ' => normalized.push_str("st"),
+            '‘' | '’' | '‚' | '‛' | '′' | 'ʼ' => normalized.push('\''),
+            '“' | '”' | '„' | '‟' | '″' => normalized.push('"'),
+            '‐' | '‑' => normalized.push('-'),
+            // Full-width ASCII forms sit at a fixed offset from the ASCII originals
+            '\u{FF01}'..='\u{FF5E}' => normalized.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)),
+            '\u{3000}' => normalized.push(' '),
+            // Soft hyphens and zero-width characters from typesetting split words invisibly
+            '\u{00AD}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' => {}
+            _ => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Lowercase words of a piece of text, as the corpus is split into them
+pub fn extract_words(text: &str) -> Vec<String> {
+    normalize_typography(text)
+        .split_whitespace()
         .map(|word| {
             word.trim_matches(|c: char| !c.is_alphanumeric())
                 .to_lowercase()
@@ -98,4 +375,46 @@ mod tests {
         let words = extract_words(text);
         assert_eq!(words, vec!["hello", "world", "this", "is", "a", "test"]);
     }
+
+    #[test]
+    fn test_typographic_variants_fold_together() {
+        let words = extract_words("ﬁre ｆｉｒｅ don’t “Quoted” hy\u{00AD}phen");
+        assert_eq!(words, vec!["fire", "fire", "don't", "quoted", "hyphen"]);
+    }
+
+    #[test]
+    fn test_sentences() {
+        let text = "Chapter One\n\nThe sea was calm.  It was\nvery calm indeed! \"Was it?\" Unfinished";
+        assert_eq!(sentences(text, 3, 10), vec!["The sea was calm.", "It was very calm indeed!"]);
+    }
+
+    #[test]
+    fn test_paragraph_locations() {
+        let words = words_by_paragraph("first line\nstill first\n\n\nsecond", Some(3));
+        assert_eq!(words[3].word, "first");
+        assert_eq!(words[3].location, Location { chapter: Some(3), paragraph: 1 });
+        assert_eq!(words[4].location.paragraph, 2);
+    }
+
+    #[test]
+    fn test_strip_html_keeps_paragraph_breaks() {
+        let text = strip_html_tags("<p class=\"a\">one</p><p>two<br/>three</p>");
+        let words = words_by_paragraph(&text, None);
+        let paragraphs: Vec<usize> = words.iter().map(|w| w.location.paragraph).collect();
+        assert_eq!(paragraphs, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_document_xml_text() {
+        let docx = "<?xml version=\"1.0\"?><w:document><w:body><w:p><w:r><w:t>Salt &amp; tide</w:t></w:r>\
+            <w:r><w:instrText> HYPERLINK \"x\" </w:instrText><w:tab/><w:t xml:space=\"preserve\">caf&#233;s</w:t></w:r></w:p>\
+            <w:p/><w:p><w:r><w:t>harbor</w:t></w:r></w:p></w:body></w:document>";
+        let words = words_by_paragraph(&document_xml_text(docx, &["w:p"]), None);
+        let found: Vec<(&str, usize)> = words.iter().map(|w| (w.word.as_str(), w.location.paragraph)).collect();
+        assert_eq!(found, vec![("salt", 1), ("tide", 1), ("cafés", 1), ("harbor", 2)]);
+
+        let odt = "<office:text><text:h>Night</text:h><text:p>one<text:s/>two<text:line-break/>three</text:p></office:text>";
+        assert_eq!(document_xml_text(odt, &["text:p", "text:h"]), "Night\n\none two three\n\n");
+        assert_eq!(decode_entities("a &lt;b&gt; &bogus; &#x2014; &"), "a <b> &bogus; — &");
+    }
 }
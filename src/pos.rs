@@ -0,0 +1,87 @@
+//! A rough part-of-speech guess from word lists and endings, enough to arrange a scatter by grammar.
+
+/// Word class as far as layout cares
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Other,  // Pronouns, prepositions, adverbs and other words with no place of their own
+}
+
+const FUNCTION_WORDS: &[&str] = &[
+    "about", "above", "across", "after", "again", "against", "along", "among", "around", "before",
+    "behind", "below", "beneath", "beside", "between", "beyond", "down", "during", "each", "either",
+    "ever", "every", "from", "here", "hers", "into", "itself", "many", "more", "much", "myself",
+    "neither", "never", "nor", "once", "ours", "over", "since", "such", "that", "thee", "their",
+    "them", "there", "these", "they", "thine", "this", "those", "thou", "though", "through", "thus",
+    "thy", "till", "toward", "towards", "under", "until", "upon", "very", "what", "when", "where",
+    "which", "while", "whom", "whose", "why", "with", "within", "without", "yet", "yours",
+];
+
+const VERBS: &[&str] = &[
+    "become", "break", "bring", "burn", "call", "came", "come", "dance", "die", "dream", "drink",
+    "fall", "feel", "find", "flew", "fly", "forget", "gave", "give", "goes", "grow", "hear", "hold",
+    "keep", "kept", "knew", "know", "lay", "leave", "lie", "live", "lose", "love", "made", "make",
+    "must", "remember", "ran", "rise", "run", "sang", "saw", "seem", "sing", "sleep", "speak",
+    "stand", "stood", "take", "tell", "think", "told", "took", "turn", "wait", "wake", "walk",
+    "wander", "watch", "went", "wept", "write", "wrote",
+];
+
+const ADJECTIVES: &[&str] = &[
+    "bitter", "black", "blue", "bright", "calm", "cold", "dark", "dead", "deep", "dim", "dry",
+    "empty", "fair", "false", "fine", "free", "full", "gentle", "golden", "gray", "great", "green",
+    "grey", "happy", "hard", "high", "holy", "hot", "late", "light", "little", "lone", "long",
+    "loud", "low", "mad", "old", "pale", "poor", "proud", "quick", "quiet", "rare", "red", "rich",
+    "sad", "sharp", "short", "silent", "slow", "small", "soft", "still", "strange", "strong",
+    "sweet", "tall", "tender", "true", "warm", "weak", "white", "wide", "wild", "wise", "young",
+];
+
+/// Words with a verb ending that are almost always nouns
+const NOUN_EXCEPTIONS: &[&str] = &[
+    "bed", "ceiling", "evening", "king", "morning", "nothing", "ring", "seed", "something", "spring",
+    "string", "thing", "wedding", "wing",
+];
+
+const ADJECTIVE_ENDINGS: &[&str] = &["ous", "ful", "less", "able", "ible", "ive", "ical", "ish"];
+const VERB_ENDINGS: &[&str] = &["ing", "ed", "ize", "ify"];
+
+/// Best guess at a word's part of speech; anything unrecognised counts as a noun, the most common case
+pub fn tag(word: &str) -> PartOfSpeech {
+    let word = word.to_lowercase();
+    let word = word.as_str();
+
+    if FUNCTION_WORDS.contains(&word) || (word.ends_with("ly") && word.len() > 4) {
+        PartOfSpeech::Other
+    } else if VERBS.contains(&word) {
+        PartOfSpeech::Verb
+    } else if ADJECTIVES.contains(&word) {
+        PartOfSpeech::Adjective
+    } else if NOUN_EXCEPTIONS.contains(&word) {
+        PartOfSpeech::Noun
+    } else if ADJECTIVE_ENDINGS.iter().any(|ending| word.len() > ending.len() + 2 && word.ends_with(ending)) {
+        PartOfSpeech::Adjective
+    } else if VERB_ENDINGS.iter().any(|ending| word.len() > ending.len() + 2 && word.ends_with(ending)) {
+        PartOfSpeech::Verb
+    } else {
+        PartOfSpeech::Noun
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_by_lists_and_endings() {
+        assert_eq!(tag("lantern"), PartOfSpeech::Noun);
+        assert_eq!(tag("morning"), PartOfSpeech::Noun);
+        assert_eq!(tag("Singing"), PartOfSpeech::Verb);
+        assert_eq!(tag("wandered"), PartOfSpeech::Verb);
+        assert_eq!(tag("sing"), PartOfSpeech::Verb);
+        assert_eq!(tag("luminous"), PartOfSpeech::Adjective);
+        assert_eq!(tag("pale"), PartOfSpeech::Adjective);
+        assert_eq!(tag("slowly"), PartOfSpeech::Other);
+        assert_eq!(tag("beneath"), PartOfSpeech::Other);
+    }
+}
@@ -0,0 +1,90 @@
+//! Constraint cards: a writing rule dealt at random to go with the scatter's words, from a built-in
+//! list or the user's own `prompts.txt` in the config directory.
+
+use crate::config;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fs;
+
+/// Stands for a small number, drawn afresh each time a card is dealt
+const NUMBER_PLACEHOLDER: &str = "{n}";
+const NUMBER_RANGE: std::ops::RangeInclusive<u32> = 3..=9;
+
+const BUILTIN_PROMPTS: &[&str] = &[
+    "Use exactly {n} words",
+    "Use no more than {n} words",
+    "Must include a color word",
+    "Must include a number",
+    "No words over 6 letters",
+    "Every word starts with a different letter",
+    "Begin with a verb",
+    "End on a question",
+    "Use no adjectives",
+    "Write it as an instruction",
+    "Address someone who isn't there",
+    "Two words must rhyme",
+    "Every line is {n} words long",
+    "Include a word from each corner of the canvas",
+    "Make it a title, not a sentence",
+    "Put the longest word last",
+    "Repeat one word {n} times",
+    "Write {n} lines, each shorter than the last",
+    "Include a place and a time",
+    "Say the opposite of what the words suggest",
+    "Use one word twice, with two meanings",
+    "Leave out the letter e",
+];
+
+/// Prompts from `prompts.txt` in the config directory, or the built-in list if there isn't one
+pub fn load() -> Vec<String> {
+    let custom = config::get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_to_string(dir.join("prompts.txt")).ok())
+        .map(|content| parse(&content))
+        .unwrap_or_default();
+
+    if custom.is_empty() {
+        BUILTIN_PROMPTS.iter().map(|prompt| prompt.to_string()).collect()
+    } else {
+        custom
+    }
+}
+
+/// One prompt per line; blank lines and `#` comments are skipped
+fn parse(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// A card from the list, as its place in the list and its text with `{n}` filled in, avoiding the card
+/// already showing when there's a choice
+pub fn deal<R: Rng>(rng: &mut R, prompts: &[String], showing: Option<usize>) -> Option<(usize, String)> {
+    let choices: Vec<usize> = (0..prompts.len()).filter(|&index| Some(index) != showing).collect();
+    let index = choices.choose(rng).copied().or(showing.filter(|&index| index < prompts.len()))?;
+    Some((index, prompts[index].replace(NUMBER_PLACEHOLDER, &rng.gen_range(NUMBER_RANGE).to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_deal_fills_in_numbers() {
+        let prompts = parse("# mine\nUse exactly {n} words\n\n  Begin with a verb  \n");
+        assert_eq!(prompts, vec!["Use exactly {n} words", "Begin with a verb"]);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let (index, card) = deal(&mut rng, &prompts, Some(1)).unwrap();
+        assert_eq!(index, 0);
+        let number: u32 = card.strip_prefix("Use exactly ").unwrap().strip_suffix(" words").unwrap().parse().unwrap();
+        assert!(NUMBER_RANGE.contains(&number));
+        assert_eq!(deal(&mut rng, &prompts[1..], Some(0)), Some((0, "Begin with a verb".to_string())));
+        assert!(deal(&mut rng, &[], None).is_none());
+    }
+}
@@ -0,0 +1,301 @@
+//! PNG snapshots: the canvas drawn onto a white image, letter by letter on the character grid, in the
+//! built-in 8x8 bitmap font or a TrueType/OpenType font named in settings.conf.
+
+use crate::export::{self, Decorations};
+use crate::scatters::ScatteredWord;
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use font8x8::UnicodeFonts;
+use std::path::Path;
+
+/// Font size PNG snapshots are drawn at unless settings.conf sets one, in pixels
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+
+/// Most pixels a PNG snapshot may have, about 190 MB of RGB while it's drawn
+const MAX_PIXELS: usize = 64_000_000;
+
+const WHITE: [u8; 3] = [255, 255, 255];
+const BLACK: [u8; 3] = [0, 0, 0];
+const WATERMARK_GREY: [u8; 3] = [200, 200, 200];
+const PATH_GREY: [u8; 3] = [153, 153, 153];
+
+/// The font PNG snapshots are drawn in, and its size in pixels
+pub struct Typeface {
+    font: Option<FontArc>,  // The built-in bitmap font when None
+    size: f32,
+}
+
+impl Default for Typeface {
+    fn default() -> Self {
+        Self { font: None, size: DEFAULT_FONT_SIZE }
+    }
+}
+
+impl Typeface {
+    /// The font file at `path` at `size` pixels, or the built-in font at that size
+    pub fn load(path: Option<&Path>, size: f32) -> Result<Self, String> {
+        let font = match path {
+            Some(path) => {
+                let data = std::fs::read(path).map_err(|e| format!("no font at {}: {}", path.display(), e))?;
+                let font = FontArc::try_from_vec(data)
+                    .map_err(|_| format!("{} isn't a TrueType or OpenType font", path.display()))?;
+                Some(font)
+            }
+            None => None,
+        };
+        Ok(Self { font, size })
+    }
+
+    /// The same font at another size
+    fn scaled(&self, factor: f32) -> Typeface {
+        Typeface { font: self.font.clone(), size: self.size * factor }
+    }
+
+    /// Pixels each bitmap font pixel takes: the 8x8 glyphs are only drawn at whole multiples
+    fn pixel_scale(&self) -> u32 {
+        ((self.size / 8.0).floor() as u32).max(1)
+    }
+
+    /// Width and height of a character cell, in whole pixels
+    fn cell(&self) -> (u32, u32) {
+        match &self.font {
+            Some(font) => {
+                let scaled = font.as_scaled(PxScale::from(self.size));
+                let width = scaled.h_advance(font.glyph_id('M'));
+                let height = scaled.ascent() - scaled.descent() + scaled.line_gap();
+                ((width.round() as u32).max(1), (height.round() as u32).max(1))
+            }
+            // Half again as tall as the glyph, so lines are spaced as on a terminal
+            None => (8 * self.pixel_scale(), 12 * self.pixel_scale()),
+        }
+    }
+
+    /// Draw `c` in the cell whose top left corner is at `left`, `top`
+    fn draw_char(&self, image: &mut Image, c: char, left: u32, top: u32, color: [u8; 3]) {
+        match &self.font {
+            Some(font) => {
+                let scaled = font.as_scaled(PxScale::from(self.size));
+                let position = ab_glyph::point(left as f32, top as f32 + scaled.ascent());
+                let glyph = font.glyph_id(c).with_scale_and_position(self.size, position);
+                if let Some(outline) = font.outline_glyph(glyph) {
+                    let bounds = outline.px_bounds();
+                    outline.draw(|x, y, coverage| {
+                        image.blend(bounds.min.x as i64 + x as i64, bounds.min.y as i64 + y as i64, color, coverage);
+                    });
+                }
+            }
+            None => {
+                let scale = self.pixel_scale();
+                let rows = bitmap_glyph(c);
+                for (row, bits) in rows.iter().enumerate() {
+                    for column in (0..8).filter(|column| bits & (1 << column) != 0) {
+                        let (x, y) = (left + column * scale, top + (2 + row as u32) * scale);
+                        image.fill(x, y, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_text(&self, image: &mut Image, text: &str, (left, top): (u32, u32), (column, row): (u16, u16), color: [u8; 3]) {
+        let (cell_width, cell_height) = self.cell();
+        for (offset, c) in text.chars().enumerate() {
+            let x = left + (column as u32 + offset as u32) * cell_width;
+            self.draw_char(image, c, x, top + row as u32 * cell_height, color);
+        }
+    }
+}
+
+/// The 8x8 rows of the built-in font's glyph for `c` (bit 0 leftmost), or a `?` for letters it lacks
+fn bitmap_glyph(c: char) -> [u8; 8] {
+    font8x8::BASIC_FONTS
+        .get(c)
+        .or_else(|| font8x8::LATIN_FONTS.get(c))
+        .or_else(|| font8x8::GREEK_FONTS.get(c))
+        .or_else(|| font8x8::BOX_FONTS.get(c))
+        .or_else(|| font8x8::BLOCK_FONTS.get(c))
+        .unwrap_or_else(|| font8x8::BASIC_FONTS.get('?').unwrap_or_default())
+}
+
+/// RGB pixels, row by row
+struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// A white image, or an error when it would have more than `MAX_PIXELS`
+    fn new(width: u32, height: u32) -> Result<Self, String> {
+        let pixels = (width as usize)
+            .checked_mul(height as usize)
+            .filter(|&pixels| pixels <= MAX_PIXELS)
+            .ok_or_else(|| format!("a {}x{} pixel PNG is too large; lower png_font_size or the canvas size", width, height))?;
+        Ok(Self { width, height, pixels: WHITE.repeat(pixels) })
+    }
+
+    /// Mix `color` into the pixel at `x`, `y` by `coverage` (0 to 1); pixels off the image are skipped
+    fn blend(&mut self, x: i64, y: i64, color: [u8; 3], coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let at = (y as usize * self.width as usize + x as usize) * 3;
+        let coverage = coverage.clamp(0.0, 1.0);
+        for (channel, target) in self.pixels[at..at + 3].iter_mut().zip(color) {
+            *channel = (*channel as f32 * (1.0 - coverage) + target as f32 * coverage).round() as u8;
+        }
+    }
+
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+        for y in y..y + height {
+            for x in x..x + width {
+                self.blend(x as i64, y as i64, color, 1.0);
+            }
+        }
+    }
+
+    /// A one pixel line between two points
+    fn line(&mut self, (x0, y0): (i64, i64), (x1, y1): (i64, i64), color: [u8; 3]) {
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1);
+        for step in 0..=steps {
+            let x = x0 + (x1 - x0) * step / steps;
+            let y = y0 + (y1 - y0) * step / steps;
+            self.blend(x, y, color, 1.0);
+        }
+    }
+
+    fn outline(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+        self.fill(x, y, width, 1, color);
+        self.fill(x, y + height.saturating_sub(1), width, 1, color);
+        self.fill(x, y, 1, height, color);
+        self.fill(x + width.saturating_sub(1), y, 1, height, color);
+    }
+
+    fn png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().expect("PNG header for a non-empty image");
+        writer.write_image_data(&self.pixels).expect("pixel data matches the image size");
+        writer.finish().expect("PNG written to memory");
+        bytes
+    }
+}
+
+/// The canvas as a PNG image: at the typeface's size, or scaled to fit a page preset's margins (turned to
+/// landscape for a wide canvas) and centred there, with the same extras as SVG snapshots; an image too
+/// big to allocate is an error
+pub fn render(words: &[ScatteredWord], decorations: &Decorations, width: u16, height: u16) -> Result<Vec<u8>, String> {
+    let default_typeface = Typeface::default();
+    let typeface = decorations.typeface.unwrap_or(&default_typeface);
+    let legend_rows = decorations.voices.as_ref().map_or(0, |_| 3);
+    let rows = height as u32 + legend_rows;
+    let drawing = |typeface: &Typeface| {
+        let (cell_width, cell_height) = typeface.cell();
+        (width as u32 * cell_width, rows * cell_height)
+    };
+
+    let (typeface, image_size) = match decorations.page {
+        None => (typeface.scaled(1.0), drawing(typeface)),
+        Some(page) => {
+            let (mut page_width, mut page_height, margin) = page.pixel_size();
+            let (drawing_width, drawing_height) = drawing(typeface);
+            if drawing_width > drawing_height {
+                std::mem::swap(&mut page_width, &mut page_height);
+            }
+            let factor = ((page_width - 2.0 * margin) / drawing_width.max(1) as f32)
+                .min((page_height - 2.0 * margin) / drawing_height.max(1) as f32);
+            (typeface.scaled(factor), (page_width.round() as u32, page_height.round() as u32))
+        }
+    };
+    let (cell_width, cell_height) = typeface.cell();
+    let (drawing_width, drawing_height) = drawing(&typeface);
+    let mut image = Image::new(image_size.0.max(1), image_size.1.max(1))?;
+    let origin = (image.width.saturating_sub(drawing_width) / 2, image.height.saturating_sub(drawing_height) / 2);
+
+    if decorations.frame.is_some() {
+        image.outline(origin.0, origin.1, width as u32 * cell_width, height as u32 * cell_height, BLACK);
+    }
+    for (x, y, line) in decorations.watermark.map(|sentence| export::watermark_lines(sentence, width, height)).unwrap_or_default() {
+        typeface.draw_text(&mut image, &line, origin, (x, y), WATERMARK_GREY);
+    }
+
+    let centres: Vec<(i64, i64)> = decorations
+        .path
+        .iter()
+        .filter_map(|&index| words.get(index))
+        .map(|word| {
+            let x = origin.0 as f32 + (word.x as f32 + word.word.chars().count() as f32 / 2.0) * cell_width as f32;
+            let y = origin.1 as f32 + (word.y as f32 + 0.5) * cell_height as f32;
+            (x as i64, y as i64)
+        })
+        .collect();
+    for pair in centres.windows(2) {
+        image.line(pair[0], pair[1], PATH_GREY);
+    }
+
+    for scattered in words {
+        let color = match (&decorations.voices, scattered.voice) {
+            (Some(voices), Some(voice)) => export::print_rgb(voices.style(voice)),
+            _ => None,
+        };
+        typeface.draw_text(&mut image, &scattered.word, origin, (scattered.x, scattered.y), color.unwrap_or(BLACK));
+    }
+    for (x, y, line) in decorations
+        .epigraph
+        .map(|epigraph| export::epigraph_lines(epigraph, width, height, decorations.title.is_some()))
+        .unwrap_or_default()
+    {
+        typeface.draw_text(&mut image, &line, origin, (x, y), BLACK);
+    }
+    if let Some(title) = decorations.title.filter(|_| height > 0) {
+        typeface.draw_text(&mut image, title, origin, export::title_origin(title, width, height), BLACK);
+    }
+    if let Some(voices) = &decorations.voices {
+        for (row, (name, style)) in voices.names.iter().zip(voices.styles).enumerate() {
+            let row = height + 1 + row as u16;
+            let swatch = (cell_width.min(cell_height) * 3 / 4).max(1);
+            let (x, y) = (origin.0, origin.1 + row as u32 * cell_height + (cell_height - swatch) / 2);
+            image.fill(x, y, swatch, swatch, export::print_rgb(style).unwrap_or(BLACK));
+            typeface.draw_text(&mut image, name, origin, (2, row), BLACK);
+        }
+    }
+
+    Ok(image.png())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::PagePreset;
+
+    fn word(word: &str, x: u16, y: u16) -> ScatteredWord {
+        ScatteredWord { word: word.to_string(), x, y, source_file: "test.txt".to_string(), voice: None }
+    }
+
+    /// Width and height from a PNG's header
+    fn png_size(png: &[u8]) -> (u32, u32) {
+        let number = |at: usize| u32::from_be_bytes([png[at], png[at + 1], png[at + 2], png[at + 3]]);
+        (number(16), number(20))
+    }
+
+    #[test]
+    fn test_png_sized_to_canvas_or_page() {
+        let words = vec![word("tide", 1, 0)];
+        let png = render(&words, &Decorations::default(), 10, 4).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(png_size(&png), (160, 96));
+
+        let typeface = Typeface { font: None, size: 8.0 };
+        let decorations = Decorations { typeface: Some(&typeface), ..Decorations::default() };
+        assert_eq!(png_size(&render(&words, &decorations, 10, 4).unwrap()), (80, 48));
+
+        let decorations = Decorations { page: Some(PagePreset::Square), ..Decorations::default() };
+        assert_eq!(png_size(&render(&words, &decorations, 10, 4).unwrap()), (1080, 1080));
+
+        let typeface = Typeface { font: None, size: 200.0 };
+        let decorations = Decorations { typeface: Some(&typeface), ..Decorations::default() };
+        assert!(render(&words, &decorations, 1000, 1000).is_err());
+        assert!(Typeface::load(Some(Path::new("/no/such/font.ttf")), 16.0).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! The route a reader takes through highlighted words, drawn with box-drawing characters, and the word
+//! a reader moving across the canvas in a direction comes to next.
+
+use crate::scatters::{ScatteredWord, DEFAULT_CELL_ASPECT};
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+/// Line characters connecting the words at `order` (indices into `words`) in sequence
+///
+/// Each step runs along the row of the first word, then turns down or up the column of the
+/// next, from word centre to word centre. Cells covered by words are left out so the words
+/// stay readable, and crossing segments join into the matching junction characters.
+pub fn path_cells(words: &[ScatteredWord], order: &[usize], width: u16, height: u16) -> Vec<(u16, u16, char)> {
+    let mut links = vec![0u8; width as usize * height as usize];
+    let cell = |x: u16, y: u16| y as usize * width as usize + x as usize;
+
+    let centre = |word: &ScatteredWord| (word.x + word.word.chars().count() as u16 / 2, word.y);
+    let stops: Vec<(u16, u16)> = order
+        .iter()
+        .filter_map(|&index| words.get(index).map(centre))
+        .filter(|&(x, y)| x < width && y < height)
+        .collect();
+
+    for step in stops.windows(2) {
+        let ((x1, y1), (x2, y2)) = (step[0], step[1]);
+
+        // Along the row from the first word to the corner under/over the second
+        let (left, right) = (x1.min(x2), x1.max(x2));
+        for x in left..right {
+            links[cell(x, y1)] |= RIGHT;
+            links[cell(x + 1, y1)] |= LEFT;
+        }
+
+        // Then along the column to the second word
+        let (top, bottom) = (y1.min(y2), y1.max(y2));
+        for y in top..bottom {
+            links[cell(x2, y)] |= DOWN;
+            links[cell(x2, y + 1)] |= UP;
+        }
+    }
+
+    let mut covered = vec![false; links.len()];
+    for word in words {
+        for offset in 0..word.word.chars().count() as u16 {
+            let x = word.x + offset;
+            if x < width && word.y < height {
+                covered[cell(x, word.y)] = true;
+            }
+        }
+    }
+
+    let mut cells = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let index = cell(x, y);
+            if covered[index] {
+                continue;
+            }
+            if let Some(symbol) = line_symbol(links[index]) {
+                cells.push((x, y, symbol));
+            }
+        }
+    }
+    cells
+}
+
+/// Box-drawing character joining the given directions
+fn line_symbol(links: u8) -> Option<char> {
+    let symbol = match links {
+        0 => return None,
+        _ if links == LEFT | RIGHT || links == LEFT || links == RIGHT => '─',
+        _ if links == UP | DOWN || links == UP || links == DOWN => '│',
+        _ if links == DOWN | RIGHT => '┌',
+        _ if links == DOWN | LEFT => '┐',
+        _ if links == UP | RIGHT => '└',
+        _ if links == UP | LEFT => '┘',
+        _ if links == UP | DOWN | RIGHT => '├',
+        _ if links == UP | DOWN | LEFT => '┤',
+        _ if links == LEFT | RIGHT | DOWN => '┬',
+        _ if links == LEFT | RIGHT | UP => '┴',
+        _ => '┼',
+    };
+    Some(symbol)
+}
+
+/// The word nearest `words[from]` in a direction, `(1, 0)` for right and `(0, 1)` for down, between word
+/// centres as they look on screen; words within 45 degrees of the direction come first, and distance off
+/// to the side counts double, so moving right keeps to the same row while there's a word along it
+pub fn nearest_in_direction(words: &[ScatteredWord], from: usize, (dx, dy): (i32, i32)) -> Option<usize> {
+    let centre = |word: &ScatteredWord| {
+        (word.x as f32 + word.word.chars().count() as f32 / 2.0, word.y as f32 * DEFAULT_CELL_ASPECT)
+    };
+    let (x0, y0) = centre(words.get(from)?);
+    words
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index != from)
+        .filter_map(|(index, word)| {
+            let (x, y) = centre(word);
+            let along = (x - x0) * dx as f32 + (y - y0) * dy as f32;
+            let across = ((x - x0) * dy as f32 - (y - y0) * dx as f32).abs();
+            (along > 0.0).then_some((index, across > along, along + 2.0 * across))
+        })
+        .min_by(|a, b| a.1.cmp(&b.1).then(a.2.total_cmp(&b.2)))
+        .map(|(index, _, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str, x: u16, y: u16) -> ScatteredWord {
+        ScatteredWord {
+            word: word.to_string(),
+            x,
+            y,
+            source_file: "test.txt".to_string(),
+            voice: None,
+        }
+    }
+
+    #[test]
+    fn test_path_turns_toward_next_word() {
+        // "ab" centred at x=1 on row 0, "cd" centred at x=6 on row 2
+        let words = vec![word("ab", 0, 0), word("cd", 5, 2)];
+        let cells = path_cells(&words, &[0, 1], 10, 4);
+
+        assert!(cells.contains(&(2, 0, '─')));
+        assert!(cells.contains(&(6, 0, '┐')));
+        assert!(cells.contains(&(6, 1, '│')));
+        // Word cells are never drawn over
+        assert!(!cells.iter().any(|&(x, y, _)| (y == 0 && x < 2) || (y == 2 && (5..7).contains(&x))));
+    }
+
+    #[test]
+    fn test_nearest_word_in_direction() {
+        let words = vec![word("tide", 10, 5), word("salt", 30, 5), word("moon", 12, 8), word("reef", 11, 1), word("gull", 2, 6)];
+        assert_eq!(nearest_in_direction(&words, 0, (1, 0)), Some(1));
+        assert_eq!(nearest_in_direction(&words, 0, (0, 1)), Some(2));
+        assert_eq!(nearest_in_direction(&words, 0, (0, -1)), Some(3));
+        assert_eq!(nearest_in_direction(&words, 0, (-1, 0)), Some(4));
+        assert_eq!(nearest_in_direction(&words, 1, (1, 0)), None);
+    }
+}
@@ -1,128 +1,1145 @@
-use rand::seq::SliceRandom;
-use rand::Rng;
+use crate::pos::{self, PartOfSpeech};
+use crate::spelling;
+use crate::stop_words;
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
 
+#[derive(Clone, PartialEq)]
 pub struct ScatteredWord {
     pub word: String,
     pub x: u16,
     pub y: u16,
     pub source_file: String,
+    pub voice: Option<Voice>, // Which corpus the word came from in dialogue mode
 }
 
+/// The result of one generation: the placed words and how many were asked for
+pub struct Scatter {
+    pub words: Vec<ScatteredWord>,
+    pub requested: usize, // Words drawn from the pool; more than were placed when the canvas filled up
+}
+
+/// One side of a two-corpus dialogue
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Voice {
+    A,
+    B,
+}
+
+impl Voice {
+    pub fn label(self) -> &'static str {
+        match self {
+            Voice::A => "a",
+            Voice::B => "b",
+        }
+    }
+}
+
+/// How the canvas is divided into zones that each have their own density
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ZoneLayout {
+    #[default]
+    Whole,
+    Rows,      // Top and bottom halves
+    Columns,   // Left and right halves
+    Quadrants,
+}
+
+impl ZoneLayout {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "whole" => Ok(Self::Whole),
+            "rows" => Ok(Self::Rows),
+            "columns" => Ok(Self::Columns),
+            "quadrants" => Ok(Self::Quadrants),
+            _ => Err(format!("unknown zone layout '{}' (expected whole, rows, columns or quadrants)", value)),
+        }
+    }
+
+    /// The layout the zone key switches to next
+    pub fn next(self) -> Self {
+        match self {
+            Self::Whole => Self::Rows,
+            Self::Rows => Self::Columns,
+            Self::Columns => Self::Quadrants,
+            Self::Quadrants => Self::Whole,
+        }
+    }
+
+    /// Arrow pointing at each zone, short enough for a sidebar title
+    pub fn zone_markers(self) -> &'static [&'static str] {
+        match self {
+            Self::Whole => &[""],
+            Self::Rows => &["↑", "↓"],
+            Self::Columns => &["←", "→"],
+            Self::Quadrants => &["↖", "↗", "↙", "↘"],
+        }
+    }
+
+    pub fn zone_count(self) -> usize {
+        self.zone_markers().len()
+    }
+
+    /// Zone rectangles covering a width x height canvas, in zone_markers() order
+    fn areas(self, width: u16, height: u16) -> Vec<Area> {
+        let (half_width, half_height) = (width / 2, height / 2);
+        let area = |x, y, width, height| Area { x, y, width, height };
+
+        match self {
+            Self::Whole => vec![area(0, 0, width, height)],
+            Self::Rows => vec![
+                area(0, 0, width, half_height),
+                area(0, half_height, width, height - half_height),
+            ],
+            Self::Columns => vec![
+                area(0, 0, half_width, height),
+                area(half_width, 0, width - half_width, height),
+            ],
+            Self::Quadrants => vec![
+                area(0, 0, half_width, half_height),
+                area(half_width, 0, width - half_width, half_height),
+                area(0, half_height, half_width, height - half_height),
+                area(half_width, half_height, width - half_width, height - half_height),
+            ],
+        }
+    }
+}
+
+/// Density a session starts at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StartDensity {
+    Fixed(f32),
+    Auto,  // Worked out from the size of the bank and the length of its words
+}
+
+impl StartDensity {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            _ => match value.parse::<f32>() {
+                Ok(density) if (0.1..=6.0).contains(&density) => Ok(Self::Fixed(density)),
+                _ => Err(format!("density must be auto or a number from 0.1 to 6.0, got '{}'", value)),
+            },
+        }
+    }
+}
+
+/// How a word's count in the corpus bears on how often it's drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Sampling {
+    #[default]
+    Chaos,     // Set by chaos: common words when orderly, rare words when unhinged
+    Even,      // Every word as likely as any other
+    Frequent,  // In proportion to its count, so an author's characteristic words come up most
+    Rare,      // In inverse proportion to its count, so one-off words come up most
+}
+
+impl Sampling {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "chaos" => Ok(Self::Chaos),
+            "even" => Ok(Self::Even),
+            "frequent" => Ok(Self::Frequent),
+            "rare" => Ok(Self::Rare),
+            _ => Err(format!("unknown sampling '{}' (expected chaos, even, frequent or rare)", value)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Chaos => "chaos",
+            Self::Even => "even",
+            Self::Frequent => "frequent",
+            Self::Rare => "rare",
+        }
+    }
+
+    /// Power a word's corpus count is raised to for its sampling weight
+    fn exponent(self, chaos: f32) -> f64 {
+        match self {
+            Self::Chaos => 1.0 - 2.0 * chaos as f64,
+            Self::Even => 0.0,
+            Self::Frequent => 1.0,
+            Self::Rare => -1.0,
+        }
+    }
+}
+
+/// Where words go by their part of speech
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Topology {
+    #[default]
+    Free,    // Anywhere
+    Centre,  // Nouns toward the middle, verbs and adjectives toward the edges
+    Rows,    // Nouns on even rows, verbs and adjectives on odd ones
+}
+
+impl Topology {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "free" => Ok(Self::Free),
+            "centre" | "center" => Ok(Self::Centre),
+            "rows" => Ok(Self::Rows),
+            _ => Err(format!("unknown topology '{}' (expected free, centre or rows)", value)),
+        }
+    }
+
+    /// Whether a word of `len` cells at (x, y) sits where its part of speech belongs, on a canvas whose
+    /// cells are `cell_aspect` times as tall as they are wide
+    fn suits(self, part: PartOfSpeech, x: u16, y: u16, len: u16, (width, height): (u16, u16), cell_aspect: f32) -> bool {
+        let content = matches!(part, PartOfSpeech::Verb | PartOfSpeech::Adjective);
+        match (self, part) {
+            (Self::Free, _) | (_, PartOfSpeech::Other) => true,
+            (Self::Centre, _) => {
+                // Distance of the word's centre from the canvas centre as it looks on screen, 1.0 at the
+                // middle of the nearest edges
+                let dx = x as f32 + len as f32 / 2.0 - width as f32 / 2.0;
+                let dy = (y as f32 + 0.5 - height as f32 / 2.0) * cell_aspect;
+                let radius = (width as f32 / 2.0).min(height as f32 / 2.0 * cell_aspect);
+                let distance = (dx * dx + dy * dy).sqrt() / radius;
+                if content { distance >= 0.6 } else { distance < 0.5 }
+            }
+            (Self::Rows, _) => (y % 2 == 1) == content,
+        }
+    }
+}
+
+/// A rectangle of the canvas, in canvas cells
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Area {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Area {
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Whether a word of `len` cells starting at (x, y) touches the area
+    pub fn touches_word(&self, x: u16, y: u16, len: u16) -> bool {
+        y >= self.y && y < self.y + self.height && x < self.x + self.width && x + len > self.x
+    }
+}
+
+/// Where a template puts a word: its first cell and how many cells it may take
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slot {
+    pub x: u16,
+    pub y: u16,
+    pub len: u16,
+}
+
+/// Empty bands kept along each canvas edge
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Margins {
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+    pub left: u16,
+}
+
+impl Margins {
+    /// Parse CSS-style margins: `ALL`, `VERTICAL,HORIZONTAL`, or `TOP,RIGHT,BOTTOM,LEFT`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let values: Vec<u16> = value
+            .split(',')
+            .map(|part| part.trim().parse().map_err(|_| format!("invalid margin '{}'", part.trim())))
+            .collect::<Result<_, _>>()?;
+
+        match values[..] {
+            [all] => Ok(Self { top: all, right: all, bottom: all, left: all }),
+            [vertical, horizontal] => Ok(Self { top: vertical, right: horizontal, bottom: vertical, left: horizontal }),
+            [top, right, bottom, left] => Ok(Self { top, right, bottom, left }),
+            _ => Err(format!("expected 1, 2 or 4 comma-separated margins, got '{}'", value)),
+        }
+    }
+}
+
+/// Areas of the canvas the generator must leave empty, and how crowded a row may get
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlacementRules {
+    pub margins: Margins,
+    pub corner_badge: Option<(u16, u16)>, // Width and height kept clear in each corner for titles/signatures
+    pub words_per_row: Option<u16>, // Most words any one row may hold, for a sparser vertical rhythm
+}
+
+/// Chaos at which sampling is uniform and placement plainly random
+pub const NEUTRAL_CHAOS: f32 = 0.5;
+/// Height of a typical terminal cell over its width
+pub const DEFAULT_CELL_ASPECT: f32 = 2.0;
+/// Widest and tallest canvas, in cells, that can be asked for
+pub const MAX_CANVAS: u16 = 1000;
+
+/// Drawable words at which weighted draws switch to looking words up in running weight totals,
+/// rather than weighing the whole pool on every reroll
+const LARGE_POOL: usize = 50_000;
+
+/// The words of one voice that scatters can be drawn from, worked out when the words or settings change
+/// instead of on every reroll, so a reroll on a huge bank only touches the words it draws
+#[derive(Clone)]
+struct VoicePool {
+    candidates: Vec<usize>,  // word_pool() indices of the words that are active and not left out
+    running_weights: Option<Vec<f64>>,  // Running total of the candidates' weights, for weighted draws on large pools
+    weighted_count: usize,  // Candidates with a weight above 0
+}
+
+impl VoicePool {
+    fn new(generator: &ScattersGenerator, indices: std::ops::Range<usize>) -> Self {
+        let candidates: Vec<usize> = indices.filter(|&index| generator.is_active(index)).collect();
+        let mut weighted_count = 0;
+        let running_weights = (generator.is_weighted() && candidates.len() >= LARGE_POOL).then(|| {
+            let mut total = 0.0;
+            candidates
+                .iter()
+                .map(|&index| {
+                    let weight = generator.weight(index);
+                    if weight > 0.0 {
+                        weighted_count += 1;
+                        total += weight;
+                    }
+                    total
+                })
+                .collect()
+        });
+        Self { candidates, running_weights, weighted_count }
+    }
+
+    /// Draw `count` distinct candidates (as positions in `candidates`) by weight, each draw a search of the
+    /// running totals; None when the pool is small or the draw would take most of the weighted words, which
+    /// are left to a draw over the whole pool
+    fn draw_large<R: Rng>(&self, rng: &mut R, count: usize) -> Option<Vec<usize>> {
+        let running_weights = self.running_weights.as_ref()?;
+        let total = *running_weights.last()?;
+        if count * 2 > self.weighted_count {
+            return None;
+        }
+        let mut drawn = HashSet::with_capacity(count);
+        let mut picks = Vec::with_capacity(count);
+        while picks.len() < count {
+            let target = rng.gen::<f64>() * total;
+            let pick = running_weights.partition_point(|&sum| sum <= target);
+            if pick < running_weights.len() && drawn.insert(pick) {
+                picks.push(pick);
+            }
+        }
+        Some(picks)
+    }
+}
+
+/// Everything sized by the bank is behind an `Arc`, so a clone handed to a worker thread for a reroll
+/// shares it instead of copying it
+#[derive(Clone)]
 pub struct ScattersGenerator {
-    word_pool: Vec<(String, String)>, // Vec of (word, source_file_path)
+    word_pool: Arc<Vec<(String, String)>>, // Vec of (word, source_file_path)
+    second_voice: Option<Arc<Vec<(String, String)>>>, // Voice B's pool in dialogue mode (word_pool is voice A)
+    weights: Option<Arc<Vec<f64>>>, // Sampling weight for each word_pool() entry; uniform when None
+    source_weights: HashMap<String, f64>, // Extra weight factor per source file, 1 when missing
+    active: Option<Arc<Vec<bool>>>, // Which word_pool() entries are in the session's active slice; all when None
+    stop_words: HashSet<String>, // Words never drawn
+    keep_numerals: bool, // Draw Roman numerals and chapter-heading words too
+    lipogram: Vec<char>, // Letters no drawn word may contain
+    syllable_budget: Option<usize>, // Syllables each scatter adds up to, in place of a density-based word count
+    min_sources: usize, // Distinct source files each scatter should draw from, when the pool has them
+    kept_words: Vec<(String, String, Option<Voice>)>, // Words in every scatter, at new positions each time
+    pinned_words: Vec<ScatteredWord>, // Words in every scatter where they stand, such as an imported poem
+    topology: Topology,
+    cell_aspect: f32, // Cell height over width, so distances are measured as they look on screen
+    chaos: f32, // 0 orderly (common words, even grid) to 1 unhinged (rare words, clumps)
+    frequencies: Arc<HashMap<String, usize>>, // Corpus count of each word, for frequency-weighted sampling
+    sampling: Sampling,
+    rules: PlacementRules,
+    exclusions: Vec<Area>, // User-drawn regions that stay empty
+    pool: OnceLock<Arc<Vec<VoicePool>>>, // Drawable words per voice, worked out again after any change to them
 }
 
 impl ScattersGenerator {
     pub fn new(words: Vec<(String, String)>) -> Self {
-        Self { word_pool: words }
+        Self {
+            word_pool: Arc::new(words),
+            second_voice: None,
+            weights: None,
+            source_weights: HashMap::new(),
+            active: None,
+            stop_words: HashSet::new(),
+            keep_numerals: false,
+            lipogram: Vec::new(),
+            syllable_budget: None,
+            min_sources: 0,
+            kept_words: Vec::new(),
+            pinned_words: Vec::new(),
+            topology: Topology::Free,
+            cell_aspect: DEFAULT_CELL_ASPECT,
+            chaos: NEUTRAL_CHAOS,
+            frequencies: Arc::default(),
+            sampling: Sampling::Chaos,
+            rules: PlacementRules::default(),
+            exclusions: Vec::new(),
+            pool: OnceLock::new(),
+        }
     }
 
-    pub fn generate_with_density(&self, width: u16, height: u16, density: f32) -> Vec<ScatteredWord> {
-        let mut rng = rand::thread_rng();
+    /// Generator that alternates between two corpora, tagging each word with its voice
+    pub fn with_dialogue(voice_a: Vec<(String, String)>, voice_b: Vec<(String, String)>) -> Self {
+        Self {
+            word_pool: Arc::new(voice_a),
+            second_voice: Some(Arc::new(voice_b)),
+            weights: None,
+            source_weights: HashMap::new(),
+            active: None,
+            stop_words: HashSet::new(),
+            keep_numerals: false,
+            lipogram: Vec::new(),
+            syllable_budget: None,
+            min_sources: 0,
+            kept_words: Vec::new(),
+            pinned_words: Vec::new(),
+            topology: Topology::Free,
+            cell_aspect: DEFAULT_CELL_ASPECT,
+            chaos: NEUTRAL_CHAOS,
+            frequencies: Arc::default(),
+            sampling: Sampling::Chaos,
+            rules: PlacementRules::default(),
+            exclusions: Vec::new(),
+            pool: OnceLock::new(),
+        }
+    }
+
+    pub fn set_placement_rules(&mut self, rules: PlacementRules) {
+        self.rules = rules;
+    }
+
+    /// Regions of the canvas no word may touch, kept until replaced
+    pub fn set_exclusions(&mut self, exclusions: Vec<Area>) {
+        self.exclusions = exclusions;
+    }
+
+    /// Words to leave out of every scatter, kept until replaced
+    pub fn set_stop_words(&mut self, stop_words: impl IntoIterator<Item = String>) {
+        self.stop_words = stop_words.into_iter().collect();
+        self.pool = OnceLock::new();
+    }
+
+    /// Make every scatter draw from at least this many source files (per voice in dialogue mode)
+    pub fn set_min_sources(&mut self, min_sources: usize) {
+        self.min_sources = min_sources;
+    }
+
+    /// Words to put in every scatter from now on at their own positions, with fresh words placed around
+    /// them; any that don't fit on the canvas are left out
+    pub fn set_pinned_words(&mut self, pinned_words: Vec<ScatteredWord>) {
+        self.pinned_words = pinned_words;
+    }
+
+    /// Words to include in every scatter from now on, placed afresh each time
+    pub fn set_kept_words(&mut self, kept_words: Vec<(String, String, Option<Voice>)>) {
+        self.kept_words = kept_words;
+    }
+
+    /// Set how far scatters stray from order, rounded to a tenth: below NEUTRAL_CHAOS favours common words
+    /// on an even grid, above it rare words in clumps
+    pub fn set_chaos(&mut self, chaos: f32) {
+        self.chaos = (chaos.clamp(0.0, 1.0) * 10.0).round() / 10.0;
+        self.pool = OnceLock::new();
+    }
+
+    pub fn chaos(&self) -> f32 {
+        self.chaos
+    }
+
+    /// How often each word occurs in the corpus, which sampling is weighted by
+    pub fn set_frequencies(&mut self, frequencies: HashMap<String, usize>) {
+        self.frequencies = Arc::new(frequencies);
+        self.pool = OnceLock::new();
+    }
+
+    /// Weigh words by their corpus count from now on, or leave it to chaos
+    pub fn set_sampling(&mut self, sampling: Sampling) {
+        self.sampling = sampling;
+        self.pool = OnceLock::new();
+    }
+
+    /// Arrange words by part of speech from now on, as far as free space allows
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    /// Cell height over width; 1 measures distances in plain cells, as if they were square. Only the
+    /// orderly grid below neutral chaos, clumps above it and `Topology::Centre` measure distances
+    pub fn set_cell_aspect(&mut self, cell_aspect: f32) {
+        self.cell_aspect = cell_aspect;
+    }
+
+    /// Let Roman numerals and words like `chapter` into scatters instead of leaving them out
+    pub fn set_keep_numerals(&mut self, keep_numerals: bool) {
+        self.keep_numerals = keep_numerals;
+        self.pool = OnceLock::new();
+    }
+
+    /// Add words to the pool, voice A's in dialogue mode; word weights and the active slice were set per
+    /// word, so they're cleared
+    pub fn add_words(&mut self, words: Vec<(String, String)>) {
+        Arc::make_mut(&mut self.word_pool).extend(words);
+        self.weights = None;
+        self.active = None;
+        self.pool = OnceLock::new();
+    }
+
+    /// Draw from other words from now on, as a single voice; word weights and the active slice are cleared
+    pub fn set_words(&mut self, words: Vec<(String, String)>) {
+        self.word_pool = Arc::new(words);
+        self.second_voice = None;
+        self.weights = None;
+        self.active = None;
+        self.pool = OnceLock::new();
+    }
+
+    /// Leave out every word containing one of `letters`, for lipograms; none leaves nothing out
+    pub fn set_lipogram(&mut self, letters: &str) {
+        self.lipogram = letters.chars().collect();
+        self.pool = OnceLock::new();
+    }
+
+    /// Draw words adding up to a number of syllables instead of a number set by density, or go back to density
+    pub fn set_syllable_budget(&mut self, budget: Option<usize>) {
+        self.syllable_budget = budget;
+    }
+
+    /// Whether a word is never drawn: a stop word, a word breaking the lipogram, or heading noise unless
+    /// numerals are kept
+    pub fn is_left_out(&self, word: &str) -> bool {
+        self.stop_words.contains(word)
+            || word.chars().any(|c| self.lipogram.iter().any(|letter| letter.eq_ignore_ascii_case(&c)))
+            || (!self.keep_numerals && stop_words::is_heading_word(word))
+    }
+
+    /// Weight each (word, source_file) entry; words with weight 0 are only drawn once everything else is used
+    pub fn set_weights(&mut self, weight: impl Fn(&str, &str) -> f64) {
+        self.weights = Some(Arc::new(self.word_pool().map(|(word, source)| weight(word, source)).collect()));
+        self.pool = OnceLock::new();
+    }
+
+    /// Scale the weight of every word from a source file; 0 holds its words back until everything else is used
+    pub fn set_source_weights(&mut self, source_weights: HashMap<String, f64>) {
+        self.source_weights = source_weights;
+        self.pool = OnceLock::new();
+    }
+
+    fn is_weighted(&self) -> bool {
+        self.weights.is_some() || !self.source_weights.is_empty() || self.sampling.exponent(self.chaos) != 0.0
+    }
+
+    /// Sampling weight of a word_pool() entry, combining the word weights, its source's weight, and its
+    /// corpus frequency raised to a power set by the sampling: 1 for frequent, -1 for rare, and under
+    /// chaos 1 when orderly, 0 at neutral and -1 when unhinged
+    fn weight(&self, index: usize) -> f64 {
+        let (word, source) = self.entry(index);
+        let word_weight = self.weights.as_ref().map_or(1.0, |weights| weights[index]);
+        let exponent = self.sampling.exponent(self.chaos);
+        let frequency_weight = match self.frequencies.get(word) {
+            Some(&count) if exponent != 0.0 => (count.max(1) as f64).powf(exponent),
+            _ => 1.0,
+        };
+        word_weight * frequency_weight * self.source_weights.get(source).copied().unwrap_or(1.0)
+    }
+
+    /// Every word the generator can draw from, across both voices in dialogue mode
+    pub fn word_pool(&self) -> impl Iterator<Item = &(String, String)> {
+        self.word_pool.iter().chain(self.second_voice.as_deref().into_iter().flatten())
+    }
+
+    fn pool_size(&self) -> usize {
+        self.word_pool.len() + self.second_voice.as_ref().map_or(0, |voice| voice.len())
+    }
+
+    /// Entry at an index into word_pool()
+    fn entry(&self, index: usize) -> &(String, String) {
+        match index.checked_sub(self.word_pool.len()) {
+            None => &self.word_pool[index],
+            Some(offset) => &self.second_voice.as_ref().expect("index within word_pool()")[offset],
+        }
+    }
+
+    fn is_active(&self, index: usize) -> bool {
+        self.active.as_ref().is_none_or(|active| active[index]) && !self.is_left_out(&self.entry(index).0)
+    }
 
-        // Calculate word count based on canvas area
-        // Use roughly 1 word per 40 character cells (width * height / 40)
-        // Add randomization so each reroll uses a different count
-        let canvas_area = (width as usize).saturating_mul(height as usize);
+    /// Limit generation to a fresh random slice holding `fraction` (0-1] of the pool
+    pub fn resample_active<R: Rng>(&mut self, rng: &mut R, fraction: f64) {
+        let total = self.pool_size();
+        let size = ((total as f64 * fraction).ceil() as usize).min(total);
+
+        let mut active = vec![false; total];
+        for index in index::sample(rng, total, size) {
+            active[index] = true;
+        }
+        self.active = Some(Arc::new(active));
+        self.pool = OnceLock::new();
+    }
+
+    /// Draw from the whole pool again
+    pub fn clear_active(&mut self) {
+        self.active = None;
+        self.pool = OnceLock::new();
+    }
+
+    /// Words generation can currently draw from
+    pub fn active_count(&self) -> usize {
+        self.pool().iter().map(|voice| voice.candidates.len()).sum()
+    }
+
+    /// Work out the drawable words now if a change reset them, so clones made for worker threads share
+    /// them rather than each working them out again
+    pub fn warm_pool(&self) {
+        self.pool();
+    }
+
+    /// The drawable words of each voice, voice A's first
+    fn pool(&self) -> &[VoicePool] {
+        self.pool.get_or_init(|| {
+            let voice_a_len = self.word_pool.len();
+            let mut voices = vec![VoicePool::new(self, 0..voice_a_len)];
+            if self.second_voice.is_some() {
+                voices.push(VoicePool::new(self, voice_a_len..self.pool_size()));
+            }
+            Arc::new(voices)
+        })
+    }
+
+    /// Words in the pool that aren't left out, whether or not they're in the active slice
+    pub fn usable_count(&self) -> usize {
+        self.word_pool().filter(|(word, _)| !self.is_left_out(word)).count()
+    }
+
+    /// A density to start at for this bank: a quarter more for every tenfold fewer usable words than
+    /// 2,000 and a quarter less for every tenfold more, so a small bank fills the canvas and a huge one
+    /// doesn't paper over it, then scaled down for long words and up for short ones against 6 letters
+    pub fn auto_density(&self) -> f32 {
+        let (count, letters) = self
+            .word_pool()
+            .filter(|(word, _)| !self.is_left_out(word))
+            .fold((0, 0), |(count, letters), (word, _)| (count + 1, letters + word.chars().count()));
+        if count == 0 {
+            return 1.0;
+        }
+        let for_size = 1.0 - 0.25 * (count as f32 / 2000.0).log10();
+        let for_length = (6.0 * count as f32 / letters as f32).clamp(0.5, 1.5);
+        (for_size * for_length).clamp(0.25, 4.0)
+    }
+
+    pub fn generate_with_density(&self, width: u16, height: u16, density: f32) -> Scatter {
+        self.generate_with_rng(&mut rand::thread_rng(), width, height, density)
+    }
+
+    /// Generate from `seed`: the same words, seed and canvas size always give the same scatter
+    pub fn generate_with_seed(&self, seed: u64, width: u16, height: u16, density: f32) -> Scatter {
+        self.generate_with_rng(&mut StdRng::seed_from_u64(seed), width, height, density)
+    }
+
+    /// Generate using the given RNG, so callers can supply a seeded one for reproducible layouts
+    pub fn generate_with_rng<R: Rng>(
+        &self,
+        rng: &mut R,
+        width: u16,
+        height: u16,
+        density: f32,
+    ) -> Scatter {
+        self.generate_zoned_with_rng(rng, width, height, ZoneLayout::Whole, &[density])
+    }
+
+    /// Generate with the canvas split into zones, each filled at its own density
+    pub fn generate_zoned_with_rng<R: Rng>(
+        &self,
+        rng: &mut R,
+        width: u16,
+        height: u16,
+        layout: ZoneLayout,
+        densities: &[f32],
+    ) -> Scatter {
+        let zones: Vec<(Area, usize)> = layout
+            .areas(width, height)
+            .into_iter()
+            .zip(densities)
+            .map(|(area, &density)| (area, self.count_for_density(rng, area, density)))
+            .collect();
+
+        let total = zones.iter().map(|(_, count)| count).sum();
+        let Some(budget) = self.syllable_budget else {
+            let selected_words = self.select_with_kept_words(rng, total);
+            return self.place_words(rng, width, height, selected_words, &zones);
+        };
+
+        // The budget decides how many words there are; the zones share them as their densities would
+        let selected_words = self.select_for_syllables(rng, budget);
+        let mut remaining = selected_words.len();
+        let zones: Vec<(Area, usize)> = zones
+            .iter()
+            .enumerate()
+            .map(|(index, &(area, count))| {
+                let share = if index + 1 == zones.len() {
+                    remaining
+                } else {
+                    (selected_words.len() * count).checked_div(total).unwrap_or(0).min(remaining)
+                };
+                remaining -= share;
+                (area, share)
+            })
+            .collect();
+        self.place_words(rng, width, height, selected_words, &zones)
+    }
+
+    /// How many words to place in an area: roughly 1 per 40 cells at density 1.0,
+    /// randomized so each reroll uses a different count
+    fn count_for_density<R: Rng>(&self, rng: &mut R, area: Area, density: f32) -> usize {
+        let canvas_area = (area.width as usize).saturating_mul(area.height as usize);
         let base_count = ((canvas_area as f32 / 40.0) * density) as usize;
         let base_count = base_count.max(2);
 
         let min_count = (base_count * 70 / 100).max(2);
-        let max_count = (base_count * 130 / 100).min(self.word_pool.len());
+        let max_count = (base_count * 130 / 100).min(self.active_count());
 
-        let count = if min_count < max_count {
+        if min_count < max_count {
             rng.gen_range(min_count..=max_count)
         } else {
-            min_count.min(self.word_pool.len())
-        };
+            min_count.min(self.active_count())
+        }
+    }
 
-        let mut selected_words: Vec<(String, String)> = self
-            .word_pool
-            .choose_multiple(&mut rng, count)
-            .cloned()
-            .collect();
+    /// Place up to `count` words without overlaps; when the canvas fills up, the rest are left out
+    pub fn generate_count_with_rng<R: Rng>(
+        &self,
+        rng: &mut R,
+        width: u16,
+        height: u16,
+        count: usize,
+    ) -> Scatter {
+        let selected_words = self.select_with_kept_words(rng, count);
+        let whole = Area { x: 0, y: 0, width, height };
+        self.place_words(rng, width, height, selected_words, &[(whole, count)])
+    }
 
-        selected_words.shuffle(&mut rng);
+    /// Fill a template's slots with fresh words, centring each in the longest slot-sized or shorter word
+    /// drawn for it; kept words take the tightest slots they fit first
+    pub fn fill_template_with_rng<R: Rng>(&self, rng: &mut R, width: u16, height: u16, slots: &[Slot]) -> Scatter {
+        let mut open = slots.to_vec();
+        let mut filled = Vec::new();
 
-        let usable_width = width;
-        let usable_height = height;
+        for kept in &self.kept_words {
+            let tightest = (0..open.len())
+                .filter(|&i| open[i].len as usize >= kept.0.chars().count())
+                .min_by_key(|&i| open[i].len);
+            if let Some(i) = tightest {
+                filled.push((open.swap_remove(i), kept.clone()));
+            }
+        }
 
-        let mut scattered_words = Vec::new();
-        let mut occupied_positions = Vec::new();
+        // Longest slots choose first, so short words don't use up the room long ones need
+        let mut candidates = self.select_words(rng, open.len() * TEMPLATE_CANDIDATES);
+        candidates.retain(|(word, _, _)| !self.kept_words.iter().any(|(kept, _, _)| kept == word));
+        open.sort_by_key(|slot| std::cmp::Reverse(slot.len));
+        for slot in open {
+            let longest = (0..candidates.len())
+                .filter(|&i| candidates[i].0.chars().count() <= slot.len as usize)
+                .max_by_key(|&i| candidates[i].0.chars().count());
+            if let Some(i) = longest {
+                filled.push((slot, candidates.swap_remove(i)));
+            }
+        }
 
-        for (word, source_file) in selected_words.iter() {
-            let mut attempts = 0;
-            let max_attempts = 100;
-            let mut placed = false;
+        let mut occupancy = Occupancy::new(width, height);
+        let mut words = Vec::new();
+        self.place_pinned_words(&mut occupancy, &mut words, width, height);
+        for (slot, (word, source_file, voice)) in filled {
+            let len = word.chars().count() as u16;
+            let x = slot.x + (slot.len - len) / 2;
+            if slot.y < height && x as usize + len as usize <= width as usize && occupancy.is_free(x, slot.y, len) {
+                occupancy.place(x, slot.y, len);
+                words.push(ScatteredWord { word, x, y: slot.y, source_file, voice });
+            }
+        }
 
-            while attempts < max_attempts {
-                let max_x = usable_width.saturating_sub(word.len() as u16);
+        Scatter { words, requested: slots.len() }
+    }
+
+    /// Place the selected words in order, filling each zone with its share before moving to the next
+    fn place_words<R: Rng>(
+        &self,
+        rng: &mut R,
+        width: u16,
+        height: u16,
+        selected_words: Vec<(String, String, Option<Voice>)>,
+        zones: &[(Area, usize)],
+    ) -> Scatter {
+        let requested = selected_words.len();
+        let mut words = Vec::new();
 
-                if max_x == 0 {
-                    break;
+        let mut occupancy = Occupancy::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if self.in_corner_badge(x, y, 1, width, height) || self.exclusions.iter().any(|area| area.contains(x, y)) {
+                    occupancy.reserve(x, y);
                 }
+            }
+        }
+        self.place_pinned_words(&mut occupancy, &mut words, width, height);
+
+        // Words on each row so far, against the words-per-row cap
+        let mut row_words = vec![0; height as usize];
+        for word in &words {
+            row_words[word.y as usize] += 1;
+        }
+        let cap = self.rules.words_per_row.unwrap_or(u16::MAX);
 
-                let x = rng.gen_range(0..=max_x);
-                let y = rng.gen_range(0..usable_height);
-
-                if !is_overlapping_tight(x, y, word, &occupied_positions) {
-                    occupied_positions.push((x, y, word.len() as u16));
-                    scattered_words.push(ScatteredWord {
-                        word: word.clone(),
-                        x,
-                        y,
-                        source_file: source_file.clone(),
-                    });
-                    placed = true;
-                    break;
+        let margins = self.rules.margins;
+        let mut selected_words = selected_words.into_iter();
+
+        for &(area, count) in zones {
+            // The zone, trimmed by the canvas margins
+            let min_x = area.x.max(margins.left);
+            let right = (area.x + area.width).min(width.saturating_sub(margins.right)); // Exclusive
+            let min_y = area.y.max(margins.top);
+            let max_y = (area.y + area.height).min(height.saturating_sub(margins.bottom)); // Exclusive
+
+            // Once no spot is left for a word, nothing at least as long will fit either
+            let mut shortest_unplaceable = u16::MAX;
+
+            // Orderly scatters aim each word at its own cell of an even grid, loosening with chaos
+            let (targets, cell_reach) = if self.chaos < NEUTRAL_CHAOS && right > min_x && max_y > min_y {
+                let (targets, (half_width, half_height)) = grid_targets(rng, (min_x, right), (min_y, max_y), count, self.cell_aspect);
+                let spread = self.chaos / NEUTRAL_CHAOS;
+                (targets, (half_width * spread, half_height * spread))
+            } else {
+                (Vec::new(), (0.0, 0.0))
+            };
+
+            for (slot, (word, source_file, voice)) in selected_words.by_ref().take(count).enumerate() {
+                let word_len = word.chars().count() as u16;
+
+                // Word is too long for the space between the side margins, or the zone is all margin
+                if word_len >= shortest_unplaceable || right < min_x + word_len || min_y >= max_y {
+                    continue;
+                }
+                let max_x = right - word_len; // Inclusive
+                let part = pos::tag(&word);
+                let suits = |(x, y): (u16, u16)| self.topology.suits(part, x, y, word_len, (width, height), self.cell_aspect);
+
+                // Unhinged scatters pile words up next to ones already placed
+                let clump_chance = ((self.chaos - NEUTRAL_CHAOS) / (1.0 - NEUTRAL_CHAOS)) as f64;
+                let aim = match targets.get(slot) {
+                    Some(&target) => Some((target, cell_reach)),
+                    None if clump_chance > 0.0 && !words.is_empty() && rng.gen_bool(clump_chance) => {
+                        let neighbour: &ScatteredWord = words.choose(rng).expect("words is not empty");
+                        let centre = (neighbour.x as f32 + neighbour.word.chars().count() as f32 / 2.0, neighbour.y as f32 + 0.5);
+                        Some((centre, (CLUMP_REACH, CLUMP_REACH / self.cell_aspect)))
+                    }
+                    None => None,
+                };
+
+                // Random tries find a spot quickly on a roomy canvas...
+                let max_attempts = 100;
+                let mut placement = (0..max_attempts)
+                    .map(|attempt| match aim {
+                        Some((centre, reach)) => {
+                            // Each failed attempt widens the search by half a column, and as far on screen in rows
+                            let growth = attempt as f32 / 2.0;
+                            let growth = (growth, growth / self.cell_aspect);
+                            aimed_spot(rng, centre, reach, growth, word_len, (min_x, max_x), (min_y, max_y))
+                        }
+                        None => (rng.gen_range(min_x..=max_x), rng.gen_range(min_y..max_y)),
+                    })
+                    .find(|&(x, y)| row_words[y as usize] < cap && occupancy.is_free(x, y, word_len) && suits((x, y)));
+
+                // ...and when they don't, every remaining free spot is considered before giving up,
+                // out of place for the topology if nowhere better is left
+                if placement.is_none() {
+                    let free_spots: Vec<(u16, u16)> = (min_y..max_y)
+                        .filter(|&y| row_words[y as usize] < cap)
+                        .flat_map(|y| (min_x..=max_x).map(move |x| (x, y)))
+                        .filter(|&(x, y)| occupancy.is_free(x, y, word_len))
+                        .collect();
+                    let suited: Vec<(u16, u16)> = free_spots.iter().copied().filter(|&spot| suits(spot)).collect();
+                    placement = suited.choose(rng).or_else(|| free_spots.choose(rng)).copied();
                 }
 
-                attempts += 1;
+                match placement {
+                    Some((x, y)) => {
+                        occupancy.place(x, y, word_len);
+                        row_words[y as usize] += 1;
+                        words.push(ScatteredWord {
+                            word,
+                            x,
+                            y,
+                            source_file,
+                            voice,
+                        });
+                    }
+                    None => shortest_unplaceable = word_len,
+                }
             }
+        }
 
-            // Fallback placement if collision avoidance failed
-            if !placed && usable_width >= word.len() as u16 {
-                let max_x = usable_width.saturating_sub(word.len() as u16);
-                let x = if max_x > 0 { rng.gen_range(0..=max_x) } else { 0 };
-                let y = rng.gen_range(0..usable_height);
-                scattered_words.push(ScatteredWord {
-                    word: word.clone(),
-                    x,
-                    y,
-                    source_file: source_file.clone(),
-                });
+        Scatter { words, requested }
+    }
+
+    /// Put the pinned words that fit on the canvas where they stand, before anything else is placed; they
+    /// were laid out together already, so they may sit closer to each other than fresh words do
+    fn place_pinned_words(&self, occupancy: &mut Occupancy, words: &mut Vec<ScatteredWord>, width: u16, height: u16) {
+        for pinned in &self.pinned_words {
+            let len = pinned.word.chars().count();
+            if pinned.y < height && pinned.x as usize + len <= width as usize {
+                occupancy.place(pinned.x, pinned.y, len as u16);
+                words.push(pinned.clone());
             }
         }
+    }
 
-        scattered_words
+    /// The kept words plus enough fresh ones to make `count`, with the kept ones at random places in the
+    /// order so they can land in any zone
+    fn select_with_kept_words<R: Rng>(&self, rng: &mut R, count: usize) -> Vec<(String, String, Option<Voice>)> {
+        let mut selected = self.select_words(rng, count.saturating_sub(self.kept_words.len()));
+        selected.retain(|(word, _, _)| !self.kept_words.iter().any(|(kept, _, _)| kept == word));
+
+        for kept in &self.kept_words {
+            let position = rng.gen_range(0..=selected.len());
+            selected.insert(position, kept.clone());
+        }
+        selected
     }
 
-}
+    /// The kept words plus fresh ones that bring the syllable count as close to `budget` as the draw allows
+    fn select_for_syllables<R: Rng>(&self, rng: &mut R, budget: usize) -> Vec<(String, String, Option<Voice>)> {
+        // Every word has a syllable, so the budget is as many words as could be needed
+        let candidates = self.select_with_kept_words(rng, budget);
+        let is_kept = |word: &str| self.kept_words.iter().any(|(kept, _, _)| kept == word);
+        let kept_syllables: usize = self.kept_words.iter().map(|(word, _, _)| spelling::syllables(word)).sum();
 
-fn is_overlapping_tight(x: u16, y: u16, word: &str, occupied: &[(u16, u16, u16)]) -> bool {
-    let word_len = word.len() as u16;
-    let min_gap = 2u16;
+        let mut remaining = budget.saturating_sub(kept_syllables);
+        candidates
+            .into_iter()
+            .filter(|(word, _, _)| {
+                let syllables = spelling::syllables(word);
+                let fits = is_kept(word) || syllables <= remaining;
+                if fits && !is_kept(word) {
+                    remaining -= syllables;
+                }
+                fits
+            })
+            .collect()
+    }
 
-    for &(ox, oy, olen) in occupied {
-        if y == oy {
-            let x_end = x + word_len;
-            let ox_end = ox + olen;
+    /// Draw `count` words in placement order; in dialogue mode the voices take turns, A first
+    fn select_words<R: Rng>(&self, rng: &mut R, count: usize) -> Vec<(String, String, Option<Voice>)> {
+        // Candidates are indices into word_pool(): voice A's pool followed by voice B's
+        let draw = |rng: &mut R, pool: &VoicePool, count: usize, voice: Option<Voice>| {
+            let candidates = &pool.candidates;
+            let count = count.min(candidates.len());
+            let weighted = self
+                .is_weighted()
+                .then(|| {
+                    pool.draw_large(rng, count).or_else(|| {
+                        index::sample_weighted(rng, candidates.len(), |i| self.weight(candidates[i]), count)
+                            .ok()
+                            .map(|picks| picks.into_vec())
+                    })
+                })
+                .flatten();
+            let mut picks = match weighted {
+                Some(picks) => picks,
+                None => index::sample(rng, candidates.len(), count).into_vec(),
+            };
+            self.spread_sources(rng, candidates, &mut picks);
 
-            if x_end + min_gap > ox && x < ox_end + min_gap {
-                return true;
+            let mut words: Vec<(String, String, Option<Voice>)> = picks
+                .into_iter()
+                .map(|pick| {
+                    let (word, source) = self.entry(candidates[pick]);
+                    (word.clone(), source.clone(), voice)
+                })
+                .collect();
+            words.shuffle(rng);
+            words
+        };
+
+        let pool = self.pool();
+        if let [only] = pool {
+            return draw(rng, only, count, None);
+        }
+
+        let voice_a = draw(rng, &pool[0], count.div_ceil(2), Some(Voice::A));
+        let voice_b = draw(rng, &pool[1], count / 2, Some(Voice::B));
+
+        // Interleave so the reading order alternates between the voices, then append
+        // whatever is left over if one corpus runs out of words first
+        let mut voice_a = voice_a.into_iter();
+        let mut voice_b = voice_b.into_iter();
+        let mut selected = Vec::with_capacity(count);
+        loop {
+            match (voice_a.next(), voice_b.next()) {
+                (None, None) => break,
+                (a, b) => selected.extend(a.into_iter().chain(b)),
             }
         }
+        selected
+    }
 
-        if (y as i16 - oy as i16).abs() <= 0 {
-            let x_end = x + word_len;
-            let ox_end = ox + olen;
-            if x_end + min_gap > ox && x < ox_end + min_gap {
-                return true;
+    /// Swap words from the best-represented source for words from unused ones until the picks
+    /// (positions in `candidates`) span `min_sources` files, or no more swaps are possible
+    fn spread_sources<R: Rng>(&self, rng: &mut R, candidates: &[usize], picks: &mut [usize]) {
+        let source = |pick: usize| self.entry(candidates[pick]).1.as_str();
+
+        loop {
+            let mut by_source: HashMap<&str, Vec<usize>> = HashMap::new();
+            for (slot, &pick) in picks.iter().enumerate() {
+                by_source.entry(source(pick)).or_default().push(slot);
+            }
+            if by_source.len() >= self.min_sources {
+                return;
             }
+
+            // Only swap out a word if its source keeps another one; ties go by name so seeded runs repeat
+            let Some((_, crowded)) = by_source
+                .iter()
+                .filter(|(_, slots)| slots.len() > 1)
+                .max_by_key(|(source, slots)| (slots.len(), *source))
+            else {
+                return;
+            };
+
+            let unused: Vec<usize> = (0..candidates.len())
+                .filter(|&pick| !by_source.contains_key(source(pick)))
+                .filter(|&pick| self.weight(candidates[pick]) > 0.0)
+                .collect();
+            let Some(&replacement) = unused.choose(rng) else {
+                return;
+            };
+
+            let slot = *crowded.choose(rng).expect("crowded source has words");
+            picks[slot] = replacement;
+        }
+    }
+
+    /// Whether a word at (x, y) would touch one of the reserved corner areas
+    fn in_corner_badge(&self, x: u16, y: u16, word_len: u16, width: u16, height: u16) -> bool {
+        let Some((badge_width, badge_height)) = self.rules.corner_badge else {
+            return false;
+        };
+
+        let in_top = y < badge_height;
+        let in_bottom = y >= height.saturating_sub(badge_height);
+        let touches_left = x < badge_width;
+        let touches_right = x + word_len > width.saturating_sub(badge_width);
+
+        (in_top || in_bottom) && (touches_left || touches_right)
+    }
+}
+
+/// Words drawn per template slot, so most slots find a word of their length
+const TEMPLATE_CANDIDATES: usize = 4;
+
+/// How far from a neighbour a clumped word may land, in columns; rows reach as far as it looks on screen
+const CLUMP_REACH: f32 = 6.0;
+
+/// Centres of an even grid with a cell for each of `count` words, in random order, and half a cell's size
+///
+/// Terminal cells are `cell_aspect` times as tall as they are wide, so the grid has that many times more
+/// columns per row than the area's width over its height would suggest, to look even.
+fn grid_targets<R: Rng>(
+    rng: &mut R,
+    (left, right): (u16, u16),
+    (top, bottom): (u16, u16),
+    count: usize,
+    cell_aspect: f32,
+) -> (Vec<(f32, f32)>, (f32, f32)) {
+    let (width, height) = ((right - left) as f32, (bottom - top) as f32);
+    let columns = ((count as f32 * width / (cell_aspect * height)).sqrt().ceil() as usize).max(1);
+    let rows = count.div_ceil(columns).max(1);
+    let (cell_width, cell_height) = (width / columns as f32, height / rows as f32);
+
+    let mut targets: Vec<(f32, f32)> = (0..rows)
+        .flat_map(|row| {
+            (0..columns).map(move |column| {
+                (left as f32 + (column as f32 + 0.5) * cell_width, top as f32 + (row as f32 + 0.5) * cell_height)
+            })
+        })
+        .collect();
+    targets.shuffle(rng);
+    targets.truncate(count);
+    (targets, (cell_width / 2.0, cell_height / 2.0))
+}
+
+/// A spot for a word centred near `centre`, within `reach` plus `growth`, the margin failed attempts have added
+fn aimed_spot<R: Rng>(
+    rng: &mut R,
+    (centre_x, centre_y): (f32, f32),
+    (reach_x, reach_y): (f32, f32),
+    (growth_x, growth_y): (f32, f32),
+    word_len: u16,
+    (min_x, max_x): (u16, u16),
+    (min_y, max_y): (u16, u16),
+) -> (u16, u16) {
+    let x = centre_x + rng.gen_range(-1.0..=1.0) * (reach_x + growth_x) - word_len as f32 / 2.0;
+    let y = centre_y + rng.gen_range(-1.0..=1.0) * (reach_y + growth_y) - 0.5;
+    (
+        (x.round().max(0.0) as u16).clamp(min_x, max_x),
+        (y.round().max(0.0) as u16).clamp(min_y, max_y - 1),
+    )
+}
+
+/// Blank cells kept between neighbouring words on the same row
+const MIN_GAP: u16 = 2;
+
+/// Whether a word of `len` cells at (x, y) stays on a `width`-wide canvas and keeps the gap placement
+/// leaves from each of `others`, for words that change after they were placed
+pub fn fits_beside<'a>(x: u16, y: u16, len: usize, width: u16, others: impl IntoIterator<Item = &'a ScatteredWord>) -> bool {
+    let (x, gap) = (x as usize, MIN_GAP as usize);
+    x + len <= width as usize
+        && others
+            .into_iter()
+            .filter(|other| other.y == y)
+            .all(|other| x + len + gap <= other.x as usize || other.x as usize + other.word.chars().count() + gap <= x)
+}
+
+/// Canvas cells taken by placed words (with their gaps) or reserved as safe areas
+struct Occupancy {
+    width: u16,
+    cells: Vec<bool>, // Row-major, width * height
+}
+
+impl Occupancy {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            cells: vec![false; width as usize * height as usize],
         }
     }
 
-    false
+    fn cell(&self, x: u16, y: u16) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    fn reserve(&mut self, x: u16, y: u16) {
+        let cell = self.cell(x, y);
+        self.cells[cell] = true;
+    }
+
+    /// Whether a word of `len` cells starting at (x, y) touches nothing already taken
+    fn is_free(&self, x: u16, y: u16, len: u16) -> bool {
+        let start = self.cell(x, y);
+        !self.cells[start..start + len as usize].iter().any(|&taken| taken)
+    }
+
+    /// Take the word's cells plus a gap on either side
+    fn place(&mut self, x: u16, y: u16, len: u16) {
+        let start = self.cell(x.saturating_sub(MIN_GAP), y);
+        // Added up as usize so a word at the right edge of a 65535-wide canvas can't overflow
+        let end = self.cell(0, y) + (x as usize + len as usize + MIN_GAP as usize).min(self.width as usize);
+        self.cells[start..end].iter_mut().for_each(|taken| *taken = true);
+    }
 }
 
 #[cfg(test)]
@@ -137,7 +1154,7 @@ mod tests {
             ("rust".to_string(), "test.txt".to_string()),
         ];
         let generator = ScattersGenerator::new(words);
-        let scattered = generator.generate_with_density(80, 24, 1.0);
+        let scattered = generator.generate_with_density(80, 24, 1.0).words;
 
         assert!(!scattered.is_empty());
         assert!(scattered.len() <= 3);
@@ -147,4 +1164,461 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_margins_and_corners_respected() {
+        let words: Vec<(String, String)> = (0..200)
+            .map(|i| (format!("w{:03}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_placement_rules(PlacementRules {
+            margins: Margins::parse("2,3,4,5").unwrap(),
+            corner_badge: Some((10, 3)),
+            words_per_row: None,
+        });
+
+        let scattered = generator.generate_with_density(60, 30, 6.0).words;
+        assert!(!scattered.is_empty());
+        for word in &scattered {
+            assert!(word.y >= 2 && word.y < 26);
+            assert!(word.x >= 5 && word.x + 4 <= 57);
+            assert!(!generator.in_corner_badge(word.x, word.y, 4, 60, 30));
+        }
+    }
+
+    #[test]
+    fn test_fits_beside() {
+        let tide = ScatteredWord { word: "tide".to_string(), x: 10, y: 0, source_file: String::new(), voice: None };
+        assert!(fits_beside(0, 0, 8, 20, [&tide]));
+        assert!(!fits_beside(0, 0, 9, 20, [&tide]));
+        assert!(fits_beside(16, 0, 4, 20, [&tide]));
+        assert!(!fits_beside(16, 0, 5, 20, [&tide]));
+        assert!(fits_beside(8, 1, 6, 20, [&tide]));
+    }
+
+    #[test]
+    fn test_accented_word_measured_in_characters() {
+        // "café" is five bytes but four cells, so it fills a four-cell canvas
+        let generator = ScattersGenerator::new(vec![("café".to_string(), "test.txt".to_string())]);
+        let scatter = generator.generate_count_with_rng(&mut rand::thread_rng(), 4, 1, 1);
+        assert_eq!(scatter.words.len(), 1);
+    }
+
+    #[test]
+    fn test_widest_canvas_placed_at_right_edge() {
+        let mut generator = ScattersGenerator::new(vec![("tide".to_string(), "test.txt".to_string())]);
+        generator.set_placement_rules(PlacementRules { margins: Margins::parse("0,0,0,65525").unwrap(), ..Default::default() });
+        let scattered = generator.generate_with_density(u16::MAX, 1, 6.0).words;
+        assert!(!scattered.is_empty());
+        assert!(scattered.iter().all(|word| word.x >= 65525 && word.x as usize + 4 <= u16::MAX as usize));
+    }
+
+    #[test]
+    fn test_words_per_row_capped() {
+        let words: Vec<(String, String)> = (0..500)
+            .map(|i| (format!("w{:03}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_placement_rules(PlacementRules { words_per_row: Some(2), ..Default::default() });
+
+        let scattered = generator.generate_with_density(80, 20, 6.0).words;
+        assert_eq!(scattered.len(), 40);
+        for y in 0..20 {
+            assert_eq!(scattered.iter().filter(|word| word.y == y).count(), 2, "row {}", y);
+        }
+    }
+
+    #[test]
+    fn test_dialogue_alternates_voices() {
+        let pool = |prefix: &str| -> Vec<(String, String)> {
+            (0..50).map(|i| (format!("{}{:02}", prefix, i), format!("{}.txt", prefix))).collect()
+        };
+        let generator = ScattersGenerator::with_dialogue(pool("aa"), pool("bb"));
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 10).words;
+        assert_eq!(scattered.len(), 10);
+        for (index, word) in scattered.iter().enumerate() {
+            let expected = if index % 2 == 0 { Voice::A } else { Voice::B };
+            assert_eq!(word.voice, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_weights_prefer_heavier_words() {
+        let words: Vec<(String, String)> = (0..40)
+            .map(|i| (format!("w{:02}", i), if i < 5 { "2019.txt" } else { "2020.txt" }.to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_weights(|_, source| if source == "2019.txt" { 1.0 } else { 0.0 });
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 5).words;
+        assert!(scattered.iter().all(|word| word.source_file == "2019.txt"));
+    }
+
+    #[test]
+    fn test_source_weights_scale_time_weights() {
+        let words: Vec<(String, String)> = (0..60)
+            .map(|i| (format!("w{:02}", i), ["a.txt", "b.txt", "c.txt"][i % 3].to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_weights(|_, source| if source == "c.txt" { 0.0 } else { 1.0 });
+        generator.set_source_weights(HashMap::from([("a.txt".to_string(), 0.0)]));
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 10).words;
+        assert_eq!(scattered.len(), 10);
+        assert!(scattered.iter().all(|word| word.source_file == "b.txt"));
+    }
+
+    #[test]
+    fn test_active_slice_limits_pool() {
+        let words: Vec<(String, String)> = (0..100)
+            .map(|i| (format!("w{:02}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        let mut rng = rand::thread_rng();
+        generator.resample_active(&mut rng, 0.1);
+        assert_eq!(generator.active_count(), 10);
+
+        let scattered = generator.generate_count_with_rng(&mut rng, 200, 100, 50).words;
+        assert_eq!(scattered.len(), 10);
+
+        generator.clear_active();
+        assert_eq!(generator.active_count(), 100);
+    }
+
+    #[test]
+    fn test_same_seed_same_layout() {
+        let words: Vec<(String, String)> = (0..300)
+            .map(|i| (format!("word{:03}", i), format!("file{}.txt", i % 7)))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_min_sources(5);
+
+        let layout = |seed| {
+            let scatter = generator.generate_zoned_with_rng(&mut StdRng::seed_from_u64(seed), 80, 24, ZoneLayout::Rows, &[2.0, 0.5]);
+            scatter.words.iter().map(|w| (w.word.clone(), w.x, w.y)).collect::<Vec<_>>()
+        };
+        assert_eq!(layout(42), layout(42));
+        assert_ne!(layout(42), layout(43));
+
+        let positions = |scatter: Scatter| scatter.words.into_iter().map(|w| (w.word, w.x, w.y)).collect::<Vec<_>>();
+        assert_eq!(positions(generator.generate_with_seed(7, 60, 20, 2.0)), positions(generator.generate_with_seed(7, 60, 20, 2.0)));
+    }
+
+    #[test]
+    fn test_min_sources_mixes_files() {
+        // One dominant file and four small ones
+        let mut words: Vec<(String, String)> = (0..500)
+            .map(|i| (format!("big{:03}", i), "big.txt".to_string()))
+            .collect();
+        words.extend((0..8).map(|i| (format!("small{}", i), format!("small{}.txt", i % 4))));
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_min_sources(4);
+
+        for _ in 0..20 {
+            let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 6).words;
+            let sources: HashSet<&str> = scattered.iter().map(|word| word.source_file.as_str()).collect();
+            assert!(sources.len() >= 4);
+        }
+    }
+
+    #[test]
+    fn test_kept_words_in_every_scatter() {
+        let words: Vec<(String, String)> = (0..100)
+            .map(|i| (format!("w{:02}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_kept_words(vec![("w07".to_string(), "test.txt".to_string(), None)]);
+
+        let mut positions = HashSet::new();
+        for _ in 0..10 {
+            let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 5).words;
+            let kept: Vec<&ScatteredWord> = scattered.iter().filter(|word| word.word == "w07").collect();
+            assert_eq!(kept.len(), 1);
+            positions.insert((kept[0].x, kept[0].y));
+        }
+        assert!(positions.len() > 1);
+    }
+
+    #[test]
+    fn test_stop_words_never_drawn() {
+        let words: Vec<(String, String)> = (0..20)
+            .map(|i| (format!("w{:02}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_stop_words((0..10).map(|i| format!("w{:02}", i)));
+        assert_eq!(generator.usable_count(), 10);
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 20).words;
+        assert_eq!(scattered.len(), 10);
+        assert!(scattered.iter().all(|word| !generator.is_left_out(&word.word)));
+    }
+
+    #[test]
+    fn test_lipogram_never_drawn() {
+        let words: Vec<(String, String)> = ["lantern", "harbor", "salt", "Sea", "moon", "tide"]
+            .iter()
+            .map(|word| (word.to_string(), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_lipogram("e");
+        assert_eq!(generator.usable_count(), 3);
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 200, 100, 6).words;
+        assert_eq!(scattered.len(), 3);
+        assert!(scattered.iter().all(|word| !word.word.contains('e')));
+        generator.set_lipogram("");
+        assert_eq!(generator.usable_count(), 6);
+    }
+
+    #[test]
+    fn test_syllable_budget() {
+        let words: Vec<(String, String)> = ["lantern", "harbor", "salt", "tide", "moon", "beautiful", "sky", "yellow"]
+            .iter()
+            .map(|word| (word.to_string(), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_syllable_budget(Some(7));
+
+        for _ in 0..20 {
+            let scatter = generator.generate_zoned_with_rng(&mut rand::thread_rng(), 200, 100, ZoneLayout::Rows, &[1.0, 1.0]);
+            let syllables: usize = scatter.words.iter().map(|word| spelling::syllables(&word.word)).sum();
+            assert!((6..=7).contains(&syllables), "{} syllables", syllables);
+        }
+    }
+
+    #[test]
+    fn test_full_canvas_never_overlaps() {
+        let words: Vec<(String, String)> = (0..200)
+            .map(|i| (format!("word{:03}", i), "test.txt".to_string()))
+            .collect();
+        let generator = ScattersGenerator::new(words);
+
+        let scatter = generator.generate_count_with_rng(&mut rand::thread_rng(), 30, 4, 100);
+        assert_eq!(scatter.requested, 100);
+        assert!(scatter.words.len() < scatter.requested);
+
+        for (i, a) in scatter.words.iter().enumerate() {
+            for b in &scatter.words[i + 1..] {
+                let apart = a.x + 7 + MIN_GAP <= b.x || b.x + 7 + MIN_GAP <= a.x;
+                assert!(a.y != b.y || apart, "{} and {} overlap", a.word, b.word);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zones_get_their_own_density() {
+        let words: Vec<(String, String)> = (0..500)
+            .map(|i| (format!("w{:03}", i), "test.txt".to_string()))
+            .collect();
+        let generator = ScattersGenerator::new(words);
+
+        let scatter = generator.generate_zoned_with_rng(&mut rand::thread_rng(), 80, 40, ZoneLayout::Rows, &[4.0, 0.1]);
+        let top = scatter.words.iter().filter(|word| word.y < 20).count();
+        let bottom = scatter.words.len() - top;
+        assert!(top > bottom * 10, "top {} bottom {}", top, bottom);
+    }
+
+    #[test]
+    fn test_auto_density_follows_bank() {
+        let bank = |count: usize, word: &str| {
+            ScattersGenerator::new((0..count).map(|i| (format!("{}{:06}", word, i), "test.txt".to_string())).collect())
+        };
+        // Six-letter words
+        let small = bank(20, "").auto_density();
+        let medium = bank(2000, "").auto_density();
+        let large = bank(200_000, "").auto_density();
+        assert!((medium - 1.0).abs() < 0.01, "medium {}", medium);
+        assert!(small > 1.4 && large < 0.6, "small {} large {}", small, large);
+        assert!(bank(2000, "lanterns").auto_density() < medium);
+        assert_eq!(StartDensity::parse("Auto"), Ok(StartDensity::Auto));
+        assert_eq!(StartDensity::parse("2.5"), Ok(StartDensity::Fixed(2.5)));
+        assert!(StartDensity::parse("9").is_err());
+    }
+
+    #[test]
+    fn test_exclusions_stay_empty() {
+        let words: Vec<(String, String)> = (0..300)
+            .map(|i| (format!("w{:03}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        let excluded = Area { x: 10, y: 5, width: 20, height: 10 };
+        generator.set_exclusions(vec![excluded]);
+
+        let scatter = generator.generate_with_density(60, 30, 6.0);
+        assert!(!scatter.words.is_empty());
+        assert!(scatter.words.iter().all(|word| !excluded.touches_word(word.x, word.y, 4)));
+    }
+
+    #[test]
+    fn test_pinned_words_stay_put() {
+        let words: Vec<(String, String)> = (0..300)
+            .map(|i| (format!("w{:03}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        let pinned = ScatteredWord { word: "lantern".to_string(), x: 12, y: 3, source_file: "poem.txt".to_string(), voice: None };
+        let off_canvas = ScatteredWord { x: 58, ..pinned.clone() };
+        generator.set_pinned_words(vec![pinned, off_canvas]);
+
+        let scatter = generator.generate_with_density(60, 30, 6.0);
+        let lantern = Area { x: 12, y: 3, width: 7, height: 1 };
+        assert_eq!(scatter.words.iter().filter(|word| word.word == "lantern").count(), 1);
+        assert!(scatter.words.iter().any(|word| word.word == "lantern" && (word.x, word.y) == (12, 3)));
+        assert!(scatter.words.iter().all(|word| word.word == "lantern" || !lantern.touches_word(word.x, word.y, 4)));
+    }
+
+    #[test]
+    fn test_rows_topology_separates_parts_of_speech() {
+        let words: Vec<(String, String)> = ('a'..='t')
+            .flat_map(|letter| [format!("{}lantern", letter), format!("{}wandering", letter)])
+            .map(|word| (word, "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_topology(Topology::Rows);
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 120, 40, 10).words;
+        assert_eq!(scattered.len(), 10);
+        for word in &scattered {
+            assert_eq!(word.y % 2 == 1, word.word.ends_with("wandering"), "{} on row {}", word.word, word.y);
+        }
+    }
+
+    #[test]
+    fn test_grid_compensates_for_cell_aspect() {
+        let columns = |cell_aspect: f32| {
+            let (targets, _) = grid_targets(&mut rand::thread_rng(), (0, 40), (0, 40), 100, cell_aspect);
+            let mut xs: Vec<u32> = targets.iter().map(|(x, _)| x.to_bits()).collect();
+            xs.sort_unstable();
+            xs.dedup();
+            xs.len()
+        };
+        // A square of cells looks twice as tall as it is wide, so it gets fewer columns than rows
+        assert_eq!(columns(1.0), 10);
+        assert_eq!(columns(2.0), 8);
+
+        // The same spot is near the centre in plain cells, but not as it looks on screen
+        assert!(Topology::Centre.suits(PartOfSpeech::Noun, 14, 26, 4, (40, 40), 1.0));
+        assert!(!Topology::Centre.suits(PartOfSpeech::Noun, 14, 26, 4, (40, 40), 2.0));
+
+        // At neutral chaos with free topology words land uniformly at random, with no distances to measure
+        let words: Vec<(String, String)> = (0..60).map(|i| (format!("w{:02}", i), "test.txt".to_string())).collect();
+        let positions = |cell_aspect: f32| {
+            let mut generator = ScattersGenerator::new(words.clone());
+            generator.set_cell_aspect(cell_aspect);
+            generator.generate_with_seed(7, 60, 20, 2.0).words.iter().map(|word| (word.x, word.y)).collect::<Vec<_>>()
+        };
+        assert_eq!(positions(1.0), positions(3.0));
+    }
+
+    #[test]
+    fn test_orderly_chaos_favours_common_words_on_a_grid() {
+        let words: Vec<(String, String)> = (0..100)
+            .map(|i| (format!("w{:02}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_frequencies(HashMap::from([("w00".to_string(), 100_000)]));
+        generator.set_chaos(0.0);
+
+        let scattered = generator.generate_count_with_rng(&mut rand::thread_rng(), 80, 20, 8).words;
+        assert_eq!(scattered.len(), 8);
+        assert!(scattered.iter().any(|word| word.word == "w00"));
+        // Every quarter of a 2x4 grid over the canvas gets a word
+        for (column, row) in [(0, 0), (1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1), (3, 1)] {
+            let cell = Area { x: column * 20, y: row * 10, width: 20, height: 10 };
+            assert!(scattered.iter().any(|word| cell.contains(word.x + 1, word.y)), "no word in {:?}", cell);
+        }
+    }
+
+    #[test]
+    fn test_high_chaos_strays_from_frequency_and_grid() {
+        let words: Vec<(String, String)> = (0..100)
+            .map(|i| (format!("w{:02}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_frequencies((0..100).map(|i| (format!("w{:02}", i), if i < 10 { 1000 } else { 1 })).collect());
+        let mut rng = StdRng::seed_from_u64(11);
+        // Share of draws from the ten common words, and how many cells of a 4x2 grid over the canvas get a word
+        let mut measure = |generator: &ScattersGenerator| {
+            let drawn: Vec<_> = (0..50).flat_map(|_| generator.select_words(&mut rng, 10)).collect();
+            let common = drawn.iter().filter(|(word, _, _)| word[1..].parse::<u32>().unwrap() < 10).count();
+            let scattered = generator.generate_count_with_rng(&mut rng, 80, 20, 8).words;
+            let cells: HashSet<(u16, u16)> = scattered.iter().map(|word| ((word.x + 1) / 20, word.y / 10)).collect();
+            (common as f32 / drawn.len() as f32, cells.len())
+        };
+
+        generator.set_chaos(0.0);
+        let (orderly_share, orderly_cells) = measure(&generator);
+        generator.set_chaos(1.0);
+        let (unhinged_share, unhinged_cells) = measure(&generator);
+        assert!(orderly_share > 0.9 && unhinged_share < 0.05, "{} {}", orderly_share, unhinged_share);
+        assert_eq!(orderly_cells, 8);
+        assert!(unhinged_cells < orderly_cells, "{}", unhinged_cells);
+    }
+
+    #[test]
+    fn test_sampling_follows_frequency() {
+        let words: Vec<(String, String)> = (0..100)
+            .map(|i| (format!("w{:02}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_frequencies((0..100).map(|i| (format!("w{:02}", i), if i < 10 { 1000 } else { 1 })).collect());
+        let common_share = |generator: &ScattersGenerator| {
+            let drawn: Vec<_> = (0..50).flat_map(|_| generator.select_words(&mut rand::thread_rng(), 10)).collect();
+            drawn.iter().filter(|(word, _, _)| word[1..].parse::<u32>().unwrap() < 10).count() as f32 / drawn.len() as f32
+        };
+
+        generator.set_sampling(Sampling::Frequent);
+        assert!(common_share(&generator) > 0.9);
+        generator.set_sampling(Sampling::Rare);
+        assert!(common_share(&generator) < 0.05);
+        // Even sampling ignores counts whatever the chaos
+        generator.set_chaos(0.0);
+        generator.set_sampling(Sampling::Even);
+        assert!((0.02..0.3).contains(&common_share(&generator)));
+        assert_eq!(Sampling::parse("Rare"), Ok(Sampling::Rare));
+    }
+
+    #[test]
+    fn test_large_pool_drawn_by_running_weights() {
+        let words: Vec<(String, String)> = (0..LARGE_POOL * 2)
+            .map(|i| (format!("w{:06}", i), "test.txt".to_string()))
+            .collect();
+        let mut generator = ScattersGenerator::new(words);
+        generator.set_frequencies((0..100).map(|i| (format!("w{:06}", i), 1_000_000)).collect());
+        generator.set_sampling(Sampling::Frequent);
+
+        let drawn = generator.select_words(&mut rand::thread_rng(), 50);
+        let distinct: HashSet<&String> = drawn.iter().map(|(word, _, _)| word).collect();
+        assert_eq!(distinct.len(), 50);
+        assert!(drawn.iter().filter(|(word, _, _)| word[1..].parse::<usize>().unwrap() < 100).count() > 40);
+
+        // Leaving words out after a draw rebuilds the pool without them
+        generator.set_stop_words((0..100).map(|i| format!("w{:06}", i)));
+        assert_eq!(generator.active_count(), LARGE_POOL * 2 - 100);
+        let drawn = generator.select_words(&mut rand::thread_rng(), 50);
+        assert!(drawn.iter().all(|(word, _, _)| word[1..].parse::<usize>().unwrap() >= 100));
+    }
+
+    #[test]
+    fn test_template_keeps_positions() {
+        let words: Vec<(String, String)> = ["lantern", "harbor", "tide", "mist", "salt", "anchors", "gulls"]
+            .iter()
+            .map(|word| (word.to_string(), "test.txt".to_string()))
+            .collect();
+        let generator = ScattersGenerator::new(words);
+        let slots = [Slot { x: 2, y: 1, len: 7 }, Slot { x: 30, y: 5, len: 4 }, Slot { x: 12, y: 5, len: 6 }];
+
+        let scatter = generator.fill_template_with_rng(&mut rand::thread_rng(), 40, 10, &slots);
+        assert_eq!(scatter.words.len(), 3);
+        for word in &scatter.words {
+            let slot = slots.iter().find(|slot| slot.y == word.y && slot.x <= word.x).unwrap();
+            assert!(word.x + word.word.chars().count() as u16 <= slot.x + slot.len, "{} overflows its slot", word.word);
+        }
+    }
+
+    #[test]
+    fn test_parse_margins() {
+        assert_eq!(Margins::parse("1,2").unwrap(), Margins { top: 1, right: 2, bottom: 1, left: 2 });
+        assert!(Margins::parse("1,2,3").is_err());
+    }
+
 }
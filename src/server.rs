@@ -0,0 +1,181 @@
+//! Long-lived server mode: JSON requests and responses, one object per line, over a unix socket.
+//!
+//! Requests:
+//!   {"op": "generate", "width": 80, "height": 24, "density": 1.0}
+//!   {"op": "export", "width": 80, "height": 24, "density": 1.0}
+//!   {"op": "bank", "query": "lan", "limit": 50}
+//! Responses carry `"ok": true` plus the result, or `"ok": false` with an `"error"` message.
+
+use crate::export;
+use crate::scatters::{ScattersGenerator, MAX_CANVAS};
+use serde_json::{json, Value};
+use std::path::Path;
+
+#[cfg(unix)]
+pub fn serve(
+    socket_path: &Path,
+    generator: ScattersGenerator,
+    word_count: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixListener;
+    use std::sync::Arc;
+
+    remove_stale_socket(socket_path)?;
+    let listener = UnixListener::bind(socket_path)?;
+    println!("Serving {} words on {}", word_count, socket_path.display());
+
+    let generator = Arc::new(generator);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Warning: Failed to accept connection: {}", e);
+                continue;
+            }
+        };
+
+        let generator = Arc::clone(&generator);
+        std::thread::spawn(move || {
+            let reader = match stream.try_clone() {
+                Ok(read_half) => BufReader::new(read_half),
+                Err(_) => return,
+            };
+            let mut writer = stream;
+
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let response = handle_request(&line, &generator);
+                if writeln!(writer, "{}", response).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Clear out a stale socket left behind by a previous run, but never a regular file
+#[cfg(unix)]
+pub fn remove_stale_socket(socket_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(metadata) = std::fs::symlink_metadata(socket_path) {
+        if metadata.file_type().is_socket() {
+            std::fs::remove_file(socket_path)?;
+        } else {
+            return Err(format!("'{}' exists and is not a socket", socket_path.display()).into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(
+    _socket_path: &Path,
+    _generator: ScattersGenerator,
+    _word_count: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    Err("serve mode requires unix domain sockets, which this platform does not support".into())
+}
+
+/// Answer a single JSON request line
+pub fn handle_request(line: &str, generator: &ScattersGenerator) -> Value {
+    match process_request(line, generator) {
+        Ok(value) => value,
+        Err(message) => json!({ "ok": false, "error": message }),
+    }
+}
+
+fn process_request(line: &str, generator: &ScattersGenerator) -> Result<Value, String> {
+    let request: Value = serde_json::from_str(line).map_err(|e| format!("invalid JSON: {}", e))?;
+    let op = request
+        .get("op")
+        .and_then(Value::as_str)
+        .ok_or("missing \"op\" field")?;
+
+    match op {
+        "generate" => {
+            let (width, height, density) = canvas_params(&request)?;
+            let scatter = generator.generate_with_density(width, height, density);
+            Ok(json!({ "ok": true, "requested": scatter.requested, "words": export::layout_json(&scatter.words) }))
+        }
+        "export" => {
+            let (width, height, density) = canvas_params(&request)?;
+            let scatter = generator.generate_with_density(width, height, density);
+            Ok(json!({ "ok": true, "text": export::render_text(&scatter.words, width, height) }))
+        }
+        "bank" => {
+            let query = request.get("query").and_then(Value::as_str).unwrap_or("");
+            let limit = request.get("limit").and_then(Value::as_u64).unwrap_or(100) as usize;
+            let mut matches: Vec<&str> = generator
+                .word_pool()
+                .map(|(word, _)| word.as_str())
+                .filter(|word| word.contains(query) && !generator.is_left_out(word))
+                .collect();
+            matches.sort_unstable();
+            let total = matches.len();
+            matches.truncate(limit);
+            Ok(json!({ "ok": true, "count": generator.usable_count(), "total_matches": total, "matches": matches }))
+        }
+        other => Err(format!("unknown op '{}'", other)),
+    }
+}
+
+/// Read width/height/density from a request, with the TUI's defaults for anything omitted; a side
+/// outside 1 to `MAX_CANVAS` cells is an error, so no client can make the server allocate a huge canvas
+fn canvas_params(request: &Value) -> Result<(u16, u16, f32), String> {
+    let dimension = |key: &str, default: u64| -> Result<u16, String> {
+        match request.get(key).and_then(Value::as_u64).unwrap_or(default) {
+            value if (1..=MAX_CANVAS as u64).contains(&value) => Ok(value as u16),
+            _ => Err(format!("\"{}\" must be from 1 to {}", key, MAX_CANVAS)),
+        }
+    };
+
+    let width = dimension("width", 80)?;
+    let height = dimension("height", 24)?;
+    let density = request.get("density").and_then(Value::as_f64).unwrap_or(1.0) as f32;
+
+    Ok((width, height, density.clamp(0.1, 6.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generator() -> ScattersGenerator {
+        ScattersGenerator::new(vec![
+            ("lantern".to_string(), "a.txt".to_string()),
+            ("harbor".to_string(), "b.txt".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_bank_query() {
+        let response = handle_request(r#"{"op": "bank", "query": "lan"}"#, &generator());
+        assert_eq!(response["ok"], true);
+        assert_eq!(response["matches"], json!(["lantern"]));
+    }
+
+    #[test]
+    fn test_canvas_size_limits() {
+        for request in [r#"{"op": "generate", "width": 0}"#, r#"{"op": "export", "width": 65535, "height": 65535}"#] {
+            let response = handle_request(request, &generator());
+            assert_eq!(response["ok"], false);
+        }
+        let response = handle_request(r#"{"op": "generate", "width": 1000, "height": 1}"#, &generator());
+        assert_eq!(response["ok"], true);
+    }
+
+    #[test]
+    fn test_unknown_op() {
+        let response = handle_request(r#"{"op": "dance"}"#, &generator());
+        assert_eq!(response["ok"], false);
+    }
+}
@@ -0,0 +1,250 @@
+//! Other spellings for the canvas words: a phonetic respelling for sound poetry, taken from a bundled
+//! pronouncing list with words the list doesn't have sounded out by rule, and reversed or mirrored
+//! letters for visual experiments. The pronouncing list also counts syllables for metered scatters.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const RESPELLINGS: &str = include_str!("../data/respellings.txt");
+
+/// Letter groups and how they sound, longest first so "tion" wins over "t"
+const SOUNDS: &[(&str, &str)] = &[
+    ("tion", "shun"), ("sion", "zhun"), ("ture", "cher"), ("augh", "aw"), ("eigh", "ay"), ("ough", "oh"),
+    ("igh", "y"), ("tch", "ch"), ("dge", "j"),
+    ("ph", "f"), ("ck", "k"), ("qu", "kw"), ("wh", "w"), ("ch", "ch"), ("sh", "sh"), ("th", "th"),
+    ("ee", "ee"), ("ea", "ee"), ("ei", "ee"), ("ie", "ee"), ("oa", "oh"), ("ai", "ay"), ("ay", "ay"),
+    ("oi", "oy"), ("oy", "oy"), ("ou", "ow"), ("ow", "ow"), ("au", "aw"), ("aw", "aw"), ("ew", "oo"),
+    ("oo", "oo"), ("ir", "er"), ("ur", "er"),
+    ("x", "ks"),
+];
+
+/// Silent letters at the start of a word: "knot", "write"
+const SILENT_STARTS: &[(&str, &str)] = &[("kn", "n"), ("wr", "r"), ("gn", "n"), ("ps", "s"), ("rh", "r")];
+
+/// Letters and the look-alikes that face the other way, for mirror writing; letters that are the same
+/// either way round are left as they are
+const MIRRORED_LETTERS: &[(char, char)] = &[
+    ('a', 'ɒ'), ('b', 'd'), ('c', 'ɔ'), ('d', 'b'), ('e', 'ɘ'), ('g', 'ϱ'), ('j', 'ſ'), ('k', 'ʞ'),
+    ('n', 'ᴎ'), ('p', 'q'), ('q', 'p'), ('r', 'ɿ'), ('s', 'ƨ'), ('t', 'ƚ'), ('y', 'γ'), ('z', 'ƹ'),
+    ('B', 'ᗺ'), ('C', 'Ɔ'), ('D', 'ᗡ'), ('E', 'Ǝ'), ('J', 'Ⴑ'), ('L', '⅃'), ('N', 'И'), ('R', 'Я'),
+    ('S', 'Ƨ'), ('Z', 'Ƹ'),
+];
+
+/// How words are spelled on the canvas and in snapshots
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Spelling {
+    #[default]
+    Written,
+    Phonetic,  // "thought" as "thawt"
+    Reversed,  // "lantern" as "nretnal"
+    Mirrored,  // Reversed with each letter facing the other way, as in a mirror
+}
+
+impl Spelling {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "written" => Ok(Self::Written),
+            "phonetic" => Ok(Self::Phonetic),
+            "reversed" => Ok(Self::Reversed),
+            "mirrored" => Ok(Self::Mirrored),
+            _ => Err(format!("unknown spelling '{}' (expected written, phonetic, reversed or mirrored)", value)),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Written => "written",
+            Self::Phonetic => "phonetic",
+            Self::Reversed => "reversed",
+            Self::Mirrored => "mirrored",
+        }
+    }
+
+    /// The spelling the H key moves on to
+    pub fn next(self) -> Self {
+        match self {
+            Self::Written => Self::Phonetic,
+            Self::Phonetic => Self::Reversed,
+            Self::Reversed => Self::Mirrored,
+            Self::Mirrored => Self::Written,
+        }
+    }
+
+    /// The word as this spelling shows it
+    pub fn apply(self, word: &str) -> Cow<'_, str> {
+        match self {
+            Self::Written => Cow::Borrowed(word),
+            Self::Phonetic => {
+                let word = word.to_lowercase();
+                match respellings().get(word.as_str()) {
+                    Some(respelling) => Cow::Borrowed(respelling),
+                    None => Cow::Owned(sound_out(&word)),
+                }
+            }
+            Self::Reversed => Cow::Owned(word.chars().rev().collect()),
+            Self::Mirrored => Cow::Owned(word.chars().rev().map(mirror).collect()),
+        }
+    }
+}
+
+fn respellings() -> &'static HashMap<&'static str, &'static str> {
+    static RESPELLING_LIST: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    RESPELLING_LIST.get_or_init(|| {
+        RESPELLINGS
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once(' '))
+            .collect()
+    })
+}
+
+/// Syllables in a word: the parts of its respelling when the pronouncing list has it, otherwise its
+/// vowel groups, leaving out silent endings
+pub fn syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    if let Some(respelling) = respellings().get(word.as_str()) {
+        return respelling.split('-').count();
+    }
+
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    let count = letters.len();
+    let sounds_vowel = |at: usize| is_vowel(letters[at]) || (letters[at] == 'y' && at > 0);
+    let mut groups = (0..count).filter(|&at| sounds_vowel(at) && (at == 0 || !sounds_vowel(at - 1))).count();
+
+    // A final e is silent ("stone") except in a final "le" after a consonant ("table"), and "ed" only
+    // sounds after t or d ("waited" but not "walked")
+    let silent_e = count >= 3
+        && letters[count - 1] == 'e'
+        && !sounds_vowel(count - 2)
+        && (letters[count - 2] != 'l' || sounds_vowel(count - 3));
+    let silent_ed = count >= 4
+        && letters[count - 2..] == ['e', 'd']
+        && !matches!(letters[count - 3], 't' | 'd')
+        && !sounds_vowel(count - 3);
+    if groups > 1 && (silent_e || silent_ed) {
+        groups -= 1;
+    }
+    groups.max(1)
+}
+
+fn mirror(letter: char) -> char {
+    MIRRORED_LETTERS.iter().find(|(plain, _)| *plain == letter).map_or(letter, |(_, mirrored)| *mirrored)
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// A lowercase word spelled as it would be read aloud, letter group by letter group
+fn sound_out(word: &str) -> String {
+    let letters: Vec<char> = word.chars().collect();
+    let count = letters.len();
+    let rest = |at: usize| letters[at..].iter().collect::<String>();
+
+    // One vowel, one consonant and a silent e says the vowel's name: "stone", "time"
+    let long_vowel = (count >= 3
+        && letters[count - 1] == 'e'
+        && !is_vowel(letters[count - 2])
+        && !matches!(letters[count - 2], 'w' | 'x' | 'y')
+        && is_vowel(letters[count - 3])
+        && (count == 3 || !is_vowel(letters[count - 4])))
+    .then(|| count - 3);
+    let sounded_vowel_before = |at: usize| letters[..at].iter().any(|&c| is_vowel(c) || c == 'y');
+
+    let mut sounded = String::new();
+    let mut at = 0;
+    while at < count {
+        let ahead = rest(at);
+        if let Some((group, sound)) = SILENT_STARTS.iter().find(|(group, _)| at == 0 && ahead.starts_with(group)) {
+            sounded.push_str(sound);
+            at += group.len();
+            continue;
+        }
+        if Some(at) != long_vowel {
+            if let Some((group, sound)) = SOUNDS.iter().find(|(group, _)| ahead.starts_with(group)) {
+                sounded.push_str(sound);
+                at += group.len();
+                continue;
+            }
+        }
+
+        let letter = letters[at];
+        let next = letters.get(at + 1).copied();
+        let last = at + 1 == count;
+        match letter {
+            _ if Some(at) == long_vowel => sounded.push_str(match letter {
+                'a' => "ay",
+                'e' => "ee",
+                'i' => "y",
+                'o' => "oh",
+                _ => "oo",
+            }),
+            // The silent e that made the vowel long
+            'e' if last && long_vowel.is_some() => {}
+            'e' if last => sounded.push_str(if sounded_vowel_before(at) { "" } else { "ee" }),
+            // Past tenses: "walked", "rained", "waited"
+            'e' if next == Some('d') && at + 2 == count && count > 3 => {
+                let sound = match letters[at - 1] {
+                    't' | 'd' => "id",
+                    'p' | 'k' | 's' | 'f' | 'x' | 'h' => "t",
+                    _ => "d",
+                };
+                sounded.push_str(sound);
+                at += 1;
+            }
+            // "table", "candle"
+            'l' if next == Some('e') && at + 2 == count && at > 0 && !is_vowel(letters[at - 1]) => {
+                sounded.push_str("ul");
+                at += 1;
+            }
+            'm' if next == Some('b') && at + 2 == count => {
+                sounded.push('m');
+                at += 1;
+            }
+            'c' if matches!(next, Some('e' | 'i' | 'y')) => sounded.push('s'),
+            'c' => sounded.push('k'),
+            'y' if at == 0 => sounded.push('y'),
+            'y' if last => sounded.push_str(if sounded_vowel_before(at) { "ee" } else { "y" }),
+            'y' => sounded.push('i'),
+            // Doubled consonants sound once
+            _ if next == Some(letter) && !is_vowel(letter) => {}
+            _ => sounded.push(letter),
+        }
+        at += 1;
+    }
+    sounded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phonetic_respelling() {
+        let phonetic = |word| Spelling::Phonetic.apply(word).into_owned();
+        assert_eq!(phonetic("Thought"), "thawt");
+        assert_eq!(phonetic("stone"), "stohn");
+        assert_eq!(phonetic("nation"), "nashun");
+        assert_eq!(phonetic("knocked"), "nokt");
+        assert_eq!(phonetic("city"), "sitee");
+        assert_eq!(phonetic("candle"), "kandul");
+        assert_eq!(phonetic("lantern"), "lantern");
+        assert_eq!(phonetic("chapter"), "chapter");
+        assert_eq!(Spelling::Written.apply("thought"), "thought");
+        assert_eq!(Spelling::Reversed.apply("lantern"), "nretnal");
+        assert_eq!(Spelling::Mirrored.apply("bed"), "bɘd");
+        assert_eq!(Spelling::Mirrored.apply("Harbor"), "ɿodɿɒH");
+        assert!(respellings().values().all(|respelling| !respelling.is_empty()));
+    }
+
+    #[test]
+    fn test_syllables() {
+        let counts: Vec<usize> =
+            ["beautiful", "lantern", "stone", "table", "walked", "waited", "sky", "yellow", "the", "Harbor"]
+                .iter()
+                .map(|word| syllables(word))
+                .collect();
+        assert_eq!(counts, vec![3, 2, 1, 2, 1, 2, 1, 2, 1, 2]);
+    }
+}
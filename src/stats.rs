@@ -0,0 +1,150 @@
+//! Session statistics and the writing timer: rerolls and words visited this session, appended to a log
+//! when it ends, and the sidebar clock that counts up or down.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Running counters for the current session
+pub struct SessionStats {
+    started: Instant,
+    pub rerolls: usize,
+    pub words_visited: usize,     // Every navigation step that lands on a word
+    pub words_highlighted: usize, // Distinct words highlighted, summed across scatters
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            rerolls: 0,
+            words_visited: 0,
+            words_highlighted: 0,
+        }
+    }
+
+    pub fn record_reroll(&mut self) {
+        self.rerolls += 1;
+    }
+
+    pub fn record_visit(&mut self, newly_highlighted: bool) {
+        self.words_visited += 1;
+        if newly_highlighted {
+            self.words_highlighted += 1;
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Single tab-separated line appended to the session stats file on exit
+    pub fn to_record(&self) -> String {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        format!(
+            "{}\trerolls={}\tvisited={}\thighlighted={}\telapsed={}",
+            timestamp,
+            self.rerolls,
+            self.words_visited,
+            self.words_highlighted,
+            format_elapsed(self.elapsed())
+        )
+    }
+}
+
+/// How long a finished countdown flashes, and how fast
+const TIMER_FLASH: Duration = Duration::from_secs(4);
+const TIMER_FLASH_PERIOD: Duration = Duration::from_millis(500);
+
+/// Sidebar clock for timed writing: counts up from when it was started, or down from a set length
+pub struct WritingTimer {
+    started: Instant,
+    length: Option<Duration>,  // Countdown length; None counts up
+}
+
+impl WritingTimer {
+    pub fn count_up() -> Self {
+        Self { started: Instant::now(), length: None }
+    }
+
+    pub fn countdown(minutes: u32) -> Self {
+        Self { started: Instant::now(), length: Some(Duration::from_secs(minutes as u64 * 60)) }
+    }
+
+    pub fn is_countdown(&self) -> bool {
+        self.length.is_some()
+    }
+
+    /// What the clock shows now
+    pub fn reading(&self) -> String {
+        self.reading_at(self.started.elapsed())
+    }
+
+    /// Whether a finished countdown is in the lit half of its flash
+    pub fn flashing(&self) -> bool {
+        self.flashing_at(self.started.elapsed())
+    }
+
+    fn reading_at(&self, elapsed: Duration) -> String {
+        match self.length {
+            None => format_elapsed(elapsed),
+            Some(length) if elapsed >= length => "time's up".to_string(),
+            // Round up so the clock reads 0:00 only once the time has run out
+            Some(length) => format_elapsed((length - elapsed) + Duration::from_millis(999)),
+        }
+    }
+
+    fn flashing_at(&self, elapsed: Duration) -> bool {
+        match self.length.and_then(|length| elapsed.checked_sub(length)) {
+            Some(over) if over < TIMER_FLASH => (over.as_millis() / TIMER_FLASH_PERIOD.as_millis()).is_multiple_of(2),
+            _ => false,
+        }
+    }
+}
+
+/// Formats a duration as h:mm:ss, or m:ss when under an hour
+pub fn format_elapsed(duration: Duration) -> String {
+    let total = duration.as_secs();
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(75)), "1:15");
+        assert_eq!(format_elapsed(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn test_countdown_reads_down_then_flashes() {
+        let timer = WritingTimer::countdown(25);
+        assert_eq!(timer.reading_at(Duration::from_millis(500)), "25:00");
+        assert_eq!(timer.reading_at(Duration::from_secs(24 * 60 + 1)), "0:59");
+        assert_eq!(timer.reading_at(Duration::from_secs(25 * 60)), "time's up");
+        assert!(!timer.flashing_at(Duration::from_secs(60)));
+        assert!(timer.flashing_at(Duration::from_secs(25 * 60)));
+        assert!(!timer.flashing_at(Duration::from_millis(25 * 60_000 + 700)));
+        assert!(!timer.flashing_at(Duration::from_secs(26 * 60)));
+        assert_eq!(WritingTimer::count_up().reading_at(Duration::from_secs(75)), "1:15");
+    }
+
+    #[test]
+    fn test_record_visit_counts() {
+        let mut stats = SessionStats::new();
+        stats.record_visit(true);
+        stats.record_visit(false);
+        assert_eq!(stats.words_visited, 2);
+        assert_eq!(stats.words_highlighted, 1);
+    }
+}
@@ -0,0 +1,240 @@
+//! Words too common to scatter: a built-in English list, or the user's own list for another language,
+//! adjusted by the user's own additions and removals.
+
+use crate::config;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+const BUILTIN: &[&str] = &[
+    "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on",
+    "with", "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we",
+    "say", "her", "she", "or", "an", "will", "my", "one", "all", "would", "there", "their",
+    "what", "so", "up", "out", "if", "about", "who", "get", "which", "go", "me", "when",
+    "make", "can", "like", "time", "no", "just", "him", "know", "take", "people", "into",
+    "year", "your", "good", "some", "could", "them", "see", "other", "than", "then", "now",
+    "look", "only", "come", "its", "over", "think", "also", "back", "after", "use", "two",
+    "how", "our", "work", "first", "well", "way", "even", "new", "want", "because", "any",
+    "these", "give", "day", "most", "us", "is", "was", "are", "been", "has", "had", "were",
+    "said", "did", "having", "may", "should", "am", "being", "does",
+];
+
+/// Words that come from chapter headings rather than the text itself, besides Roman numerals
+const HEADING_WORDS: &[&str] = &["chapter", "chapters", "contents", "canto", "cantos"];
+
+/// Real words that happen to be valid Roman numerals
+const NUMERAL_LOOKALIKES: &[&str] = &["mix"];
+
+/// Whether a word is heading noise: a Roman numeral such as `xvii`, or a word like `chapter`
+pub fn is_heading_word(word: &str) -> bool {
+    HEADING_WORDS.contains(&word) || (is_roman_numeral(word) && !NUMERAL_LOOKALIKES.contains(&word))
+}
+
+/// Whether a lowercase word is a well-formed Roman numeral from 1 to 3999
+fn is_roman_numeral(word: &str) -> bool {
+    let value = |c: char| match c {
+        'i' => Some(1),
+        'v' => Some(5),
+        'x' => Some(10),
+        'l' => Some(50),
+        'c' => Some(100),
+        'd' => Some(500),
+        'm' => Some(1000),
+        _ => None,
+    };
+    let Some(values) = word.chars().map(value).collect::<Option<Vec<u32>>>() else {
+        return false;
+    };
+
+    // Subtractive pairs count negatively; re-encoding the total rejects malformed strings like "iiv"
+    let total: u32 = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| if values.get(i + 1).is_some_and(|&next| next > v) { 0u32.wrapping_sub(v) } else { v })
+        .fold(0u32, u32::wrapping_add);
+    (1..4000).contains(&total) && to_roman(total) == word
+}
+
+fn to_roman(mut number: u32) -> String {
+    const NUMERALS: &[(u32, &str)] = &[
+        (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"), (100, "c"), (90, "xc"),
+        (50, "l"), (40, "xl"), (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i"),
+    ];
+    let mut roman = String::new();
+    for &(value, numeral) in NUMERALS {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+    roman
+}
+
+/// How a `--stop-words` list combines with the built-in one
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ListMode {
+    #[default]
+    Extend,  // Leave out the list's words as well as the built-in English ones
+    Replace,  // Leave out only the list's words
+}
+
+impl ListMode {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "extend" => Ok(Self::Extend),
+            "replace" => Ok(Self::Replace),
+            _ => Err(format!("unknown stop word mode '{}' (expected extend or replace)", value)),
+        }
+    }
+}
+
+/// The effective stop-word list, saved as changes to the base list
+pub struct StopWords {
+    base: BTreeSet<String>,     // The built-in list, with or replaced by a `--stop-words` list
+    added: BTreeSet<String>,    // Extra words to leave out
+    removed: BTreeSet<String>,  // Base stop words to scatter after all
+}
+
+impl Default for StopWords {
+    fn default() -> Self {
+        Self {
+            base: BUILTIN.iter().map(|word| word.to_string()).collect(),
+            added: BTreeSet::new(),
+            removed: BTreeSet::new(),
+        }
+    }
+}
+
+impl StopWords {
+    /// Load the user's changes from `stop_words.txt` in the config directory, if there are any
+    pub fn load() -> Self {
+        config::get_config_dir()
+            .ok()
+            .and_then(|dir| fs::read_to_string(dir.join("stop_words.txt")).ok())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Use the stop words in the file at `path`, one per line, alongside or instead of the built-in list
+    pub fn use_list(&mut self, path: &Path, mode: ListMode) -> Result<(), String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        self.set_list(&content, mode);
+        Ok(())
+    }
+
+    /// One word per line; blank lines and `#` comments are skipped
+    fn set_list(&mut self, content: &str, mode: ListMode) {
+        if mode == ListMode::Replace {
+            self.base.clear();
+        }
+        let words = content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        self.base.extend(words.map(str::to_lowercase));
+    }
+
+    /// One word per line to add to the list, or `-word` to take a base word off it
+    fn parse(content: &str) -> Self {
+        let mut stop_words = Self::default();
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            match line.strip_prefix('-') {
+                Some(word) => stop_words.removed.insert(word.to_lowercase()),
+                None => stop_words.added.insert(line.to_lowercase()),
+            };
+        }
+        stop_words
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut content = String::from("# Added stop words, and built-in ones removed with '-'\n");
+        for word in &self.added {
+            content.push_str(&format!("{}\n", word));
+        }
+        for word in &self.removed {
+            content.push_str(&format!("-{}\n", word));
+        }
+        fs::write(config::get_config_dir()?.join("stop_words.txt"), content)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        (self.base.contains(word) || self.added.contains(word)) && !self.removed.contains(word)
+    }
+
+    /// Every stop word in effect, sorted
+    pub fn words(&self) -> BTreeSet<String> {
+        self.base
+            .iter()
+            .chain(self.added.iter())
+            .filter(|word| !self.removed.contains(*word))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the user added this word, rather than it being in the base list
+    pub fn is_added(&self, word: &str) -> bool {
+        self.added.contains(word)
+    }
+
+    /// Put a word on the list if it isn't there, or take it off if it is; returns whether it's now a stop word
+    pub fn toggle(&mut self, word: &str) -> bool {
+        let word = word.trim().to_lowercase();
+        let in_base = self.base.contains(&word);
+        if self.contains(&word) {
+            self.added.remove(&word);
+            if in_base {
+                self.removed.insert(word);
+            }
+            false
+        } else {
+            self.removed.remove(&word);
+            if !in_base {
+                self.added.insert(word);
+            }
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_and_parse() {
+        let mut stop_words = StopWords::parse("# mine\nlantern\n-people\n");
+        assert!(stop_words.contains("the"));
+        assert!(stop_words.contains("lantern"));
+        assert!(!stop_words.contains("people"));
+
+        assert!(!stop_words.toggle("the"));
+        assert!(!stop_words.contains("the"));
+        assert!(stop_words.toggle("People"));
+        assert!(stop_words.contains("people"));
+        assert!(!stop_words.toggle("lantern"));
+        assert!(!stop_words.is_added("lantern"));
+    }
+
+    #[test]
+    fn test_list_extends_or_replaces() {
+        let mut extended = StopWords::default();
+        extended.set_list("# Spanish\nque\nDel\n", ListMode::Extend);
+        assert!(extended.contains("que") && extended.contains("del") && extended.contains("the"));
+
+        let mut replaced = StopWords::parse("-que\nlantern\n");
+        replaced.set_list("que\ndel\n", ListMode::Replace);
+        assert!(!replaced.contains("the"));
+        assert!(!replaced.contains("que"));
+        assert!(replaced.contains("del") && replaced.contains("lantern"));
+        assert!(replaced.toggle("the"));
+        assert!(replaced.is_added("the"));
+    }
+
+    #[test]
+    fn test_heading_words() {
+        for word in ["xvii", "iii", "mcmxc", "chapter", "xl"] {
+            assert!(is_heading_word(word), "{}", word);
+        }
+        for word in ["mix", "dim", "civil", "vivid", "iiv", "lantern"] {
+            assert!(!is_heading_word(word), "{}", word);
+        }
+    }
+}
@@ -1,7 +1,82 @@
+use crate::config;
 use ratatui::{
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     widgets::BorderType,
 };
+use std::fs;
+
+/// Names accepted by `--theme` without a user theme file
+pub const BUILTIN_THEMES: &[&str] = &[
+    "monochrome",
+    "lightmono",
+    "redmono",
+    "softmono",
+    "bluemono",
+    "nord",
+    "nord-bg",
+    "gruvbox",
+    "rosepine",
+    "goldgreen-light",
+    "goldgreen-dark",
+    "high-contrast",
+    "high-contrast-light",
+    "colorblind",
+];
+
+/// Every theme `--theme` accepts: the built-in ones, then user themes by name
+pub fn theme_names() -> Vec<String> {
+    let mut user_themes: Vec<String> = config::get_config_dir()
+        .ok()
+        .and_then(|dir| fs::read_dir(dir.join("themes")).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_stem()?.to_str()?.to_string();
+            (path.extension()? == "theme" && !BUILTIN_THEMES.contains(&name.as_str())).then_some(name)
+        })
+        .collect();
+    user_themes.sort();
+    BUILTIN_THEMES.iter().map(|name| name.to_string()).chain(user_themes).collect()
+}
+
+/// Border types in the order the runtime toggle cycles through them
+const BORDER_CYCLE: [BorderType; 4] = [
+    BorderType::Plain,
+    BorderType::Rounded,
+    BorderType::Double,
+    BorderType::Thick,
+];
+
+/// Whether the terminal is sent colors
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorChoice {
+    #[default]
+    Auto,  // Unless NO_COLOR is set
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(format!("unknown color choice '{}' (expected always, never or auto)", value)),
+        }
+    }
+
+    /// Whether to use colors, given the value of NO_COLOR; set to anything but an empty string, it turns
+    /// them off unless they're asked for (https://no-color.org)
+    pub fn use_color(self, no_color: Option<&std::ffi::OsStr>) -> bool {
+        match self {
+            Self::Auto => no_color.is_none_or(|value| value.is_empty()),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct AppStyling {
@@ -11,29 +86,126 @@ pub struct AppStyling {
     pub selected_text_style: Style,
     pub current_selected_style: Style,  // Style for currently selected word (brighter)
     pub density_bar_style: Style,  // Style for filled portion of density bar
+    pub second_voice_style: Style,  // Unvisited words from the second corpus in dialogue mode
     pub border_type: BorderType,
     pub use_background_fill: bool,  // Whether to fill backgrounds (for monochrome theme)
 }
 
 impl AppStyling {
     pub fn from_theme(theme: &str) -> Result<Self, String> {
+        if let Some(styling) = Self::builtin_theme(theme) {
+            return Ok(styling);
+        }
+
+        Self::from_user_theme(theme).unwrap_or_else(|| Err(format!(
+            "Invalid theme '{}'. Valid themes: {} (or a user theme in the themes config folder)",
+            theme,
+            BUILTIN_THEMES.join(", ")
+        )))
+    }
+
+    fn builtin_theme(theme: &str) -> Option<Self> {
         match theme.to_lowercase().as_str() {
-            "monochrome" => Ok(Self::monochrome_theme()),
-            "lightmono" => Ok(Self::lightmono_theme()),
-            "redmono" => Ok(Self::redmono_theme()),
-            "softmono" => Ok(Self::softmono_theme()),
-            "bluemono" => Ok(Self::bluemono_theme()),
-            "nord" => Ok(Self::nord_theme()),
-            "nord-bg" => Ok(Self::nord_bg_theme()),
-            "gruvbox" => Ok(Self::gruvbox_theme()),
-            "rosepine" => Ok(Self::rosepine_theme()),
-            "goldgreen-light" => Ok(Self::goldgreen_light_theme()),
-            "goldgreen-dark" => Ok(Self::goldgreen_dark_theme()),
-            _ => Err(format!(
-                "Invalid theme '{}'. Valid themes: monochrome, lightmono, redmono, softmono, bluemono, nord, nord-bg, gruvbox, rosepine, goldgreen-light, goldgreen-dark",
-                theme
-            )),
+            "monochrome" => Some(Self::monochrome_theme()),
+            "lightmono" => Some(Self::lightmono_theme()),
+            "redmono" => Some(Self::redmono_theme()),
+            "softmono" => Some(Self::softmono_theme()),
+            "bluemono" => Some(Self::bluemono_theme()),
+            "nord" => Some(Self::nord_theme()),
+            "nord-bg" => Some(Self::nord_bg_theme()),
+            "gruvbox" => Some(Self::gruvbox_theme()),
+            "rosepine" => Some(Self::rosepine_theme()),
+            "goldgreen-light" => Some(Self::goldgreen_light_theme()),
+            "goldgreen-dark" => Some(Self::goldgreen_dark_theme()),
+            "high-contrast" => Some(Self::high_contrast_theme()),
+            "high-contrast-light" => Some(Self::high_contrast_light_theme()),
+            "colorblind" => Some(Self::colorblind_theme()),
+            _ => None,
+        }
+    }
+
+    /// Load `<config dir>/themes/<name>.theme`, returning None if no such file exists
+    fn from_user_theme(name: &str) -> Option<Result<Self, String>> {
+        let path = config::get_config_dir()
+            .ok()?
+            .join("themes")
+            .join(format!("{}.theme", name));
+        let content = fs::read_to_string(&path).ok()?;
+
+        Some(Self::parse_theme_file(&content).map_err(|e| format!("Theme file '{}': {}", path.display(), e)))
+    }
+
+    /// Parse a user theme: `key = value` lines, starting from `base` (a built-in theme, monochrome by default)
+    ///
+    /// Style values are `FG [on BG] [bold] [dim] [italic] [underline] [reversed]`, where colors are
+    /// `#rrggbb`, a named terminal color, or `default`.
+    fn parse_theme_file(content: &str) -> Result<Self, String> {
+        let entries: Vec<(usize, &str, &str)> = content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(number, line)| {
+                line.split_once('=')
+                    .map(|(key, value)| (number, key.trim(), value.trim()))
+                    .ok_or_else(|| format!("line {}: expected 'key = value'", number))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let base = entries
+            .iter()
+            .find(|(_, key, _)| *key == "base")
+            .map(|(_, _, value)| *value)
+            .unwrap_or("monochrome");
+        let mut theme = Self::builtin_theme(base).ok_or_else(|| format!("unknown base theme '{}'", base))?;
+
+        for (number, key, value) in entries {
+            let error = |e: String| format!("line {}: {}", number, e);
+            match key {
+                "base" => {}
+                "border" => theme.border_style = parse_style(value).map_err(error)?,
+                "canvas_border" => theme.highlighted_border_style = parse_style(value).map_err(error)?,
+                "text" => theme.text_style = parse_style(value).map_err(error)?,
+                "visited" => theme.selected_text_style = parse_style(value).map_err(error)?,
+                "current" => theme.current_selected_style = parse_style(value).map_err(error)?,
+                "density_bar" => theme.density_bar_style = parse_style(value).map_err(error)?,
+                "second_voice" => theme.second_voice_style = parse_style(value).map_err(error)?,
+                "border_type" => theme.border_type = parse_border_type(value).map_err(error)?,
+                "background_fill" => {
+                    theme.use_background_fill = value
+                        .parse()
+                        .map_err(|_| error(format!("expected true or false, got '{}'", value)))?
+                }
+                _ => return Err(error(format!("unknown key '{}'", key))),
+            }
         }
+
+        Ok(theme)
+    }
+
+    /// The same borders with no colors at all, the terminal's own foreground and background throughout:
+    /// visited words are bold and the current word reversed as well
+    pub fn without_color(&self) -> Self {
+        Self {
+            border_style: Style::default(),
+            highlighted_border_style: Style::default(),
+            text_style: Style::default(),
+            selected_text_style: Style::default().add_modifier(Modifier::BOLD),
+            current_selected_style: Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            density_bar_style: Style::default(),
+            second_voice_style: Style::default().add_modifier(Modifier::ITALIC),
+            border_type: self.border_type,
+            use_background_fill: false,
+        }
+    }
+
+    /// Switch to the next border type (plain, rounded, double, thick)
+    pub fn cycle_border_type(&mut self) {
+        let current = BORDER_CYCLE
+            .iter()
+            .position(|&border_type| border_type == self.border_type)
+            .unwrap_or(0);
+        self.border_type = BORDER_CYCLE[(current + 1) % BORDER_CYCLE.len()];
     }
 
     // Nord theme
@@ -53,6 +225,7 @@ impl AppStyling {
             current_selected_style: Self::hex_style(NORD_BG)
                 .bg(Self::hex_color(NORD_FROST_CYAN)),  // Dark on cyan for current selection
             density_bar_style: Self::hex_style(NORD_FROST_BLUE),  // Same as border
+            second_voice_style: Self::hex_style(NORD_FROST_CYAN),  // Cyan answers the light foreground
             border_type: BorderType::Plain,
             use_background_fill: false,  // No background fill for nord theme
         }
@@ -74,6 +247,7 @@ impl AppStyling {
             current_selected_style: Self::hex_style(NORD_BG)
                 .bg(Self::hex_color(NORD_FROST_CYAN)),  // Dark on cyan for current selection
             density_bar_style: Self::hex_style(NORD_FROST_BLUE).bg(Self::hex_color(NORD_BG)),  // Same as border
+            second_voice_style: Self::hex_style(NORD_FROST_CYAN).bg(Self::hex_color(NORD_BG)),
             border_type: BorderType::Plain,
             use_background_fill: true,  // No background fill for nord theme
         }
@@ -96,6 +270,7 @@ impl AppStyling {
             current_selected_style: Self::hex_style(GRUVBOX_DARK)
                 .bg(Self::hex_color(GRUVBOX_YELLOW)),  // Dark text on yellow background for current selection
             density_bar_style: Self::hex_style(GRUVBOX_FG).bg(Self::hex_color(GRUVBOX_BG)),  // Same as border
+            second_voice_style: Self::hex_style(GRUVBOX_ORANGE).bg(Self::hex_color(GRUVBOX_BG)),
             border_type: BorderType::Plain,
             use_background_fill: true,  // Enable background fill for gruvbox theme
         }
@@ -114,6 +289,7 @@ impl AppStyling {
             current_selected_style: Self::hex_style(BLACK)
                 .bg(Self::hex_color(BLACK)),  
             density_bar_style: Self::hex_style(BLACK),  
+            second_voice_style: Self::hex_style(BLACK).add_modifier(Modifier::ITALIC),  
             border_type: BorderType::Plain,
             use_background_fill: false,  
         }
@@ -130,6 +306,7 @@ impl AppStyling {
                 .bg(Color::Black),  // Black on black = solid black boxes
             current_selected_style: Style::default().fg(Color::Black),  // Black text, no background (default state)
             density_bar_style: Style::default().fg(Color::Black).bg(Color::White),  // Same as border
+            second_voice_style: Style::default().fg(Color::Black).bg(Color::White).add_modifier(Modifier::ITALIC),  // No second color, so italics
             border_type: BorderType::Plain,
             use_background_fill: true,  // Enable background fill for seamless white background
         }
@@ -147,6 +324,7 @@ impl AppStyling {
                 .bg(Color::Black),  // Black on black = solid black boxes (previously visited + toggled current)
             current_selected_style: Style::default().fg(Color::Black),  // Black text, no background (default state)
             density_bar_style: Style::default().fg(Color::Black).bg(Self::hex_color(SOFT_WHITE)),  // Same as border
+            second_voice_style: Style::default().fg(Color::Black).bg(Self::hex_color(SOFT_WHITE)).add_modifier(Modifier::ITALIC),
             border_type: BorderType::Plain,
             use_background_fill: true,  // Enable background fill for seamless soft white background
         }
@@ -165,6 +343,7 @@ impl AppStyling {
                 .fg(Color::Black)
                 .bg(Color::Black),  // Black on black = darker highlight (default state)
             density_bar_style: Style::default().fg(Color::Black).bg(Self::hex_color(SOFT_WHITE)),  // Same as border
+            second_voice_style: Style::default().fg(Color::Black).bg(Self::hex_color(SOFT_WHITE)).add_modifier(Modifier::ITALIC),
             border_type: BorderType::Plain,
             use_background_fill: true,  // Enable background fill for seamless soft white background
         }
@@ -180,6 +359,7 @@ impl AppStyling {
             selected_text_style: Self::hex_style(MONO_COLOR).bg(Self::hex_color(MONO_COLOR)),  
             current_selected_style: Self::hex_style(MONO_COLOR),
             density_bar_style: Self::hex_style(MONO_COLOR),  
+            second_voice_style: Self::hex_style(MONO_COLOR).add_modifier(Modifier::ITALIC),  
             border_type: BorderType::Plain,
             use_background_fill: false,  
         }
@@ -203,6 +383,7 @@ impl AppStyling {
             current_selected_style: Self::hex_style(ROSE_BG)
                 .bg(Self::hex_color(ROSE_GOLD)),  // Dark on warm gold for current selection
             density_bar_style: Self::hex_style(ROSE_LOVE).bg(Self::hex_color(ROSE_BG)),  // Rose pink like highlighted text
+            second_voice_style: Self::hex_style(ROSE_IRIS).bg(Self::hex_color(ROSE_BG)),  // Soft purple second voice
             border_type: BorderType::Plain,
             use_background_fill: true,  // Enable background fill for rose pine theme
         }
@@ -219,6 +400,7 @@ impl AppStyling {
             selected_text_style: Self::hex_style(GREEN).bg(Self::hex_color(GOLD)),  
             current_selected_style: Self::hex_style(GREEN).bg(Self::hex_color(GOLD)),  
             density_bar_style: Self::hex_style(GOLD).bg(Self::hex_color(GREEN)),  
+            second_voice_style: Self::hex_style(GOLD).bg(Self::hex_color(GREEN)).add_modifier(Modifier::ITALIC),  
             border_type: BorderType::Plain,
             use_background_fill: true,  
         }
@@ -235,11 +417,80 @@ impl AppStyling {
             selected_text_style: Self::hex_style(GOLD).bg(Self::hex_color(GREEN)),  
             current_selected_style: Self::hex_style(GOLD).bg(Self::hex_color(GREEN)),  
             density_bar_style: Self::hex_style(GREEN).bg(Self::hex_color(GOLD)),  
+            second_voice_style: Self::hex_style(GREEN).bg(Self::hex_color(GOLD)).add_modifier(Modifier::ITALIC),  
             border_type: BorderType::Plain,
             use_background_fill: true,  
         }
     }
 
+    // High contrast themes: pure black/white (21:1), states differ by modifiers rather than hue
+    fn high_contrast_theme() -> Self {
+        const BLACK: &str = "#000000";
+        const WHITE: &str = "#ffffff";
+
+        Self {
+            border_style: Self::hex_style(WHITE).bg(Self::hex_color(BLACK)),
+            highlighted_border_style: Self::hex_style(WHITE).bg(Self::hex_color(BLACK)),
+            text_style: Self::hex_style(WHITE).bg(Self::hex_color(BLACK)),
+            selected_text_style: Self::hex_style(WHITE)
+                .bg(Self::hex_color(BLACK))
+                .add_modifier(Modifier::UNDERLINED),  // Visited words are underlined
+            current_selected_style: Self::hex_style(BLACK)
+                .bg(Self::hex_color(WHITE))
+                .add_modifier(Modifier::BOLD),  // Current word is inverted and bold
+            density_bar_style: Self::hex_style(WHITE).bg(Self::hex_color(BLACK)),
+            second_voice_style: Self::hex_style(WHITE).bg(Self::hex_color(BLACK)).add_modifier(Modifier::ITALIC),
+            border_type: BorderType::Thick,  // Heavier lines are easier to see
+            use_background_fill: true,
+        }
+    }
+
+    fn high_contrast_light_theme() -> Self {
+        const BLACK: &str = "#000000";
+        const WHITE: &str = "#ffffff";
+
+        Self {
+            border_style: Self::hex_style(BLACK).bg(Self::hex_color(WHITE)),
+            highlighted_border_style: Self::hex_style(BLACK).bg(Self::hex_color(WHITE)),
+            text_style: Self::hex_style(BLACK).bg(Self::hex_color(WHITE)),
+            selected_text_style: Self::hex_style(BLACK)
+                .bg(Self::hex_color(WHITE))
+                .add_modifier(Modifier::UNDERLINED),  // Visited words are underlined
+            current_selected_style: Self::hex_style(WHITE)
+                .bg(Self::hex_color(BLACK))
+                .add_modifier(Modifier::BOLD),  // Current word is inverted and bold
+            density_bar_style: Self::hex_style(BLACK).bg(Self::hex_color(WHITE)),
+            second_voice_style: Self::hex_style(BLACK).bg(Self::hex_color(WHITE)).add_modifier(Modifier::ITALIC),
+            border_type: BorderType::Thick,  // Heavier lines are easier to see
+            use_background_fill: true,
+        }
+    }
+
+    // Colorblind-safe theme using the Okabe-Ito blue/orange pair, which stays distinct
+    // under deuteranopia and protanopia; states also differ by underline/reverse
+    fn colorblind_theme() -> Self {
+        const BG: &str = "#1a1a1a";
+        const FG: &str = "#f0f0f0"; // ~16:1 against BG
+        const BLUE: &str = "#56b4e9"; // Okabe-Ito sky blue
+        const ORANGE: &str = "#e69f00"; // Okabe-Ito orange
+
+        Self {
+            border_style: Self::hex_style(BLUE).bg(Self::hex_color(BG)),
+            highlighted_border_style: Self::hex_style(BLUE).bg(Self::hex_color(BG)),
+            text_style: Self::hex_style(FG).bg(Self::hex_color(BG)),
+            selected_text_style: Self::hex_style(BLUE)
+                .bg(Self::hex_color(BG))
+                .add_modifier(Modifier::UNDERLINED),  // Blue underlined for visited
+            current_selected_style: Self::hex_style(BG)
+                .bg(Self::hex_color(ORANGE))
+                .add_modifier(Modifier::BOLD),  // Dark on orange block for current
+            density_bar_style: Self::hex_style(ORANGE).bg(Self::hex_color(BG)),
+            second_voice_style: Self::hex_style(ORANGE).bg(Self::hex_color(BG)),  // Orange voice against the plain foreground
+            border_type: BorderType::Plain,
+            use_background_fill: true,
+        }
+    }
+
     // Helper to convert hex string to Color
     fn hex_color(hex: &str) -> Color {
         let hex = hex.trim_start_matches('#');
@@ -254,3 +505,145 @@ impl AppStyling {
         Style::default().fg(Self::hex_color(hex))
     }
 }
+
+fn parse_border_type(value: &str) -> Result<BorderType, String> {
+    match value.to_lowercase().as_str() {
+        "plain" => Ok(BorderType::Plain),
+        "rounded" => Ok(BorderType::Rounded),
+        "double" => Ok(BorderType::Double),
+        "thick" => Ok(BorderType::Thick),
+        _ => Err(format!("unknown border type '{}' (plain, rounded, double, thick)", value)),
+    }
+}
+
+fn parse_color(value: &str) -> Result<Option<Color>, String> {
+    if value.starts_with('#') {
+        let hex = value.trim_start_matches('#');
+        if hex.len() != 6 || u32::from_str_radix(hex, 16).is_err() {
+            return Err(format!("invalid hex color '{}'", value));
+        }
+        return Ok(Some(AppStyling::hex_color(value)));
+    }
+
+    match value.to_lowercase().as_str() {
+        "default" => Ok(None),
+        "black" => Ok(Some(Color::Black)),
+        "red" => Ok(Some(Color::Red)),
+        "green" => Ok(Some(Color::Green)),
+        "yellow" => Ok(Some(Color::Yellow)),
+        "blue" => Ok(Some(Color::Blue)),
+        "magenta" => Ok(Some(Color::Magenta)),
+        "cyan" => Ok(Some(Color::Cyan)),
+        "gray" | "grey" => Ok(Some(Color::Gray)),
+        "white" => Ok(Some(Color::White)),
+        _ => Err(format!("unknown color '{}'", value)),
+    }
+}
+
+/// Parse `FG [on BG] [modifiers...]`, e.g. `#2e3440 on #88c0d0 bold`
+fn parse_style(value: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut tokens = value.split_whitespace();
+
+    if let Some(fg) = tokens.next() {
+        if let Some(color) = parse_color(fg)? {
+            style = style.fg(color);
+        }
+    }
+
+    while let Some(token) = tokens.next() {
+        match token.to_lowercase().as_str() {
+            "on" => {
+                let bg = tokens.next().ok_or("expected a color after 'on'")?;
+                if let Some(color) = parse_color(bg)? {
+                    style = style.bg(color);
+                }
+            }
+            "bold" => style = style.add_modifier(Modifier::BOLD),
+            "dim" => style = style.add_modifier(Modifier::DIM),
+            "italic" => style = style.add_modifier(Modifier::ITALIC),
+            "underline" => style = style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style = style.add_modifier(Modifier::REVERSED),
+            other => return Err(format!("unknown style word '{}'", other)),
+        }
+    }
+
+    Ok(style)
+}
+
+/// Modifiers font-style mode chooses between; plain comes up twice so variety doesn't become noise
+const FONT_STYLES: [Modifier; 6] = [
+    Modifier::empty(),
+    Modifier::empty(),
+    Modifier::BOLD,
+    Modifier::ITALIC,
+    Modifier::DIM,
+    Modifier::BOLD.union(Modifier::ITALIC),
+];
+
+/// A word's font style in the scatter made from `seed`: the same for the same seed and word, so a
+/// replayed scatter looks the same
+pub fn font_style(seed: u64, word: &str) -> Modifier {
+    // FNV-1a over the word, then a SplitMix64 finish with the seed, which unlike the std hasher is
+    // the same in every build
+    let mut hash = word.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+    hash ^= seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^= hash >> 31;
+    FONT_STYLES[(hash % FONT_STYLES.len() as u64) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style() {
+        let style = parse_style("#2e3440 on #88c0d0 bold").unwrap();
+        assert_eq!(style.fg, Some(Color::Rgb(0x2e, 0x34, 0x40)));
+        assert_eq!(style.bg, Some(Color::Rgb(0x88, 0xc0, 0xd0)));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+        assert!(parse_style("chartreuse").is_err());
+    }
+
+    #[test]
+    fn test_parse_theme_file() {
+        let theme = AppStyling::parse_theme_file(
+            "# my theme\nbase = nord\nborder_type = double\ncurrent = black on yellow\n",
+        )
+        .unwrap();
+        assert_eq!(theme.border_type, BorderType::Double);
+        assert_eq!(theme.current_selected_style.bg, Some(Color::Yellow));
+        assert!(AppStyling::parse_theme_file("border_type = wavy").is_err());
+    }
+
+    #[test]
+    fn test_font_style_stable_per_seed() {
+        assert_eq!(font_style(42, "lantern"), font_style(42, "lantern"));
+        let styles: std::collections::HashSet<Modifier> = (0..200).map(|seed| font_style(seed, "lantern")).collect();
+        assert_eq!(styles.len(), 5);
+    }
+
+    #[test]
+    fn test_no_color_drops_colors() {
+        assert!(ColorChoice::Auto.use_color(None));
+        assert!(ColorChoice::Auto.use_color(Some("".as_ref())));
+        assert!(!ColorChoice::Auto.use_color(Some("1".as_ref())));
+        assert!(ColorChoice::Always.use_color(Some("1".as_ref())));
+
+        let theme = AppStyling::from_theme("nord-bg").unwrap().without_color();
+        let styles = [theme.border_style, theme.text_style, theme.selected_text_style, theme.current_selected_style];
+        assert!(styles.iter().all(|style| style.fg.is_none() && style.bg.is_none()));
+        assert!(theme.current_selected_style.add_modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_cycle_border_type() {
+        // Built-in themes start plain; rounded borders are chosen in a theme file or with the cycle key
+        assert_eq!(AppStyling::from_theme("rosepine").unwrap().border_type, BorderType::Plain);
+        let mut theme = AppStyling::from_theme("monochrome").unwrap();
+        theme.cycle_border_type();
+        assert_eq!(theme.border_type, BorderType::Rounded);
+    }
+}
@@ -0,0 +1,73 @@
+//! Suggested lines from a language model, reached through a command the user configures: the prompt
+//! goes to the command's stdin and each line it prints back is a suggestion.
+
+use crate::transform;
+use std::sync::mpsc::{self, Receiver};
+
+/// How many lines are asked for and shown
+pub const SUGGESTION_COUNT: usize = 3;
+
+/// What the suggestion panel shows
+pub enum Suggestions {
+    Waiting,
+    Lines(Vec<String>),
+    Failed(String),
+}
+
+/// The instruction sent to the model, with the words to build from
+fn prompt(words: &[String]) -> String {
+    format!(
+        "Write {} short lines of poetry, one per line, using only these words (each may be used more than once), \
+         with no numbering or commentary:\n{}\n",
+        SUGGESTION_COUNT,
+        words.join(" ")
+    )
+}
+
+/// The model's reply as lines of words, with list markers and quotes taken off
+fn parse(reply: &str) -> Vec<String> {
+    reply
+        .lines()
+        .map(|line| strip_marker(line.trim()).trim().trim_matches(['"', '“', '”']).trim().to_string())
+        .filter(|line| !line.is_empty())
+        .take(SUGGESTION_COUNT)
+        .collect()
+}
+
+/// A line without the `1.`, `2)` or `-` a model may have put in front of it
+fn strip_marker(line: &str) -> &str {
+    let unnumbered = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    match unnumbered.strip_prefix(['.', ')']) {
+        Some(rest) if unnumbered.len() < line.len() => rest,
+        _ => line.strip_prefix(['-', '*', '•']).unwrap_or(line),
+    }
+}
+
+/// Ask `command` for lines made of `words` on a background thread, so the canvas stays live while the
+/// model thinks; the answer arrives on the returned channel
+pub fn request(command: String, words: Vec<String>) -> Receiver<Suggestions> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let suggestions = match transform::pipe(&command, prompt(&words)) {
+            Ok(reply) => match parse(&reply) {
+                lines if lines.is_empty() => Suggestions::Failed("no lines came back".to_string()),
+                lines => Suggestions::Lines(lines),
+            },
+            Err(e) => Suggestions::Failed(e),
+        };
+        let _ = sender.send(suggestions);
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_parsed_into_lines() {
+        let reply = "1. the lantern drowns\n\n2) \"harbor of salt\"\n- 3 lanterns\n* one too many\n";
+        assert_eq!(parse(reply), vec!["the lantern drowns", "harbor of salt", "3 lanterns"]);
+        assert!(prompt(&["tide".to_string(), "moon".to_string()]).ends_with("\ntide moon\n"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Scatter templates: the positions and lengths of a layout's words without the words themselves,
+//! saved in the config directory so the same skeleton can be filled with fresh words later.
+
+use crate::config;
+use crate::embed;
+use crate::scatters::{ScatteredWord, Slot};
+use std::fs;
+use std::path::PathBuf;
+
+pub struct Template {
+    pub name: String,
+    pub size: (u16, u16), // Canvas size the layout was made on
+    pub slots: Vec<Slot>,
+}
+
+impl Template {
+    /// The skeleton of a scatter generated on a `size` canvas
+    pub fn from_words(name: &str, words: &[ScatteredWord], size: (u16, u16)) -> Self {
+        Self {
+            name: name.to_string(),
+            size,
+            slots: words
+                .iter()
+                .map(|word| Slot { x: word.x, y: word.y, len: word.word.chars().count() as u16 })
+                .collect(),
+        }
+    }
+
+    /// The slots moved in proportion to a `width` x `height` canvas, leaving out any that no longer fit
+    pub fn slots_for(&self, width: u16, height: u16) -> Vec<Slot> {
+        let (template_width, template_height) = (self.size.0.max(1) as u32, self.size.1.max(1) as u32);
+        self.slots
+            .iter()
+            .map(|slot| Slot {
+                x: (slot.x as u32 * width as u32 / template_width) as u16,
+                y: (slot.y as u32 * height as u32 / template_height) as u16,
+                len: slot.len,
+            })
+            .filter(|slot| slot.x.checked_add(slot.len).is_some_and(|end| end <= width) && slot.y < height)
+            .collect()
+    }
+
+    /// A `WIDTHxHEIGHT` line, then one `x<TAB>y<TAB>length` line per slot
+    fn to_text(&self) -> String {
+        let mut text = format!("{}x{}\n", self.size.0, self.size.1);
+        for slot in &self.slots {
+            text.push_str(&format!("{}\t{}\t{}\n", slot.x, slot.y, slot.len));
+        }
+        text
+    }
+
+    fn parse(name: &str, content: &str) -> Result<Self, String> {
+        let mut lines = content.lines();
+        let size = embed::parse_size(lines.next().unwrap_or("").trim())?;
+        let slots = lines
+            .filter_map(|line| match line.split('\t').map(str::parse).collect::<Result<Vec<u16>, _>>() {
+                Ok(fields) if fields.len() == 3 && fields[2] > 0 => Some(Slot { x: fields[0], y: fields[1], len: fields[2] }),
+                _ => None,
+            })
+            .collect();
+        Ok(Self { name: name.to_string(), size, slots })
+    }
+}
+
+/// `templates/NAME.txt` in the config directory
+fn template_path(name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("'{}' isn't a usable template name", name).into());
+    }
+    let dir = config::get_config_dir()?.join("templates");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.txt", name)))
+}
+
+/// Save a template, replacing any of the same name
+pub fn save(template: &Template) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = template_path(&template.name)?;
+    fs::write(&path, template.to_text())?;
+    Ok(path)
+}
+
+/// A saved template; an unknown one is an error
+pub fn load(name: &str) -> Result<Template, Box<dyn std::error::Error>> {
+    let path = template_path(name)?;
+    let content = fs::read_to_string(&path)
+        .map_err(|_| format!("no template named '{}' (save one with :template save {})", name, name))?;
+    let template = Template::parse(name, &content)?;
+    if template.slots.is_empty() {
+        return Err(format!("template '{}' has no words", name).into());
+    }
+    Ok(template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_round_trip_and_scaling() {
+        let template = Template {
+            name: "harbor".to_string(),
+            size: (80, 24),
+            slots: vec![Slot { x: 10, y: 4, len: 7 }, Slot { x: 70, y: 20, len: 9 }],
+        };
+        let parsed = Template::parse("harbor", &template.to_text()).unwrap();
+        assert_eq!(parsed.size, (80, 24));
+        assert_eq!(parsed.slots, template.slots);
+
+        // Halving the canvas halves the positions; the second word no longer fits on its row
+        assert_eq!(parsed.slots_for(40, 12), vec![Slot { x: 5, y: 2, len: 7 }]);
+        assert!(Template::parse("broken", "wide\n1\t2\t3\n").is_err());
+    }
+
+    #[test]
+    fn test_slots_measured_in_characters_and_kept_in_bounds() {
+        let words = [ScatteredWord { word: "café".to_string(), x: 3, y: 1, source_file: String::new(), voice: None }];
+        assert_eq!(Template::from_words("cafe", &words, (20, 5)).slots, vec![Slot { x: 3, y: 1, len: 4 }]);
+
+        // A hand-edited slot running past the largest position is left out rather than overflowing
+        let edited = Template::parse("edited", "1000x10\n0\t0\t5\n999\t0\t65535\n").unwrap();
+        assert_eq!(edited.slots_for(1000, 10), vec![Slot { x: 0, y: 0, len: 5 }]);
+    }
+}
@@ -0,0 +1,382 @@
+//! Dates read from corpus file names (journals, chat logs) for chronological sampling.
+
+use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Inclusive span of days, stored as `YYYYMMDD` numbers so they compare in date order
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DateSpan {
+    start: u32,
+    end: u32,
+}
+
+impl DateSpan {
+    /// Span covered by a partial date: a whole year, a whole month, or a single day
+    fn from_parts(year: u32, month: Option<u32>, day: Option<u32>) -> Option<Self> {
+        match (month, day) {
+            (None, _) => Some(Self { start: year * 10000 + 101, end: year * 10000 + 1231 }),
+            (Some(month), _) if !(1..=12).contains(&month) => None,
+            (Some(month), None) => Some(Self {
+                start: year * 10000 + month * 100 + 1,
+                end: year * 10000 + month * 100 + 31,
+            }),
+            (Some(_), Some(day)) if !(1..=31).contains(&day) => None,
+            (Some(month), Some(day)) => {
+                let date = year * 10000 + month * 100 + day;
+                Some(Self { start: date, end: date })
+            }
+        }
+    }
+
+    /// Parse `2019`, `2019-03`, `2019-03-04`, or a `START..END` range of those
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let parse_date = |date: &str| {
+            parse_date(date.trim()).ok_or_else(|| format!("invalid date '{}', expected YYYY, YYYY-MM or YYYY-MM-DD", date))
+        };
+
+        match value.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (parse_date(start)?, parse_date(end)?);
+                if start.start > end.end {
+                    return Err(format!("range '{}' ends before it starts", value));
+                }
+                Ok(Self { start: start.start, end: end.end })
+            }
+            None => parse_date(value),
+        }
+    }
+
+    pub fn overlaps(&self, other: &DateSpan) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The span's last day, counted in days since 1970-01-01
+    fn last_day(&self) -> i64 {
+        days_from_civil(self.end as i64 / 10000, self.end / 100 % 100, self.end % 100)
+    }
+}
+
+impl std::fmt::Display for DateSpan {
+    /// Shortest form that covers the span: `2019`, `2019-03..2019-06`, `2019-03-04`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let point = |date: u32| -> String {
+            if self.start % 10000 == 101 && self.end % 10000 == 1231 {
+                format!("{}", date / 10000)
+            } else if self.start % 100 == 1 && self.end % 100 == 31 {
+                format!("{}-{:02}", date / 10000, date / 100 % 100)
+            } else {
+                format!("{}-{:02}-{:02}", date / 10000, date / 100 % 100, date % 100)
+            }
+        };
+
+        let (start, end) = (point(self.start), point(self.end));
+        if start == end {
+            write!(f, "{}", start)
+        } else {
+            write!(f, "{}..{}", start, end)
+        }
+    }
+}
+
+/// How much more readily newer files are drawn than older ones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Recency {
+    HalfLife(u32),  // Days over which a file's weight halves
+    Linear,  // Falling evenly from the newest file to the oldest
+}
+
+impl Recency {
+    /// Parse a half-life (`90d`, `6w`, `3m`, `1y`) or `linear`
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.eq_ignore_ascii_case("linear") {
+            return Ok(Self::Linear);
+        }
+
+        let invalid = || format!("invalid recency '{}', expected a half-life such as 90d, 6w, 3m or 1y, or linear", value);
+        let split = value.len().checked_sub(1).filter(|&at| value.is_char_boundary(at)).ok_or_else(invalid)?;
+        let (count, unit) = value.split_at(split);
+        let days_per = match unit.to_ascii_lowercase().as_str() {
+            "d" => 1,
+            "w" => 7,
+            "m" => 30,
+            "y" => 365,
+            _ => return Err(invalid()),
+        };
+        match count.parse::<u32>() {
+            Ok(count) if count > 0 => Ok(Self::HalfLife(count.saturating_mul(days_per))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl std::fmt::Display for Recency {
+    /// How the weighting is described under the canvas: `newest first, half-life 1y`
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::HalfLife(days) if days.is_multiple_of(365) => write!(f, "newest first, half-life {}y", days / 365),
+            Self::HalfLife(days) if days.is_multiple_of(30) => write!(f, "newest first, half-life {}m", days / 30),
+            Self::HalfLife(days) if days.is_multiple_of(7) => write!(f, "newest first, half-life {}w", days / 7),
+            Self::HalfLife(days) => write!(f, "newest first, half-life {}d", days),
+            Self::Linear => write!(f, "newest first, linear"),
+        }
+    }
+}
+
+/// A whole date string: `YYYY`, `YYYY-MM` or `YYYY-MM-DD` (with `-`, `_` or `.` separators)
+fn parse_date(value: &str) -> Option<DateSpan> {
+    let parts: Vec<&str> = value.split(['-', '_', '.']).collect();
+    let number = |part: &str, digits: usize| -> Option<u32> {
+        (part.len() == digits && part.bytes().all(|b| b.is_ascii_digit())).then(|| part.parse().ok())?
+    };
+
+    match parts[..] {
+        [year] => DateSpan::from_parts(number(year, 4)?, None, None),
+        [year, month] => DateSpan::from_parts(number(year, 4)?, Some(number(month, 2)?), None),
+        [year, month, day] => DateSpan::from_parts(number(year, 4)?, Some(number(month, 2)?), Some(number(day, 2)?)),
+        _ => None,
+    }
+}
+
+/// Find a date in a file path, e.g. `journal/2019-03-04.md`, `chat_20190304.txt` or `notes 2019.txt`
+///
+/// The most specific date wins, and the last path component is searched before its parents.
+pub fn date_from_path(path: &str) -> Option<DateSpan> {
+    path.rsplit(['/', '\\']).find_map(date_from_name)
+}
+
+fn date_from_name(name: &str) -> Option<DateSpan> {
+    date_chunks(name)
+        .into_iter()
+        .filter_map(|chunk| {
+            if chunk.len() == 8 && chunk.bytes().all(|b| b.is_ascii_digit()) {
+                return parse_date(&format!("{}-{}-{}", &chunk[..4], &chunk[4..6], &chunk[6..]));
+            }
+
+            // Try the whole chunk, then drop trailing parts (e.g. "2019-03-04_2" as a day)
+            let parts: Vec<&str> = chunk.split(['-', '_', '.']).collect();
+            (1..=parts.len().min(3))
+                .rev()
+                .find_map(|count| parse_date(&parts[..count].join("-")))
+        })
+        .filter(|span| (1900..=2099).contains(&(span.start / 10000)))
+        // The most specific date wins
+        .min_by_key(|span| span.end - span.start)
+}
+
+/// Runs of digits joined by date separators, e.g. "2019-03-04" in "journal 2019-03-04.md"
+fn date_chunks(name: &str) -> Vec<&str> {
+    let bytes = name.as_bytes();
+    let mut chunks = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if !bytes[index].is_ascii_digit() {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < bytes.len()
+            && (bytes[index].is_ascii_digit()
+                || (matches!(bytes[index], b'-' | b'_' | b'.') && bytes.get(index + 1).is_some_and(u8::is_ascii_digit)))
+        {
+            index += 1;
+        }
+        chunks.push(&name[start..index]);
+    }
+
+    chunks
+}
+
+/// Today's date as `YYYY-MM-DD` (UTC)
+pub fn today() -> String {
+    let days = unix_seconds() / 86_400;
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// The date and time as `YYYY-MM-DD HH:MM UTC`
+pub fn now() -> String {
+    let seconds = unix_seconds();
+    format!("{} {:02}:{:02} UTC", today(), seconds / 3600 % 24, seconds / 60 % 60)
+}
+
+fn unix_seconds() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Calendar date for a count of days since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 for a calendar date, the inverse of `civil_from_days`
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Successive windows for sweeping across a corpus: each year it covers, or each month
+/// when everything falls within a single year
+pub fn sweep_periods<'a>(paths: impl Iterator<Item = &'a str>) -> Vec<(String, DateSpan)> {
+    let dates: Vec<DateSpan> = paths.filter_map(date_from_path).collect();
+    let years: BTreeSet<u32> = dates.iter().map(|span| span.start / 10000).collect();
+
+    if years.len() > 1 {
+        return years
+            .into_iter()
+            .filter_map(|year| Some((year.to_string(), DateSpan::from_parts(year, None, None)?)))
+            .collect();
+    }
+
+    let months: BTreeSet<(u32, u32)> = dates
+        .iter()
+        .filter(|span| span.start / 100 == span.end / 100) // Skip year-only dates
+        .map(|span| (span.start / 10000, span.start / 100 % 100))
+        .collect();
+
+    if months.len() > 1 {
+        months
+            .into_iter()
+            .filter_map(|(year, month)| {
+                Some((format!("{}-{:02}", year, month), DateSpan::from_parts(year, Some(month), None)?))
+            })
+            .collect()
+    } else {
+        years
+            .into_iter()
+            .filter_map(|year| Some((year.to_string(), DateSpan::from_parts(year, None, None)?)))
+            .collect()
+    }
+}
+
+/// Sampling weight that keeps to files dated within `span`, so other words are only used as filler
+pub fn weight_within(span: DateSpan) -> impl Fn(&str, &str) -> f64 {
+    move |_, source| {
+        if date_from_path(source).is_some_and(|date| span.overlaps(&date)) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Sampling weight for a word and its source file
+pub type Weight = Box<dyn Fn(&str, &str) -> f64>;
+
+/// Sampling weight that favors newer files along `recency`'s curve, measured back from the newest
+/// file; files without a date weigh as much as the oldest. None when no file has a date in its name
+pub fn weight_by_recency<'a>(recency: Recency, paths: impl Iterator<Item = &'a str>) -> Option<Weight> {
+    let days: Vec<i64> = paths.filter_map(date_from_path).map(|span| span.last_day()).collect();
+    let (oldest, newest) = (*days.iter().min()?, *days.iter().max()?);
+    Some(Box::new(move |_: &str, source: &str| {
+        let day = date_from_path(source).map_or(oldest, |span| span.last_day());
+        match recency {
+            Recency::HalfLife(half_life) => 0.5f64.powf((newest - day) as f64 / half_life as f64),
+            Recency::Linear => (day - oldest + 1) as f64 / (newest - oldest + 1) as f64,
+        }
+    }))
+}
+
+/// Steps through a corpus's periods, moving on to the next one at every reroll
+pub struct TimeSweep {
+    periods: Vec<(String, DateSpan)>,
+    current: usize,
+}
+
+impl TimeSweep {
+    /// None when no file in the corpus has a date in its name
+    pub fn new<'a>(paths: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let periods = sweep_periods(paths);
+        (!periods.is_empty()).then_some(Self { periods, current: 0 })
+    }
+
+    /// Label and span of the period being scattered
+    pub fn current(&self) -> (&str, DateSpan) {
+        let (label, span) = &self.periods[self.current];
+        (label, *span)
+    }
+
+    /// Move to the next period, wrapping back to the earliest after the last
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % self.periods.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_from_path() {
+        let day = DateSpan::parse("2019-03-04").unwrap();
+        assert_eq!(date_from_path("journal/2019-03-04.md"), Some(day));
+        assert_eq!(date_from_path("chat_20190304.txt"), Some(day));
+        assert_eq!(date_from_path("2019/notes.txt"), Some(DateSpan::parse("2019").unwrap()));
+        assert_eq!(date_from_path("logs/2019_03 standup.txt"), Some(DateSpan::parse("2019-03").unwrap()));
+        assert_eq!(date_from_path("chapter-12345.txt"), None);
+        assert_eq!(date_from_path("essay.txt"), None);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        let range = DateSpan::parse("2019-03..2019-06").unwrap();
+        assert!(range.overlaps(&DateSpan::parse("2019-04-15").unwrap()));
+        assert!(range.overlaps(&DateSpan::parse("2019").unwrap()));
+        assert!(!range.overlaps(&DateSpan::parse("2019-07-01").unwrap()));
+        assert!(DateSpan::parse("2020..2019").is_err());
+        assert_eq!(range.to_string(), "2019-03..2019-06");
+        assert_eq!(DateSpan::parse("2019").unwrap().to_string(), "2019");
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_741), (2026, 10, 15));
+        assert_eq!(days_from_civil(2026, 10, 15), 20_741);
+        assert_eq!(days_from_civil(2000, 2, 29), 11_016);
+    }
+
+    #[test]
+    fn test_recency_favors_newer_files() {
+        assert_eq!(Recency::parse("1y"), Ok(Recency::HalfLife(365)));
+        assert_eq!(Recency::parse("Linear"), Ok(Recency::Linear));
+        assert!(Recency::parse("0d").is_err() && Recency::parse("y").is_err() && Recency::parse("soon").is_err());
+        assert_eq!(Recency::HalfLife(60).to_string(), "newest first, half-life 2m");
+
+        let paths = ["2024-01-01.txt", "2025-01-01.txt", "notes.txt"];
+        let weight = weight_by_recency(Recency::HalfLife(366), paths.into_iter()).unwrap();
+        assert_eq!(weight("", "2025-01-01.txt"), 1.0);
+        assert_eq!(weight("", "2024-01-01.txt"), 0.5);
+        assert_eq!(weight("", "notes.txt"), 0.5);
+
+        let weight = weight_by_recency(Recency::Linear, paths.into_iter()).unwrap();
+        assert!(weight("", "2024-06-01.txt") > weight("", "2024-01-01.txt"));
+        assert!(weight_by_recency(Recency::Linear, ["notes.txt"].into_iter()).is_none());
+    }
+
+    #[test]
+    fn test_sweep_periods() {
+        let periods = sweep_periods(["2019-01-02.txt", "2018-05-01.txt", "undated.txt"].into_iter());
+        let labels: Vec<&str> = periods.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, ["2018", "2019"]);
+
+        let periods = sweep_periods(["2019-01-02.txt", "2019-05-01.txt"].into_iter());
+        let labels: Vec<&str> = periods.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, ["2019-01", "2019-05"]);
+    }
+}
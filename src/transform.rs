@@ -0,0 +1,130 @@
+//! External word transforms: words piped through a user's command, one per line on stdin, with the
+//! command's output lines taking their places. Translators, LLM wrappers and one-line scripts all fit.
+//! Line suggestions run their command the same way.
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// How long a command may run before it's killed and counted as failed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Run `command` through the shell with `words` on its stdin; returns one replacement per word, the
+/// original word wherever the command printed an empty line
+pub fn run(command: &str, words: &[&str]) -> Result<Vec<String>, String> {
+    let input: String = words.iter().map(|word| format!("{}\n", word)).collect();
+    let output = pipe(command, input)?;
+    let lines: Vec<&str> = output.lines().collect();
+    if lines.len() != words.len() {
+        return Err(format!("transform gave back {} lines for {} words", lines.len(), words.len()));
+    }
+
+    Ok(words
+        .iter()
+        .zip(lines)
+        .map(|(word, line)| match line.trim() {
+            "" => word.to_string(),
+            replacement => replacement.to_string(),
+        })
+        .collect())
+}
+
+/// Run `command` through the shell with `input` on its stdin, returning what it printed; a command
+/// still running after `COMMAND_TIMEOUT` is killed
+pub fn pipe(command: &str, input: String) -> Result<String, String> {
+    pipe_within(command, input, COMMAND_TIMEOUT)
+}
+
+fn pipe_within(command: &str, input: String, timeout: Duration) -> Result<String, String> {
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run '{}': {}", command, e))?;
+
+    // Feed the input from another thread so a command that writes as it reads can't fill its pipe and stall
+    let mut stdin = child.stdin.take().ok_or("could not write to the command")?;
+    let feeder = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    // Output is drained as it comes for the same reason, leaving this thread free to watch the clock
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| format!("'{}' failed: {}", command, e))? {
+            Some(status) => break status,
+            None if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("'{}' took longer than {} seconds", command, timeout.as_secs()));
+            }
+            None => std::thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    let _ = feeder.join();
+    if !status.success() {
+        let error = String::from_utf8_lossy(&stderr.join().unwrap_or_default()).into_owned();
+        return Err(format!("'{}' failed: {}", command, error.lines().next().unwrap_or("no output")));
+    }
+    let output = stdout.join().unwrap_or_default();
+    String::from_utf8(output).map_err(|_| format!("'{}' printed something that isn't UTF-8", command))
+}
+
+/// Read a child's output pipe to the end on its own thread
+fn drain(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut output);
+        }
+        output
+    })
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Run `command` over `words` on a background thread, as line suggestions are, so a slow or stuck command
+/// never freezes the canvas; the replacements arrive on the returned channel
+pub fn request(command: String, words: Vec<String>) -> Receiver<Result<Vec<String>, String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let _ = sender.send(run(&command, &words));
+    });
+    receiver
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_replaced_line_for_line() {
+        assert_eq!(run("tr a-z A-Z", &["tide", "lantern"]), Ok(vec!["TIDE".to_string(), "LANTERN".to_string()]));
+        assert_eq!(run("sed 's/^tide$//'", &["tide", "moon"]), Ok(vec!["tide".to_string(), "moon".to_string()]));
+        assert!(run("head -n 1", &["tide", "moon"]).is_err());
+        assert!(run("exit 3", &["tide"]).is_err());
+    }
+
+    #[test]
+    fn test_slow_command_killed() {
+        let started = Instant::now();
+        let result = pipe_within("sleep 5", String::new(), Duration::from_millis(200));
+        assert!(result.is_err_and(|e| e.contains("took longer")));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
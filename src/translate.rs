@@ -0,0 +1,86 @@
+//! Offline translation for bilingual cut-ups: a word list in another language, read from a
+//! tab-separated dictionary file, shown next to each word or in its place.
+
+use crate::config;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How a translated word appears on the canvas
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TranslationDisplay {
+    #[default]
+    Alongside,  // "harbor/port"
+    Replace,  // "port"
+}
+
+impl TranslationDisplay {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "alongside" => Ok(Self::Alongside),
+            "replace" => Ok(Self::Replace),
+            _ => Err(format!("unknown translation display '{}' (expected alongside or replace)", value)),
+        }
+    }
+
+    /// The word as shown with its translation
+    pub fn show(self, word: &str, translation: &str) -> String {
+        match self {
+            Self::Alongside => format!("{}/{}", word, translation),
+            Self::Replace => translation.to_string(),
+        }
+    }
+}
+
+/// A dictionary and how to show what it translates
+#[derive(Clone, Debug, Default)]
+pub struct Translation {
+    pub dictionary: HashMap<String, String>,
+    pub display: TranslationDisplay,
+}
+
+/// The dictionary at `name`: a file path, or `dictionaries/NAME.tsv` in the config directory for a bare name
+pub fn load_dictionary(name: &str) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let given = Path::new(name);
+    let path = if given.is_file() || given.extension().is_some() || given.components().count() > 1 {
+        given.to_path_buf()
+    } else {
+        config::get_config_dir()?.join("dictionaries").join(format!("{}.tsv", name))
+    };
+    let content = fs::read_to_string(&path)
+        .map_err(|_| format!("no dictionary at {} (one `word<TAB>translation` line per word)", path.display()))?;
+
+    let dictionary = parse(&content);
+    if dictionary.is_empty() {
+        return Err(format!("{} has no `word<TAB>translation` lines", path.display()).into());
+    }
+    Ok(dictionary)
+}
+
+/// `word<TAB>translation` lines, keyed by the lowercase word; where several translations are listed
+/// (`harbor<TAB>port, havre`) the first is used, and `#` lines are comments
+fn parse(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once('\t'))
+        .filter_map(|(word, translations)| {
+            let translation = translations.split([',', ';']).next()?.trim();
+            let word = word.trim().to_lowercase();
+            (!word.is_empty() && !translation.is_empty()).then(|| (word, translation.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dictionary_lines() {
+        let dictionary = parse("# en-fr\nHarbor\tport, havre\nsea\tmer\nbroken line\n\t\n");
+        assert_eq!(dictionary.len(), 2);
+        assert_eq!(dictionary["harbor"], "port");
+        assert_eq!(TranslationDisplay::Alongside.show("sea", &dictionary["sea"]), "sea/mer");
+    }
+}
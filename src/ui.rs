@@ -1,26 +1,326 @@
-use crate::scatters::ScatteredWord;
-use crate::styling::AppStyling;
+use crate::decks;
+use crate::export;
+use crate::logging;
+use crate::parser::Location;
+use crate::reading_path;
+use crate::scatters::{self, Area, Scatter, ScatteredWord, Voice, ZoneLayout};
+use crate::spelling::{self, Spelling};
+use crate::stats::{format_elapsed, SessionStats, WritingTimer};
+use crate::stop_words::StopWords;
+use crate::suggest::Suggestions;
+use crate::styling::{self, AppStyling};
+use crate::templates::Template;
+use crate::timeline::{self, TimeSweep};
+use crate::word_bank::PruneDecision;
+use crate::wordplay;
+use rand::rngs::StdRng;
 use ratatui::{
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Paragraph, Wrap, Block, BorderType, Borders},
+    widgets::{Clear, Paragraph, Wrap, Block, BorderType, Borders, Widget},
     Frame,
 };
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
-//use std::fs::OpenOptions;
-//use std::io::Write; // For debug logging
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+/// Keys listed in the help overlay
+const HELP_KEYS: &[(&str, &str)] = &[
+    ("←/→", "move between words, highlighting each one"),
+    ("h j k l", "move to the nearest word left, down, up or right"),
+    ("↑/↓ 1-9", "word density, used from the next reroll"),
+    ("[ ]", "less or more chaos: orderly grid to rare words in clumps"),
+    ("r", "reroll the scatter"),
+    ("u ctrl+r", "undo and redo rerolls, highlights and density changes"),
+    ("space", "switch the current word's highlight style"),
+    ("c", "reading path through the highlighted words"),
+    ("enter", "type the selected word on the typewriter line"),
+    ("/", "line break on the typewriter line, twice for a stanza break"),
+    ("backspace del", "take back the last typed word or break, clear the line"),
+    ("y", "pair the selected word with the next one you press y on"),
+    ("L", "keep the selected word through rerolls"),
+    ("\"", "quote a source sentence with the selected word as an epigraph"),
+    ("N", "concordance: every recorded context of the selected word"),
+    ("f", "add the selected word to the current deck"),
+    ("S", "save a snapshot to the export folder"),
+    ("s", "save the canvas as a text file, named at a prompt"),
+    ("d", "compose the highlighted words into a poem"),
+    ("K", "copy the canvas to the clipboard, over SSH too"),
+    ("D", "suggest lines from the highlighted words (needs suggest in settings.conf)"),
+    (":", "command prompt: title, epigraph, density, chaos, sampling, seed, replay, deck, inject, import, template, poem, couplets, anthology, timer, transform, spelling, lipogram, syllables, log, seeds, open, add, prune"),
+    ("e", "edit the stop-word list"),
+    ("m", "weight each source file, 0 (off) to 5"),
+    ("t", "try the themes and switch without restarting"),
+    ("T", "show the last scatter faintly beneath this one; enter brings a word back"),
+    ("z Z", "canvas zones, and which zone ↑/↓ adjusts"),
+    ("x", "draw areas to keep empty"),
+    ("a A", "scatter from a random slice of the bank, or all of it again"),
+    ("v", "full window canvas"),
+    ("shift+arrows", "pan across a canvas bigger than the terminal"),
+    ("w g i", "word cloud, coordinate ruler, session stats"),
+    ("o", "random bold, italic and dim words"),
+    ("H", "spell words as they sound, reversed or mirrored"),
+    ("P", "deal a constraint card to write to (esc puts it away)"),
+    ("b B", "border style, hide the canvas border"),
+    ("?", "show these keys again"),
+    ("q", "quit"),
+];
+
+/// Widest the suggestion panel gets, borders included
+const SUGGESTION_PANEL_WIDTH: u16 = 44;
+/// Width of the compose panel down the right side
+const COMPOSE_PANEL_WIDTH: u16 = 36;
+
+/// Widest the constraint card gets, borders included
+const PROMPT_CARD_WIDTH: u16 = 30;
+
+/// How long the cue for a newly selected word lasts
+const SELECTION_CUE_DURATION: Duration = Duration::from_millis(250);
+
+/// How a newly selected word is pointed out, so the eye can follow the jump on a dense canvas
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SelectionCue {
+    Off,
+    #[default]
+    Flash,   // The word briefly in reverse video
+    Marker,  // A pointer briefly left of the word
+}
+
+impl SelectionCue {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "flash" => Ok(Self::Flash),
+            "marker" => Ok(Self::Marker),
+            _ => Err(format!("unknown selection cue '{}', expected flash, marker or off", value)),
+        }
+    }
+}
+
+/// How the words of a new scatter arrive after a reroll, for performing with the canvas projected
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Transition {
+    #[default]
+    Instant,
+    Fade,        // Words appear dim a few at a time and settle to full strength
+    Typewriter,  // Words typed out a few letters a tick, in reading order
+    Rainfall,    // Words fall from the top of the canvas to their places
+}
+
+impl Transition {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "instant" => Ok(Self::Instant),
+            "fade" => Ok(Self::Fade),
+            "typewriter" => Ok(Self::Typewriter),
+            "rainfall" => Ok(Self::Rainfall),
+            _ => Err(format!("unknown transition '{}', expected instant, fade, typewriter or rainfall", value)),
+        }
+    }
+}
+
+/// Ticks a word stays dim after fading in, and the most a word waits before it starts
+const FADE_TICKS: usize = 4;
+/// Ticks the typewriter takes to type out a whole scatter
+const TYPEWRITER_TICKS: usize = 40;
+/// Rows a falling word drops a tick, and the most ticks a word waits before it starts to fall
+const RAIN_SPEED: usize = 2;
+const RAIN_STAGGER: usize = 10;
+
+/// A reroll transition in progress
+pub struct Arrival {
+    style: Transition,
+    tick: usize,
+    starts: Vec<usize>,  // When each word starts to arrive: a tick, or with the typewriter a letter count
+    letters_per_tick: usize,
+    end: usize,  // Tick when every word is in place
+}
+
+/// Colors that tell word pairs apart, in the order pairs are made
+const PAIR_COLORS: [Color; 6] = [Color::Yellow, Color::Cyan, Color::Magenta, Color::Green, Color::Red, Color::Blue];
+
+/// A pair's number as superscript digits, the marker shown after both of its words
+fn pair_marker(number: usize) -> String {
+    const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    number.to_string().chars().filter_map(|digit| digit.to_digit(10)).map(|digit| SUPERSCRIPTS[digit as usize]).collect()
+}
+
+/// Marks a line break among the typed words; two in a row end a stanza
+pub const LINE_BREAK: &str = "\n";
+
+/// How long words carried over from the previous scatter stay emphasized after a reroll
+const RECURRING_HIGHLIGHT: Duration = Duration::from_secs(2);
+
+/// Rerolls in a row a word has to survive before the screensaver shows it faded
+const AGE_FADE_ROUNDS: u32 = 3;
+
+/// Highest weight a source file can be given in the source weights panel
+pub const MAX_SOURCE_WEIGHT: u8 = 5;
+
+/// Densities for the number keys 1-9, with 4 as the starting density
+pub const DENSITY_PRESETS: [f32; 9] = [0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.5, 6.0];
+
+/// Seed and canvas size of a scatter, as `:replay` takes them
+pub type SeedRecord = (u64, (u16, u16));
+
+/// Changes `u` can take back
+const UNDO_LIMIT: usize = 100;
+
+/// What undo and redo bring back: the words on the canvas, which are highlighted, and the density
+#[derive(Clone, PartialEq)]
+pub struct Checkpoint {
+    words: Vec<ScatteredWord>,
+    highlighted: Vec<usize>,
+    selected: Option<usize>,
+    density: f32,
+    scatter: SeedRecord,
+}
+
+/// How a new scatter's words come onto the canvas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Entrance {
+    Appear,  // All at once
+    Arrive,  // With the reroll transition chosen in settings
+    Reveal,  // One by one, as the screensaver shows them
+}
+
+/// A scatter being generated on a worker thread; it comes back with the RNG it was drawn from, which
+/// the watermark is chosen with next
+pub struct PendingScatter {
+    pub seed: u64,
+    pub size: (u16, u16),
+    pub entrance: Entrance,
+    pub receiver: Receiver<(Scatter, StdRng)>,
+}
+
+/// Words sent through the transform command, waiting for what it makes of them
+pub struct PendingTransform {
+    pub sent: Vec<(usize, String)>,  // Index and text of each word sent, to tell whether it's still on the canvas
+    pub receiver: Receiver<Result<Vec<String>, String>>,
+}
 
 pub struct App {
     pub scattered_words: Vec<ScatteredWord>,
     pub word_count: usize,
     pub styling: AppStyling,
+    pub color: bool,  // Colors are in use; without them pairs are told apart by their markers alone
+    pub theme: String,  // Name of the theme in use
+    pub themes: Vec<String>,  // Themes listed in the theme picker
+    pub theme_panel: Option<(usize, usize)>,  // Selected theme and the one it was opened on, while the theme picker is open
     pub selected_word_index: Option<usize>,
     pub highlighted_words: Vec<usize>,  // Track all highlighted words
-    pub density: f32,  // Density multiplier for word generation (0.1 to 6.0)
+    pub density: f32,  // Density multiplier for word generation (0.1 to 6.0), of the focused zone when zoned
+    pub zone_layout: ZoneLayout,
+    pub zone_densities: Vec<f32>,  // Density of each zone; the focused one is kept in `density` while focused
+    pub focused_zone: usize,
     pub use_dimmed_current: bool,  // If true, current selection uses visited color instead of bright color
     pub fullscreen_mode: bool,
     pub directory: PathBuf,  // Current directory being used
+    pub source_roots: Vec<PathBuf>,  // Files and directories the corpus was loaded from, to read sources again
     pub actual_bar_width: u16,  // Actual rendered width of density bar (updated during render)
+    pub density_bar: Rect,  // Where the density bar is on screen, for clicks (updated during render)
+    pub dragging_density: bool,  // The density bar was pressed and the button is still down
+    pub mouse: bool,  // Mouse captured all session, not only while drawing exclusion zones
+    pub screensaver_active: bool,  // Idle mode: automatic rerolls with highlighting paused
+    pub reveal_count: Option<usize>,  // Words revealed so far during an animated transition
+    pub transition: Transition,  // How words arrive after a reroll
+    pub arrival: Option<Arrival>,  // The reroll transition in progress
+    pub stats: SessionStats,
+    pub show_stats: bool,
+    pub timer: Option<WritingTimer>,  // Writing timer shown in the sidebar
+    pub lipogram: Option<String>,  // Letters no drawn word contains, shown in the sidebar
+    pub syllable_budget: Option<usize>,  // Syllables each scatter adds up to instead of following density
+    pub pinned_words: Vec<ScatteredWord>,  // An imported poem's words, in every scatter where they were saved
+    pub anthology: Option<PathBuf>,  // Anthology file poems are added to, once one is chosen
+    pub prompts: Vec<String>,  // Constraint cards the P key deals from
+    pub prompt_card: Option<(usize, String)>,  // Card showing on the canvas, by its place in the list
+    pub show_ruler: bool,  // Coordinate ruler along the canvas edges
+    pub word_locations: HashMap<String, Location>,  // Where each bank word first appears in its source
+    pub word_contexts: HashMap<String, Vec<(String, String)>>,  // Snippets around each bank word's first occurrences, with their files
+    pub source_titles: HashMap<String, String>,  // Readable names for source files, such as EPUB titles
+    pub cloud_mode: bool,  // Emphasize frequent words and dim rare ones
+    pub font_styles: bool,  // Give each word a bold, italic or dim look of its own, chosen by the seed
+    pub spelling: Spelling,  // How words are spelled on the canvas and in snapshots
+    pub show_path: bool,  // Connect highlighted words in visit order
+    pub notice: Option<String>,  // One-off message under the canvas, cleared by the next key
+    pub palette: Option<String>,  // Command being typed at the `:` prompt
+    pub save_prompt: Option<String>,  // File name being typed after s, to save the canvas as text
+    pub title: Option<String>,  // Title block shown in the canvas corner and in exports
+    pub stop_words: StopWords,  // Effective stop-word list
+    pub stop_word_editor: Option<String>,  // Word being typed while the stop-word list is open
+    pub locked_words: Vec<(String, String, Option<Voice>)>,  // Words kept through rerolls, at new positions
+    pub source_weights: Vec<(String, u8)>,  // Sampling weight of each source file, 1 unless changed
+    pub source_panel: Option<usize>,  // Selected file while the source weights panel is open
+    pub prune_clusters: Vec<Vec<(String, usize)>>,  // Near-duplicate spellings in the bank with their counts, most frequent first
+    pub prune_panel: Option<(usize, usize)>,  // Selected cluster and spelling in it while the pruning panel is open
+    pub log_panel: Option<usize>,  // Lines scrolled back from the newest while the log panel is open
+    pub concordance: Option<usize>,  // Lines scrolled down while the selected word's concordance panel is open
+    pub suggestions: Option<Suggestions>,  // Suggested lines while their panel is open
+    pub suggestion_choice: usize,  // Suggested line Enter would accept
+    pub deck: String,  // Deck the f key adds the selected word to
+    pub deck_words: Vec<(String, String, Option<Voice>)>,  // Words of the injected deck, in every scatter
+    pub template: Option<Template>,  // Layout filled with fresh words on each reroll instead of a new scatter
+    pub typed_words: Vec<String>,  // Words typed onto the typewriter line, in order, with LINE_BREAK between lines
+    pub draft: Vec<String>,  // The poem in the compose panel, with LINE_BREAK between lines; kept across rerolls
+    pub drafted: Vec<usize>,  // Highlighted words already in the draft, by index (cleared by a reroll)
+    pub compose: Option<usize>,  // Draft entry the cursor is on while the compose panel is open
+    pub typed_shown: usize,  // Characters of the typewriter line typed out so far
+    pub cursor_visible: bool,  // Blink phase of the typewriter cursor
+    pub selection_cue: SelectionCue,
+    pub watermark: Option<String>,  // Corpus sentence laid faintly behind the current scatter
+    pub epigraph: Option<String>,  // Source sentence pinned along the bottom of the canvas, kept across rerolls
+    pub watermark_sentences: Vec<String>,  // Sentences to choose each scatter's watermark from; none when it's off
+    pub pairs: Vec<(usize, usize)>,  // Paired words of this scatter, as indices, in the order they were paired
+    pub pair_start: Option<usize>,  // First word of a pair still waiting for its partner
+    pub selection_cue_until: Option<Instant>,  // When the cue for the last selection change ends
+    pub recurring_words: HashSet<String>,  // Words that were also in the previous scatter
+    pub ghost_words: Vec<ScatteredWord>,  // The previous scatter, which the ghost overlay shows beneath this one
+    pub ghost: Option<usize>,  // Ghost word the cursor is on while the ghost overlay is showing
+    pub recurring_until: Option<Instant>,  // When to stop emphasizing them
+    pub word_ages: HashMap<String, u32>,  // Rerolls in a row each word on the canvas has survived, 0 for new words
+    pub show_canvas_border: bool,  // Frame around the canvas; hidden for a frameless look
+    pub show_help: bool,  // Key overlay, shown on a first run and with `?`
+    pub seed: u64,  // Seed the current scatter was generated from
+    pub scatter_size: (u16, u16),  // Canvas size it was generated for
+    pub seed_history: Vec<SeedRecord>,  // Seed and canvas size of every scatter this session, oldest first
+    pub favorite_seeds: Vec<SeedRecord>,  // Seeds marked in the seed panel, saved to the config directory
+    pub seed_panel: Option<usize>,  // Selected row while the seed panel is open
+    pub undo_history: VecDeque<Checkpoint>,  // Earlier states, newest last, at most UNDO_LIMIT
+    pub redo_history: Vec<Checkpoint>,  // States undone since the last change, newest last
+    pub settled: Option<Checkpoint>,  // The state as of the last input, to tell whether anything has changed it since
+    pub generating: Option<PendingScatter>,  // Reroll still being generated; the canvas keeps its words until it's ready
+    pub transforming: Option<PendingTransform>,  // Words out at the transform command
+    pub voice_names: Option<[String; 2]>,  // Corpus names for voices A and B in dialogue mode
+    pub time_window: Option<String>,  // Date range words are drawn from, shown under the canvas
+    pub time_sweep: Option<TimeSweep>,  // Periods stepped through on each reroll
+    pub slice_percent: u8,  // Share of the bank drawn into a new active slice
+    pub active_word_count: Option<usize>,  // Size of the active slice, when one is in use
+    pub requested_words: usize,  // Words the last generation tried to place (more than placed when the canvas is full)
+    pub exclusions: Vec<Area>,  // Canvas regions kept empty across rerolls
+    pub exclusion_editor: Option<ExclusionEditor>,  // Set while exclusion zones are being drawn
+    pub canvas_inner: Rect,  // Canvas area inside its border (updated during render)
+    pub canvas_override: (Option<u16>, Option<u16>),  // Width and height set with --width/--height, whatever the terminal's size
+    pub pan: (u16, u16),  // Canvas cell shown in the top left corner when the canvas is bigger than the terminal
+    word_frequencies: HashMap<String, usize>,
+    frequency_tiers: FrequencyTiers,
+}
+
+/// Cursor and first corner while drawing an exclusion zone, in canvas cells
+pub struct ExclusionEditor {
+    pub cursor: (u16, u16),
+    pub anchor: Option<(u16, u16)>,  // First corner, once marked
+}
+
+/// Corpus frequency cut-offs for word cloud emphasis
+#[derive(Default)]
+struct FrequencyTiers {
+    strong: usize,  // 95th percentile and up: bold + reversed
+    bold: usize,    // 75th percentile and up: bold
+    dim: usize,     // Median and below: dimmed
 }
 
 impl App {
@@ -34,30 +334,729 @@ impl App {
             scattered_words,
             word_count,
             styling,
+            color: true,
+            theme: String::new(),
+            themes: Vec::new(),
+            theme_panel: None,
             selected_word_index: Some(0),
             highlighted_words: vec![0],  // Start with first word highlighted
             density: 1.0,  // Start at default density
+            zone_layout: ZoneLayout::Whole,
+            zone_densities: vec![1.0],
+            focused_zone: 0,
             use_dimmed_current: false,  // Start with bright current selection
             fullscreen_mode: false,
+            source_roots: vec![directory.clone()],
             directory,
             actual_bar_width: 16,  // Default value, will be updated during first render
+            density_bar: Rect::default(),
+            dragging_density: false,
+            mouse: false,
+            screensaver_active: false,
+            reveal_count: None,
+            transition: Transition::Instant,
+            arrival: None,
+            stats: SessionStats::new(),
+            show_stats: false,
+            timer: None,
+            lipogram: None,
+            syllable_budget: None,
+            pinned_words: Vec::new(),
+            anthology: None,
+            prompts: Vec::new(),
+            prompt_card: None,
+            show_ruler: false,
+            word_locations: HashMap::new(),
+            word_contexts: HashMap::new(),
+            source_titles: HashMap::new(),
+            cloud_mode: false,
+            font_styles: false,
+            spelling: Spelling::default(),
+            show_path: false,
+            notice: None,
+            palette: None,
+            title: None,
+            stop_words: StopWords::default(),
+            stop_word_editor: None,
+            locked_words: Vec::new(),
+            source_weights: Vec::new(),
+            source_panel: None,
+            prune_clusters: Vec::new(),
+            prune_panel: None,
+            log_panel: None,
+            concordance: None,
+            save_prompt: None,
+            suggestions: None,
+            suggestion_choice: 0,
+            deck: decks::DEFAULT_DECK.to_string(),
+            deck_words: Vec::new(),
+            template: None,
+            typed_words: Vec::new(),
+            draft: Vec::new(),
+            drafted: Vec::new(),
+            compose: None,
+            typed_shown: 0,
+            cursor_visible: true,
+            selection_cue: SelectionCue::default(),
+            watermark: None,
+            epigraph: None,
+            watermark_sentences: Vec::new(),
+            pairs: Vec::new(),
+            pair_start: None,
+            selection_cue_until: None,
+            recurring_words: HashSet::new(),
+            word_ages: HashMap::new(),
+            ghost_words: Vec::new(),
+            ghost: None,
+            recurring_until: None,
+            show_canvas_border: true,
+            show_help: false,
+            seed: 0,
+            scatter_size: (0, 0),
+            seed_history: Vec::new(),
+            favorite_seeds: Vec::new(),
+            seed_panel: None,
+            undo_history: VecDeque::new(),
+            redo_history: Vec::new(),
+            settled: None,
+            generating: None,
+            transforming: None,
+            voice_names: None,
+            time_window: None,
+            time_sweep: None,
+            slice_percent: 10,
+            active_word_count: None,
+            requested_words: 0,
+            exclusions: Vec::new(),
+            exclusion_editor: None,
+            canvas_inner: Rect::default(),
+            canvas_override: (None, None),
+            pan: (0, 0),
+            word_frequencies: HashMap::new(),
+            frequency_tiers: FrequencyTiers::default(),
         }
     }
 
-    pub fn update_words(&mut self, scattered_words: Vec<ScatteredWord>) {
-        self.scattered_words = scattered_words;
-        self.selected_word_index = Some(0);
-        self.highlighted_words = vec![0];  // Reset to single highlighted word on reroll
+    pub fn update_words(&mut self, scatter: Scatter) {
+        // Words the chance process brought back, not counting the ones locked in on purpose
+        let previous: HashSet<&str> = self.scattered_words.iter().map(|word| word.word.as_str()).collect();
+        self.recurring_words = scatter
+            .words
+            .iter()
+            .filter(|word| previous.contains(word.word.as_str()) && !self.is_kept(&word.word))
+            .map(|word| word.word.clone())
+            .collect();
+        self.recurring_until = (!self.recurring_words.is_empty()).then(|| Instant::now() + RECURRING_HIGHLIGHT);
+
+        self.word_ages = scatter
+            .words
+            .iter()
+            .map(|word| (word.word.clone(), self.word_ages.get(&word.word).map_or(0, |age| age + 1)))
+            .collect();
+
+        self.requested_words = scatter.requested;
+        self.ghost_words = std::mem::replace(&mut self.scattered_words, scatter.words);
+        // An open ghost overlay now shows the scatter just replaced, and closes if that one was empty
+        self.ghost = self.ghost.filter(|_| !self.ghost_words.is_empty()).map(|cursor| cursor.min(self.ghost_words.len() - 1));
+        self.pairs.clear();
+        self.pair_start = None;
+        self.drafted.clear();
+        if self.scattered_words.is_empty() {
+            self.selected_word_index = None;
+            self.highlighted_words.clear();
+        } else {
+            self.selected_word_index = Some(0);
+            self.highlighted_words = vec![0];  // Reset to single highlighted word on reroll
+        }
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            words: self.scattered_words.clone(),
+            highlighted: self.highlighted_words.clone(),
+            selected: self.selected_word_index,
+            density: self.density,
+            scatter: (self.seed, self.scatter_size),
+        }
+    }
+
+    /// Keep the state as of the last input for undo if anything has changed it since; called on every input,
+    /// so whatever a key, click or timer did is taken back in one step
+    pub fn note_changes(&mut self) {
+        let now = self.checkpoint();
+        if let Some(settled) = self.settled.take().filter(|settled| *settled != now) {
+            if self.undo_history.len() == UNDO_LIMIT {
+                self.undo_history.pop_front();
+            }
+            self.undo_history.push_back(settled);
+            self.redo_history.clear();
+        }
+        self.settled = Some(now);
+    }
+
+    /// Go back to the state before the last change; returns whether there was one
+    pub fn undo(&mut self) -> bool {
+        let Some(previous) = self.undo_history.pop_back() else { return false };
+        self.redo_history.push(self.checkpoint());
+        self.restore(previous);
+        true
+    }
+
+    /// Bring back the state the last undo left; returns whether there was one
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.redo_history.pop() else { return false };
+        self.undo_history.push_back(self.checkpoint());
+        self.restore(next);
+        true
+    }
+
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        self.settled = Some(checkpoint.clone());
+        self.scattered_words = checkpoint.words;
+        self.highlighted_words = checkpoint.highlighted;
+        self.selected_word_index = checkpoint.selected;
+        self.density = checkpoint.density;
+        (self.seed, self.scatter_size) = checkpoint.scatter;
+        // Pairs and the draft point at words by index, which may not be the same words any more
+        self.pairs.clear();
+        self.pair_start = None;
+        self.drafted.clear();
+        self.arrival = None;
+        // A reroll still being generated would otherwise land on top of the restored scatter
+        self.generating = None;
+    }
+
+    /// Name the scatter with today's date, or remove the title block
+    pub fn set_title(&mut self, name: Option<String>) {
+        self.title = name.map(|name| format!("[ {} · {} ]", name, timeline::today()));
+    }
+
+    /// Keep the selected word through rerolls (its position still changes), or release it
+    pub fn toggle_lock(&mut self) {
+        let Some(selected) = self.selected_word_index.and_then(|index| self.scattered_words.get(index)) else {
+            return;
+        };
+
+        match self.locked_words.iter().position(|(word, _, _)| *word == selected.word) {
+            Some(position) => {
+                self.locked_words.remove(position);
+            }
+            None => self
+                .locked_words
+                .push((selected.word.clone(), selected.source_file.clone(), selected.voice)),
+        }
+    }
+
+    pub fn is_locked(&self, word: &str) -> bool {
+        self.locked_words.iter().any(|(locked, _, _)| locked == word)
+    }
+
+    /// The highlighted words' text, in the order they were highlighted
+    pub fn highlighted_word_texts(&self) -> Vec<String> {
+        self.highlighted_words
+            .iter()
+            .filter_map(|&index| self.scattered_words.get(index))
+            .map(|word| word.word.clone())
+            .collect()
+    }
+
+    /// Locked words and the injected deck's words, which the generator puts in every scatter
+    pub fn kept_words(&self) -> Vec<(String, String, Option<Voice>)> {
+        let mut kept = self.locked_words.clone();
+        for entry in &self.deck_words {
+            if !self.is_locked(&entry.0) {
+                kept.push(entry.clone());
+            }
+        }
+        kept
+    }
+
+    /// Whether a canvas word is one of the imported poem's, where the poem had it
+    pub fn is_pinned(&self, scattered: &ScatteredWord) -> bool {
+        self.pinned_words.iter().any(|pinned| pinned.word == scattered.word && (pinned.x, pinned.y) == (scattered.x, scattered.y))
+    }
+
+    fn is_kept(&self, word: &str) -> bool {
+        self.is_locked(word) || self.deck_words.iter().any(|(kept, _, _)| kept == word)
+    }
+
+    /// A source file's title if it has one, otherwise its path
+    pub fn source_name<'a>(&'a self, source_file: &'a str) -> &'a str {
+        self.source_titles.get(source_file).map_or(source_file, String::as_str)
+    }
+
+    /// Every distinct source file at the default weight
+    pub fn set_sources<'a>(&mut self, sources: impl Iterator<Item = &'a str>) {
+        let sources: std::collections::BTreeSet<&str> = sources.collect();
+        let previous: HashMap<String, u8> = std::mem::take(&mut self.source_weights).into_iter().collect();
+        self.source_weights = sources
+            .into_iter()
+            .map(|source| (source.to_string(), previous.get(source).copied().unwrap_or(1)))
+            .collect();
+    }
+
+    /// Rows of the seed panel: favorites first, then this session's other seeds, newest first, each once
+    pub fn seed_rows(&self) -> Vec<(SeedRecord, bool)> {
+        let mut rows: Vec<(SeedRecord, bool)> = self.favorite_seeds.iter().map(|&seed| (seed, true)).collect();
+        for &seed in self.seed_history.iter().rev() {
+            if !rows.iter().any(|&(row, _)| row == seed) {
+                rows.push((seed, false));
+            }
+        }
+        rows
+    }
+
+    /// Move the seed panel's selection up or down
+    pub fn move_seed_selection(&mut self, delta: isize) {
+        let last = self.seed_rows().len().saturating_sub(1);
+        if let Some(selected) = &mut self.seed_panel {
+            *selected = selected.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Seed and canvas size of the seed panel's selected row
+    pub fn selected_seed(&self) -> Option<SeedRecord> {
+        self.seed_panel.and_then(|selected| self.seed_rows().get(selected).map(|&(seed, _)| seed))
+    }
+
+    /// Mark the selected seed as a favorite, or unmark it, keeping it selected as it moves in the list;
+    /// returns whether it's now a favorite
+    pub fn toggle_favorite_seed(&mut self) -> Option<bool> {
+        let seed = self.selected_seed()?;
+        let favorite = match self.favorite_seeds.iter().position(|&favorite| favorite == seed) {
+            Some(index) => {
+                self.favorite_seeds.remove(index);
+                false
+            }
+            None => {
+                self.favorite_seeds.push(seed);
+                true
+            }
+        };
+        self.seed_panel = self.seed_rows().iter().position(|&(row, _)| row == seed).or(Some(0));
+        Some(favorite)
+    }
+
+    /// Open the theme picker on the theme in use, listing every built-in and user theme
+    pub fn open_theme_picker(&mut self) {
+        self.themes = styling::theme_names();
+        let current = self.themes.iter().position(|name| name.eq_ignore_ascii_case(&self.theme)).unwrap_or(0);
+        self.theme_panel = Some((current, current));
+    }
+
+    /// Move the theme picker's selection up or down, switching to the selected theme as it goes
+    pub fn move_theme_selection(&mut self, delta: isize) {
+        if let Some((selected, _)) = &mut self.theme_panel {
+            *selected = selected.saturating_add_signed(delta).min(self.themes.len().saturating_sub(1));
+            let name = self.themes[*selected].clone();
+            self.set_theme(&name);
+        }
+    }
+
+    /// Close the theme picker, going back to the theme it was opened on unless `keep`
+    pub fn close_theme_picker(&mut self, keep: bool) {
+        if let Some((_, opened)) = self.theme_panel.take().filter(|_| !keep) {
+            let name = self.themes[opened].clone();
+            self.set_theme(&name);
+        }
+    }
+
+    /// Restyle everything with a theme, without colors if they're off
+    pub fn set_theme(&mut self, name: &str) {
+        match AppStyling::from_theme(name) {
+            Ok(styling) => {
+                self.styling = if self.color { styling } else { styling.without_color() };
+                self.theme = name.to_string();
+            }
+            Err(e) => self.notice = Some(e),
+        }
+    }
+
+    /// Move the source weights panel's selection up or down
+    pub fn move_source_selection(&mut self, delta: isize) {
+        if let Some(selected) = &mut self.source_panel {
+            *selected = selected.saturating_add_signed(delta).min(self.source_weights.len().saturating_sub(1));
+        }
+    }
+
+    /// Set the selected file's weight, clamped to 0..=MAX_SOURCE_WEIGHT
+    pub fn set_source_weight(&mut self, weight: u8) {
+        if let Some(entry) = self.source_panel.and_then(|selected| self.source_weights.get_mut(selected)) {
+            entry.1 = weight.min(MAX_SOURCE_WEIGHT);
+        }
+    }
+
+    pub fn selected_source_weight(&self) -> Option<u8> {
+        self.source_panel.and_then(|selected| self.source_weights.get(selected)).map(|(_, weight)| *weight)
+    }
+
+    /// Open the pruning panel on the bank's near-duplicates, or list them again after pruning with the
+    /// selection kept on the same row where it can be
+    pub fn show_near_duplicates(&mut self, clusters: Vec<Vec<(String, usize)>>) {
+        let cluster = self.prune_panel.map_or(0, |(cluster, _)| cluster).min(clusters.len().saturating_sub(1));
+        self.prune_clusters = clusters;
+        self.prune_panel = Some((cluster, 0));
+    }
+
+    /// Move the pruning panel's selection by `clusters` rows and `spellings` along the row
+    pub fn move_prune_selection(&mut self, clusters: isize, spellings: isize) {
+        if let Some((cluster, spelling)) = &mut self.prune_panel {
+            if clusters != 0 {
+                *cluster = cluster.saturating_add_signed(clusters).min(self.prune_clusters.len().saturating_sub(1));
+                *spelling = 0;
+            }
+            let forms = self.prune_clusters.get(*cluster).map_or(0, Vec::len);
+            *spelling = spelling.saturating_add_signed(spellings).min(forms.saturating_sub(1));
+        }
+    }
+
+    /// Merge the selected cluster into the selected spelling, or with `all` every cluster into its most
+    /// frequent spelling
+    pub fn prune_merge(&self, all: bool) -> Vec<PruneDecision> {
+        let Some((cluster, spelling)) = self.prune_panel else { return Vec::new() };
+        let targets: Vec<(&Vec<(String, usize)>, usize)> = if all {
+            self.prune_clusters.iter().map(|forms| (forms, 0)).collect()
+        } else {
+            self.prune_clusters.get(cluster).map(|forms| (forms, spelling)).into_iter().collect()
+        };
+        targets
+            .into_iter()
+            .flat_map(|(forms, into)| {
+                forms.iter().enumerate().filter(move |&(index, _)| index != into).map(move |(_, (from, _))| {
+                    PruneDecision::Merge { from: from.clone(), into: forms[into].0.clone() }
+                })
+            })
+            .collect()
+    }
+
+    /// Delete the selected spelling, or with `others` every other spelling in its cluster
+    pub fn prune_delete(&self, others: bool) -> Vec<PruneDecision> {
+        let Some((forms, spelling)) = self.prune_panel.and_then(|(cluster, spelling)| Some((self.prune_clusters.get(cluster)?, spelling)))
+        else {
+            return Vec::new();
+        };
+        forms
+            .iter()
+            .enumerate()
+            .filter(|&(index, _)| (index == spelling) != others)
+            .map(|(_, (word, _))| PruneDecision::Delete(word.clone()))
+            .collect()
+    }
+
+    /// Weights the generator should apply, leaving out files at the default
+    pub fn changed_source_weights(&self) -> HashMap<String, f64> {
+        self.source_weights
+            .iter()
+            .filter(|(_, weight)| *weight != 1)
+            .map(|(source, weight)| (source.clone(), *weight as f64))
+            .collect()
+    }
+
+    /// Save the selected word to the current deck, reporting the result under the canvas
+    pub fn add_to_deck(&mut self) {
+        let Some(selected) = self.selected_word_index.and_then(|index| self.scattered_words.get(index)) else {
+            return;
+        };
+        self.notice = Some(match decks::add(&self.deck, &selected.word, &selected.source_file) {
+            Ok(true) => format!("added '{}' to deck {}", selected.word, self.deck),
+            Ok(false) => format!("'{}' is already in deck {}", selected.word, self.deck),
+            Err(e) => format!("could not save deck: {}", e),
+        });
+    }
+
+    /// Start a pair at the selected word, or finish the waiting one there; on a paired word, undo its pair
+    pub fn pair_selected_word(&mut self) {
+        let Some(selected) = self.selected_word_index else { return };
+
+        if let Some(position) = self.pairs.iter().position(|&(a, b)| a == selected || b == selected) {
+            self.pairs.remove(position);
+            self.notice = Some("pair undone".to_string());
+            return;
+        }
+        match self.pair_start.take() {
+            None => {
+                self.pair_start = Some(selected);
+                self.notice = Some(format!("pairing '{}': press y on its partner", self.scattered_words[selected].word));
+            }
+            Some(start) if start == selected => self.notice = Some("pairing cancelled".to_string()),
+            Some(start) => self.pairs.push((start, selected)),
+        }
+    }
+
+    /// Which pair a word belongs to, counting from 0, or the next pair's number while it waits for a partner
+    fn pair_of(&self, index: usize) -> Option<usize> {
+        self.pairs
+            .iter()
+            .position(|&(a, b)| a == index || b == index)
+            .or_else(|| (self.pair_start == Some(index)).then_some(self.pairs.len()))
+    }
+
+    /// The highlighted words (or with `all`, every word) to send through the transform command, with
+    /// their indices
+    pub fn transform_targets(&self, all: bool) -> Vec<(usize, String)> {
+        let indices: Vec<usize> = if all {
+            (0..self.scattered_words.len()).collect()
+        } else {
+            self.highlighted_words.clone()
+        };
+        indices.into_iter().map(|index| (index, self.scattered_words[index].word.clone())).collect()
+    }
+
+    /// Take in the transform command's answer once it arrives, putting each replacement in place of the
+    /// word it came from; a word that has left the canvas meanwhile, or whose replacement would run off
+    /// the canvas or into a neighbour, stays as it is. True when there was an answer to show
+    pub fn finish_transform(&mut self) -> bool {
+        let replacements = match self.transforming.as_ref().map(|pending| pending.receiver.try_recv()) {
+            Some(Ok(replacements)) => replacements,
+            Some(Err(TryRecvError::Disconnected)) => Err("the transform command stopped".to_string()),
+            Some(Err(TryRecvError::Empty)) | None => return false,
+        };
+        let Some(pending) = self.transforming.take() else { return false };
+        self.notice = Some(match replacements {
+            Ok(replacements) => {
+                let (replaced, kept) = self.replace_words(&pending.sent, replacements);
+                match kept {
+                    0 => format!("transformed {} words", replaced),
+                    kept => format!("transformed {} words; {} no longer fit and were kept", replaced, kept),
+                }
+            }
+            Err(e) => e,
+        });
+        true
+    }
+
+    /// Put `replacements` in place of the `sent` words that are still where they were and still fit;
+    /// returns how many were replaced and how many kept
+    fn replace_words(&mut self, sent: &[(usize, String)], replacements: Vec<String>) -> (usize, usize) {
+        let width = self.scatter_size.0;
+        let mut replaced = 0;
+        for ((index, word), replacement) in sent.iter().zip(replacements) {
+            let Some(current) = self.scattered_words.get(*index).filter(|current| current.word == *word) else {
+                continue;
+            };
+            let (x, y, len) = (current.x, current.y, replacement.chars().count());
+            let others = self.scattered_words.iter().enumerate().filter(|&(other, _)| other != *index).map(|(_, other)| other);
+            let clear = !self.exclusions.iter().any(|area| area.touches_word(x, y, len.min(u16::MAX as usize) as u16));
+            if clear && scatters::fits_beside(x, y, len, width, others) {
+                self.scattered_words[*index].word = replacement;
+                replaced += 1;
+            }
+        }
+        (replaced, sent.len() - replaced)
+    }
+
+    /// The scatter's words as the canvas shows them, respelled if a spelling other than the written one is
+    /// on. A respelling longer than its word keeps its length only where it still clears its neighbours,
+    /// each taken at the wider of its two spellings, and is cut to the word's own width otherwise
+    pub fn shown_words(&self) -> Cow<'_, [ScatteredWord]> {
+        if self.spelling == Spelling::Written {
+            return Cow::Borrowed(&self.scattered_words);
+        }
+        let respelled: Vec<ScatteredWord> = self
+            .scattered_words
+            .iter()
+            .map(|scattered| ScatteredWord { word: self.spelling.apply(&scattered.word).into_owned(), ..scattered.clone() })
+            .collect();
+        let widest: Vec<&ScatteredWord> = self
+            .scattered_words
+            .iter()
+            .zip(&respelled)
+            .map(|(written, shown)| if shown.word.chars().count() > written.word.chars().count() { shown } else { written })
+            .collect();
+        let width = self.scatter_size.0;
+        Cow::Owned(
+            respelled
+                .iter()
+                .enumerate()
+                .map(|(index, shown)| {
+                    let letters = self.scattered_words[index].word.chars().count();
+                    let len = shown.word.chars().count();
+                    let others = widest.iter().enumerate().filter(|&(other, _)| other != index).map(|(_, &other)| other);
+                    if len > letters && !scatters::fits_beside(shown.x, shown.y, len, width, others) {
+                        ScatteredWord { word: shown.word.chars().take(letters).collect(), ..shown.clone() }
+                    } else {
+                        shown.clone()
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Syllables across the scatter's words, to compare with the syllable budget
+    pub fn syllable_count(&self) -> usize {
+        self.scattered_words.iter().map(|scattered| spelling::syllables(&scattered.word)).sum()
+    }
+
+    /// Each pair as a two-line couplet, with a blank line between couplets
+    pub fn couplets(&self) -> String {
+        self.pairs
+            .iter()
+            .map(|&(a, b)| format!("{}\n{}\n", self.scattered_words[a].word, self.scattered_words[b].word))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Open the stop-word list, ready to add or remove the selected word
+    pub fn open_stop_word_editor(&mut self) {
+        let selected = self.selected_word_index.and_then(|index| self.scattered_words.get(index));
+        self.stop_word_editor = Some(selected.map(|word| word.word.clone()).unwrap_or_default());
+    }
+
+    /// Add or remove the typed word and save the list
+    pub fn toggle_stop_word(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let word = self.stop_word_editor.replace(String::new()).unwrap_or_default();
+        self.stop_words.toggle(&word);
+        self.stop_words.save()
+    }
+
+    /// Start drawing exclusion zones, with the cursor in the middle of the canvas
+    pub fn start_exclusion_edit(&mut self) {
+        self.exclusion_editor = Some(ExclusionEditor {
+            cursor: (self.page_size().0 / 2, self.page_size().1 / 2),
+            anchor: None,
+        });
+    }
+
+    pub fn move_exclusion_cursor(&mut self, dx: i32, dy: i32) {
+        let (width, height) = self.page_size();
+        let (max_x, max_y) = (width.saturating_sub(1), height.saturating_sub(1));
+        if let Some(editor) = &mut self.exclusion_editor {
+            editor.cursor.0 = (editor.cursor.0 as i32 + dx).clamp(0, max_x as i32) as u16;
+            editor.cursor.1 = (editor.cursor.1 as i32 + dy).clamp(0, max_y as i32) as u16;
+        }
+    }
+
+    /// Mark the first corner at the cursor, or finish the rectangle if one is already marked
+    pub fn mark_exclusion_corner(&mut self) {
+        let Some(editor) = &mut self.exclusion_editor else {
+            return;
+        };
+
+        match editor.anchor.take() {
+            None => editor.anchor = Some(editor.cursor),
+            Some(anchor) => {
+                let area = area_between(anchor, editor.cursor);
+                self.add_exclusion(area);
+            }
+        }
+    }
+
+    /// Drop the half-drawn rectangle if there is one, otherwise the most recent zone
+    pub fn undo_exclusion(&mut self) {
+        match self.exclusion_editor.as_mut().and_then(|editor| editor.anchor.take()) {
+            Some(_) => {}
+            None => {
+                self.exclusions.pop();
+            }
+        }
+    }
+
+    /// The rectangle from the marked corner to the cursor, while one is being drawn
+    pub fn pending_exclusion(&self) -> Option<Area> {
+        let editor = self.exclusion_editor.as_ref()?;
+        editor.anchor.map(|anchor| area_between(anchor, editor.cursor))
+    }
+
+    /// Keep a region empty from now on, clearing any words already in it
+    pub fn add_exclusion(&mut self, area: Area) {
+        self.exclusions.push(area);
+
+        let remaining: Vec<ScatteredWord> = std::mem::take(&mut self.scattered_words)
+            .into_iter()
+            .filter(|word| !area.touches_word(word.x, word.y, word.word.len() as u16))
+            .collect();
+        let requested = remaining.len();
+        self.update_words(Scatter { words: remaining, requested });
+    }
+
+    /// Canvas cell under a terminal position, if it's inside the canvas
+    pub fn canvas_cell(&self, column: u16, row: u16) -> Option<(u16, u16)> {
+        let inner = self.canvas_inner;
+        let inside = column >= inner.x && column < inner.x + inner.width && row >= inner.y && row < inner.y + inner.height;
+        inside.then(|| (column - inner.x + self.pan.0, row - inner.y + self.pan.1))
+    }
+
+    /// Width and height of the whole canvas: the `--width`/`--height` override, otherwise the area on screen
+    pub fn page_size(&self) -> (u16, u16) {
+        (self.canvas_override.0.unwrap_or(self.canvas_inner.width), self.canvas_override.1.unwrap_or(self.canvas_inner.height))
+    }
+
+    /// Move the view across a canvas bigger than the terminal; it stops at the canvas's edges
+    pub fn pan_by(&mut self, dx: i32, dy: i32) {
+        self.pan = (
+            (self.pan.0 as i32 + dx).max(0) as u16,
+            (self.pan.1 as i32 + dy).max(0) as u16,
+        );
+        self.clamp_pan();
+    }
+
+    /// Keep the view within the canvas, for after a pan or a change to the terminal's size
+    fn clamp_pan(&mut self) {
+        let (width, height) = self.page_size();
+        self.pan.0 = self.pan.0.min(width.saturating_sub(self.canvas_inner.width));
+        self.pan.1 = self.pan.1.min(height.saturating_sub(self.canvas_inner.height));
+    }
+
+    /// Show the previous scatter beneath this one, with the cursor on its first word
+    pub fn open_ghost(&mut self) {
+        if self.ghost_words.is_empty() {
+            self.notice = Some("no earlier scatter yet (r to reroll)".to_string());
+        } else {
+            self.ghost = Some(0);
+        }
+    }
+
+    /// List every recorded context of the selected word
+    pub fn open_concordance(&mut self) {
+        if self.selected_word_index.is_none() {
+            self.notice = Some("select a word to see it in context".to_string());
+        } else {
+            self.concordance = Some(0);
+        }
+    }
+
+    /// Move the ghost cursor to the previous scatter's next or previous word
+    pub fn move_ghost_cursor(&mut self, delta: isize) {
+        if let Some(cursor) = &mut self.ghost {
+            let len = self.ghost_words.len() as isize;
+            if len == 0 {
+                return;
+            }
+            *cursor = (*cursor as isize + delta).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Whether a word can go at its position without touching a word on the canvas
+    fn has_room(&self, word: &ScatteredWord) -> bool {
+        let end = word.x + word.word.chars().count() as u16;
+        !self.scattered_words.iter().any(|other| {
+            other.y == word.y && other.x <= end && word.x <= other.x + other.word.chars().count() as u16
+        })
+    }
+
+    /// Bring the ghost word under the cursor back onto the canvas where it was, selected and highlighted,
+    /// and say how it went
+    pub fn resurrect_ghost(&mut self) -> String {
+        let Some(ghost) = self.ghost.and_then(|cursor| self.ghost_words.get(cursor)).cloned() else {
+            return String::new();
+        };
+        if self.scattered_words.iter().any(|word| word.word == ghost.word) {
+            return format!("{} is already on the canvas", ghost.word);
+        }
+        if !self.has_room(&ghost) {
+            return format!("{}'s old spot is taken", ghost.word);
+        }
+        let index = self.scattered_words.len();
+        let notice = format!("{} is back", ghost.word);
+        self.scattered_words.push(ghost);
+        self.selected_word_index = Some(index);
+        self.highlighted_words.push(index);
+        notice
     }
 
     pub fn select_next_word(&mut self) {
         if let Some(index) = self.selected_word_index {
-            let next_index = (index + 1) % self.scattered_words.len();
-            self.selected_word_index = Some(next_index);
-            // Add to highlighted words if not already there
-            if !self.highlighted_words.contains(&next_index) {
-                self.highlighted_words.push(next_index);
-            }
+            self.visit((index + 1) % self.scattered_words.len());
         }
     }
 
@@ -68,14 +1067,51 @@ impl App {
             } else {
                 index - 1
             };
-            self.selected_word_index = Some(prev_index);
-            // Add to highlighted words if not already there
-            if !self.highlighted_words.contains(&prev_index) {
-                self.highlighted_words.push(prev_index);
-            }
+            self.visit(prev_index);
         }
     }
 
+    /// Select the nearest word on screen in a direction, `(1, 0)` for right and `(0, 1)` for down
+    pub fn select_toward(&mut self, direction: (i32, i32)) {
+        let nearest = self
+            .selected_word_index
+            .and_then(|index| reading_path::nearest_in_direction(&self.scattered_words, index, direction));
+        if let Some(index) = nearest {
+            self.visit(index);
+        }
+    }
+
+    /// Select a word, highlighting it if it isn't already
+    fn visit(&mut self, index: usize) {
+        self.selected_word_index = Some(index);
+        let newly_highlighted = !self.highlighted_words.contains(&index);
+        if newly_highlighted {
+            self.highlighted_words.push(index);
+        }
+        self.stats.record_visit(newly_highlighted);
+        self.start_selection_cue();
+    }
+
+    fn start_selection_cue(&mut self) {
+        if self.selection_cue != SelectionCue::Off {
+            self.selection_cue_until = Some(Instant::now() + SELECTION_CUE_DURATION);
+        }
+    }
+
+    /// Whether the cue for the last selection change is still showing
+    fn selection_cue_showing(&self, cue: SelectionCue) -> bool {
+        self.selection_cue == cue && self.selection_cue_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// End the selection cue once its time is up; returns whether it just ended
+    pub fn expire_selection_cue(&mut self) -> bool {
+        let expired = self.selection_cue_until.is_some_and(|until| Instant::now() >= until);
+        if expired {
+            self.selection_cue_until = None;
+        }
+        expired
+    }
+
     pub fn increase_density(&mut self, bar_width: u16) {
         let density_per_pixel = (6.0 - 0.1) / bar_width.max(1) as f32;
         self.density = (self.density + density_per_pixel).min(6.0);
@@ -86,15 +1122,335 @@ impl App {
         self.density = (self.density - density_per_pixel).max(0.1);
     }
 
+    /// The density a click on the density bar at a terminal position sets, filling the bar up to and
+    /// including the clicked cell
+    pub fn density_at(&self, column: u16, row: u16) -> Option<f32> {
+        let bar = self.density_bar;
+        let inside = row == bar.y && column >= bar.x && column < bar.x + bar.width;
+        inside.then(|| 0.1 + (column - bar.x + 1) as f32 / bar.width as f32 * (6.0 - 0.1))
+    }
+
+    /// Jump straight to a density, kept within 0.1 to 6.0
+    pub fn set_density(&mut self, density: f32) {
+        self.density = density.clamp(0.1, 6.0);
+        self.notice = Some(format!("density {:.2} (r to reroll)", self.density));
+    }
+
+    /// Density for every zone of the current layout
+    pub fn densities(&self) -> Vec<f32> {
+        let mut densities = self.zone_densities.clone();
+        densities[self.focused_zone] = self.density;
+        densities
+    }
+
+    /// Switch to a zone layout, with each zone's density taken from `densities` (or the current density)
+    pub fn set_zone_layout(&mut self, layout: ZoneLayout, densities: Option<Vec<f32>>) {
+        self.zone_densities = densities.unwrap_or_else(|| vec![self.density; layout.zone_count()]);
+        self.zone_layout = layout;
+        self.focused_zone = 0;
+        self.density = self.zone_densities[0];
+    }
+
+    /// Move the density controls on to the next zone
+    pub fn focus_next_zone(&mut self) {
+        self.zone_densities[self.focused_zone] = self.density;
+        self.focused_zone = (self.focused_zone + 1) % self.zone_densities.len();
+        self.density = self.zone_densities[self.focused_zone];
+    }
+
     pub fn toggle_current_highlight(&mut self) {
         // Toggle between bright current selection and dimmed (visited color) current selection
         self.use_dimmed_current = !self.use_dimmed_current;
     }
+
+    /// Store corpus frequencies and derive the percentile cut-offs for cloud mode
+    pub fn set_word_frequencies(&mut self, frequencies: HashMap<String, usize>) {
+        let mut counts: Vec<usize> = frequencies.values().copied().collect();
+        counts.sort_unstable();
+
+        let percentile = |p: usize| counts.get(counts.len().saturating_sub(1) * p / 100).copied().unwrap_or(0);
+        self.frequency_tiers = FrequencyTiers {
+            strong: percentile(95),
+            bold: percentile(75),
+            dim: percentile(50),
+        };
+        self.word_frequencies = frequencies;
+    }
+
+    /// Style for an unhighlighted word in cloud mode, based on where its frequency falls
+    fn cloud_style(&self, word: &str, base: Style) -> Style {
+        let count = self.word_frequencies.get(word).copied().unwrap_or(0);
+        let tiers = &self.frequency_tiers;
+
+        // Tiers that collapse onto the median (e.g. a corpus of one-off words) carry no emphasis
+        if count >= tiers.strong && tiers.strong > tiers.dim {
+            base.add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else if count >= tiers.bold && tiers.bold > tiers.dim {
+            base.add_modifier(Modifier::BOLD)
+        } else if count <= tiers.dim && tiers.strong > tiers.dim {
+            base.add_modifier(Modifier::DIM)
+        } else {
+            base
+        }
+    }
+
+    pub fn start_screensaver(&mut self) {
+        self.screensaver_active = true;
+    }
+
+    /// Put the selected word on the typewriter line, to be typed out a character per tick
+    pub fn type_selected_word(&mut self) {
+        if let Some(word) = self.selected_word_index.and_then(|index| self.scattered_words.get(index)) {
+            self.typed_words.push(word.word.clone());
+            self.cursor_visible = true;
+        }
+    }
+
+    /// End the typed line; a second break in a row ends the stanza, and there is nothing to break before the first word
+    pub fn break_typed_line(&mut self) {
+        let trailing_breaks = self.typed_words.iter().rev().take_while(|token| *token == LINE_BREAK).count();
+        if !self.typed_words.is_empty() && trailing_breaks < 2 {
+            self.typed_words.push(LINE_BREAK.to_string());
+        }
+    }
+
+    /// Put the chosen suggested line on the typewriter line as a line of its own, and close the panel
+    pub fn accept_suggestion(&mut self) {
+        let Some(Suggestions::Lines(lines)) = self.suggestions.take() else {
+            return;
+        };
+        let Some(line) = lines.get(self.suggestion_choice) else {
+            return;
+        };
+
+        if self.typed_words.last().is_some_and(|token| token != LINE_BREAK) {
+            self.typed_words.push(LINE_BREAK.to_string());
+        }
+        self.typed_words.extend(line.split_whitespace().map(str::to_string));
+        self.cursor_visible = true;
+    }
+
+    /// Take the last word or break back off the typewriter line
+    pub fn delete_typed_word(&mut self) {
+        self.typed_words.pop();
+        self.typed_shown = self.typed_shown.min(self.typed_text().chars().count());
+    }
+
+    /// The typed words as lines of a poem, with a blank line between stanzas
+    pub fn typed_text(&self) -> String {
+        poem_text(&self.typed_words)
+    }
+
+    /// Open the compose panel, adding the words highlighted since it was last open to the end of the draft
+    /// in the order they were visited
+    pub fn open_compose(&mut self) {
+        for &index in &self.highlighted_words {
+            if let Some(word) = self.scattered_words.get(index).filter(|_| !self.drafted.contains(&index)) {
+                self.draft.push(word.word.clone());
+                self.drafted.push(index);
+            }
+        }
+        self.compose = Some(self.draft.len().saturating_sub(1));
+    }
+
+    /// Move the compose cursor up or down the draft
+    pub fn move_compose_cursor(&mut self, delta: isize) {
+        if let Some(cursor) = &mut self.compose {
+            *cursor = cursor.saturating_add_signed(delta).min(self.draft.len().saturating_sub(1));
+        }
+    }
+
+    /// Swap the entry under the compose cursor with its neighbour, the cursor moving with it
+    pub fn move_draft_entry(&mut self, delta: isize) {
+        let Some(cursor) = self.compose.filter(|&cursor| cursor < self.draft.len()) else { return };
+        let target = cursor.saturating_add_signed(delta).min(self.draft.len() - 1);
+        self.draft.swap(cursor, target);
+        self.compose = Some(target);
+    }
+
+    /// Take the entry under the compose cursor out of the draft
+    pub fn remove_draft_entry(&mut self) {
+        let Some(cursor) = self.compose.filter(|&cursor| cursor < self.draft.len()) else { return };
+        self.draft.remove(cursor);
+        self.compose = Some(cursor.min(self.draft.len().saturating_sub(1)));
+    }
+
+    /// Break the draft's line after the entry under the compose cursor; two breaks in a row end a stanza
+    pub fn break_draft_line(&mut self) {
+        let Some(cursor) = self.compose.filter(|&cursor| cursor < self.draft.len()) else { return };
+        self.draft.insert(cursor + 1, LINE_BREAK.to_string());
+        self.compose = Some(cursor + 1);
+    }
+
+    /// The draft as lines of a poem, with a blank line between stanzas
+    pub fn draft_text(&self) -> String {
+        poem_text(&self.draft)
+    }
+
+    /// Type the next character of the typewriter line; None once it's all typed
+    pub fn advance_typing(&mut self) -> Option<char> {
+        let next = self.typed_text().chars().nth(self.typed_shown)?;
+        self.typed_shown += 1;
+        Some(next)
+    }
+
+    pub fn stop_screensaver(&mut self) {
+        self.screensaver_active = false;
+        self.reveal_count = None;
+    }
+
+    /// Hide all words so the next ticks can reveal them a few at a time
+    pub fn begin_reveal(&mut self) {
+        self.reveal_count = Some(0);
+    }
+
+    /// Time left on the recurring-word emphasis, if it's showing
+    pub fn recurring_remaining(&self) -> Option<Duration> {
+        self.recurring_until.map(|until| until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Stop emphasizing recurring words once their time is up
+    pub fn expire_recurring(&mut self) {
+        if self.recurring_remaining().is_some_and(|remaining| remaining.is_zero()) {
+            self.recurring_until = None;
+            self.recurring_words.clear();
+        }
+    }
+
+    /// Start bringing the scatter's words in with the reroll transition, unless it's instant
+    pub fn begin_arrival(&mut self) {
+        let words = &self.scattered_words;
+        // A spread of starting ticks that looks random but is the same for the same scatter
+        let stagger = |index: usize, spread: usize| (index * 7 + words[index].x as usize) % (spread + 1);
+        let (starts, letters_per_tick, end) = match self.transition {
+            Transition::Instant => {
+                self.arrival = None;
+                return;
+            }
+            Transition::Fade => {
+                let starts: Vec<usize> = (0..words.len()).map(|index| stagger(index, FADE_TICKS)).collect();
+                (starts, 0, 2 * FADE_TICKS + 1)
+            }
+            Transition::Typewriter => {
+                let mut order: Vec<usize> = (0..words.len()).collect();
+                order.sort_by_key(|&index| (words[index].y, words[index].x));
+                let mut starts = vec![0; words.len()];
+                let mut letters = 0;
+                for index in order {
+                    starts[index] = letters;
+                    letters += words[index].word.chars().count();
+                }
+                let letters_per_tick = letters.div_ceil(TYPEWRITER_TICKS).max(1);
+                (starts, letters_per_tick, letters.div_ceil(letters_per_tick))
+            }
+            Transition::Rainfall => {
+                let starts: Vec<usize> = (0..words.len()).map(|index| stagger(index, RAIN_STAGGER)).collect();
+                let end = words
+                    .iter()
+                    .zip(&starts)
+                    .map(|(word, start)| start + (word.y as usize).div_ceil(RAIN_SPEED))
+                    .max()
+                    .unwrap_or(0);
+                (starts, 0, end)
+            }
+        };
+        self.arrival = (end > 0).then_some(Arrival { style: self.transition, tick: 0, starts, letters_per_tick, end });
+    }
+
+    /// Move the reroll transition on a tick, ending it once every word is in place
+    pub fn advance_arrival(&mut self) {
+        if let Some(arrival) = &mut self.arrival {
+            arrival.tick += 1;
+            if arrival.tick >= arrival.end {
+                self.arrival = None;
+            }
+        }
+    }
+
+    /// How a word looks this far into the reroll transition: the row it's on, how many of its letters
+    /// show and any extra modifier; None while it hasn't appeared yet
+    fn arriving_word(&self, index: usize, word: &ScatteredWord) -> Option<(u16, usize, Modifier)> {
+        let letters = word.word.chars().count();
+        let Some(arrival) = &self.arrival else {
+            return Some((word.y, letters, Modifier::empty()));
+        };
+        let start = arrival.starts.get(index).copied().unwrap_or(0);
+        match arrival.style {
+            Transition::Instant => Some((word.y, letters, Modifier::empty())),
+            Transition::Fade => {
+                let dim = if arrival.tick < start + FADE_TICKS { Modifier::DIM } else { Modifier::empty() };
+                (arrival.tick >= start).then_some((word.y, letters, dim))
+            }
+            Transition::Typewriter => {
+                let typed = (arrival.tick * arrival.letters_per_tick).checked_sub(start).filter(|&typed| typed > 0)?;
+                Some((word.y, typed.min(letters), Modifier::empty()))
+            }
+            Transition::Rainfall => {
+                let fallen = arrival.tick.checked_sub(start)? * RAIN_SPEED;
+                Some(((fallen as u16).min(word.y), letters, Modifier::empty()))
+            }
+        }
+    }
+
+    /// Reveal the next batch of words, ending the transition once all are visible
+    pub fn advance_reveal(&mut self) {
+        if let Some(count) = self.reveal_count {
+            let step = (self.scattered_words.len() / 20).max(1);
+            let next = count + step;
+            self.reveal_count = if next >= self.scattered_words.len() {
+                None
+            } else {
+                Some(next)
+            };
+        }
+    }
+}
+
+/// Scattered word count against the bank, or against the active slice when one is in use
+fn count_text(app: &App) -> String {
+    match app.active_word_count {
+        Some(active) => format!("{} / {} active", app.scattered_words.len(), active),
+        None => format!("{} / {} words", app.scattered_words.len(), app.word_count),
+    }
+}
+
+/// Smallest area covering two opposite corners
+fn area_between(a: (u16, u16), b: (u16, u16)) -> Area {
+    Area {
+        x: a.0.min(b.0),
+        y: a.1.min(b.1),
+        width: a.0.abs_diff(b.0) + 1,
+        height: a.1.abs_diff(b.1) + 1,
+    }
+}
+
+/// " Density " plus an arrow at the focused zone when the canvas is split into zones
+fn density_title(app: &App) -> String {
+    match app.zone_layout {
+        ZoneLayout::Whole => " Density ".to_string(),
+        layout => format!(" Density {} ", layout.zone_markers()[app.focused_zone]),
+    }
+}
+
+/// "full (143/180)" when the last generation couldn't fit every word it drew, so density is capped
+fn overflow_text(app: &App) -> Option<String> {
+    (app.requested_words > app.scattered_words.len())
+        .then(|| format!("full ({}/{})", app.scattered_words.len(), app.requested_words))
+}
+
+/// Width and height available for words in a terminal of the given size, or as set with `--width`/`--height`
+pub fn canvas_size(app: &App, width: u16, height: u16) -> (u16, u16) {
+    let border = if app.show_canvas_border { 2 } else { 0 };
+    let sidebar_width = if app.fullscreen_mode { 0 } else { calculate_sidebar_width_for_app(app) };
+    (
+        app.canvas_override.0.unwrap_or(width.saturating_sub(sidebar_width).saturating_sub(border)),
+        app.canvas_override.1.unwrap_or(height.saturating_sub(border)),
+    )
 }
 
 pub fn calculate_sidebar_width_for_app(app: &App) -> u16 {
     // Formatting Scatters box content for calculations
-    let count_text = format!("{} / {} words", app.scattered_words.len(), app.word_count);
+    let count_text = count_text(app);
     let highlighted_text = format!("{} / {} selected", app.highlighted_words.len(), app.scattered_words.len());
 
     // Compare both lines
@@ -107,6 +1463,8 @@ pub fn calculate_sidebar_width_for_app(app: &App) -> u16 {
         "spc - toggle",
         "r - reroll",
         "v - view",
+        "i - stats",
+        "g - ruler",
         "q - quit",
     ];
     let controls_width = (controls_lines.iter()
@@ -148,21 +1506,6 @@ pub fn calculate_sidebar_width_for_app(app: &App) -> u16 {
     let path_width = truncated_path_lines.iter().map(|s| s.len()).max().unwrap_or(0);
     */
 
-    // Glitchy debug output - useless for intended implemenation (clashes with TUI) but looks sick in execution
-    // dbg!(scatters_width, controls_width);
-    // dbg!(content_width);
-
-    // Log to file for debugging
-    /*
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("debug.log") {
-        writeln!(file, "scatters_width: {}, controls_width: {}, content_width: {}",
-                 scatters_width, controls_width, content_width).ok();
-    }
-    */
-
     // Take the maximum of all sections
     // let content_width = scatters_width.max(controls_width).max(info_width).max(path_width);
 
@@ -171,6 +1514,9 @@ pub fn calculate_sidebar_width_for_app(app: &App) -> u16 {
 
   
 
+/// Height of the Controls box: one line per control plus borders
+const CONTROLS_BOX_HEIGHT: u16 = 10;
+
 fn widget_block(border_type: BorderType) -> Block<'static> {
     Block::default()
         .border_type(border_type)
@@ -183,6 +1529,7 @@ fn wrap_path_smart(path_str: &str, max_width: usize) -> Vec<String> {
     let mut current_line = String::new();
 
     // Split by both / and \ to handle cross-platform paths
+    #[allow(clippy::manual_pattern_char_comparison)]
     let components: Vec<&str> = path_str.split(|c| c == '/' || c == '\\').collect();
 
     for (i, component) in components.iter().enumerate() {
@@ -287,36 +1634,484 @@ fn wrap_text_line(text: &str, max_width: usize) -> Vec<String> {
         current_pos += chunk_size;
     }
 
-    lines
+    lines
+}
+
+pub fn ui(f: &mut Frame, app: &mut App) {
+    let frame_area = f.area();
+
+    // Render unified background for theme when necessary
+    if app.styling.use_background_fill {
+        let background = widget_block(app.styling.border_type)
+            .style(app.styling.text_style)
+            .borders(ratatui::widgets::Borders::empty());
+        f.render_widget(background, frame_area);
+    }
+
+    if app.fullscreen_mode {
+        // Fullscreen mode: canvas takes entire screen, with no density bar to click
+        app.density_bar = Rect::default();
+        render_canvas(f, frame_area, app);
+    } else {
+        // Normal mode: sidebar + canvas layout
+        // Calculate sidebar width based on content
+        let sidebar_width = calculate_sidebar_width_for_app(app);
+
+        let main_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(sidebar_width), Constraint::Min(0)])
+            .split(frame_area);
+
+        render_sidebar(f, main_layout[0], app);
+        render_canvas(f, main_layout[1], app);
+    }
+
+    if app.stop_word_editor.is_some() {
+        render_stop_word_editor(f, frame_area, app);
+    }
+
+    if app.source_panel.is_some() {
+        render_source_weights(f, frame_area, app);
+    }
+
+    if let Some(selected) = app.seed_panel {
+        render_seeds(f, frame_area, app, selected);
+    }
+
+    if let Some((cluster, spelling)) = app.prune_panel {
+        render_pruning(f, frame_area, app, cluster, spelling);
+    }
+
+    if let Some((selected, _)) = app.theme_panel {
+        render_theme_picker(f, frame_area, app, selected);
+    }
+
+    if let Some(scrolled) = app.log_panel {
+        render_log(f, frame_area, app, scrolled);
+    }
+
+    if app.concordance.is_some() {
+        render_concordance(f, frame_area, app);
+    }
+
+    if let Some(cursor) = app.compose {
+        render_compose(f, frame_area, app, cursor);
+    }
+
+    if let Some(suggestions) = &app.suggestions {
+        render_suggestions(f, frame_area, app, suggestions);
+    }
+
+    if app.show_help {
+        render_help(f, frame_area, app);
+    }
+}
+
+/// A word's style in the screensaver by how many rerolls in a row it has survived: bold when new, plain
+/// for a while, then dim, so the vocabulary can be seen churning
+fn age_style(base: Style, age: u32) -> Style {
+    match age {
+        0 => base.add_modifier(Modifier::BOLD),
+        age if age < AGE_FADE_ROUNDS => base,
+        _ => base.add_modifier(Modifier::DIM),
+    }
+}
+
+/// Area for a panel drawn over the middle of the screen
+fn centered_panel(frame_area: Rect, max_width: u16, max_height: u16) -> Rect {
+    let width = frame_area.width.saturating_sub(4).min(max_width);
+    let height = frame_area.height.saturating_sub(2).min(max_height);
+    Rect {
+        x: frame_area.x + (frame_area.width - width) / 2,
+        y: frame_area.y + (frame_area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Overlay listing the keys, with a pointer to using your own files when showing the demo
+fn render_help(f: &mut Frame, frame_area: Rect, app: &App) {
+    let key_width = HELP_KEYS.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = HELP_KEYS
+        .iter()
+        .map(|(keys, action)| {
+            Line::from(vec![
+                Span::styled(format!(" {:<width$}  ", keys, width = key_width), app.styling.selected_text_style),
+                Span::styled(format!(" {}", action), app.styling.text_style),
+            ])
+        })
+        .collect();
+    lines.insert(0, Line::from(Span::styled("Words from your text, scattered at random. Keys:", app.styling.text_style)));
+    lines.insert(1, Line::from(""));
+
+    let area = centered_panel(frame_area, 72, lines.len() as u16 + 2);
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Text Scatters ", app.styling.text_style)))
+        .title_bottom(Line::from(Span::styled(" any key to start ", app.styling.text_style)).left_aligned());
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Words and line breaks as lines of a poem, with a blank line between stanzas
+fn poem_text(tokens: &[String]) -> String {
+    let mut text = String::new();
+    let mut line_start = true;
+    for token in tokens {
+        if token == LINE_BREAK {
+            text.push('\n');
+            line_start = true;
+        } else {
+            if !line_start {
+                text.push(' ');
+            }
+            text.push_str(token);
+            line_start = false;
+        }
+    }
+    text
+}
+
+/// Centered panel listing every stop word in effect, with the ones the user added highlighted
+fn render_stop_word_editor(f: &mut Frame, frame_area: Rect, app: &App) {
+    let input = app.stop_word_editor.as_deref().unwrap_or_default();
+    let words = app.stop_words.words();
+
+    let mut list = Vec::new();
+    for word in &words {
+        let style = if app.stop_words.is_added(word) {
+            app.styling.selected_text_style
+        } else {
+            app.styling.text_style
+        };
+        list.push(Span::styled(word.as_str(), style));
+        list.push(Span::styled("  ", app.styling.text_style));
+    }
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("word: ", app.styling.text_style),
+            Span::styled(format!("{}▏", input), app.styling.current_selected_style),
+        ]),
+        Line::from(""),
+        Line::from(list),
+    ];
+
+    let area = centered_panel(frame_area, 72, 24);
+
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(format!(" Stop Words ({}) ", words.len()), app.styling.text_style)))
+        .title_bottom(
+            Line::from(Span::styled(" enter: add/remove  esc: close ", app.styling.text_style)).left_aligned(),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), area);
+}
+
+/// Panel down the right side with the suggested lines, the one Enter would accept marked
+fn render_suggestions(f: &mut Frame, frame_area: Rect, app: &App, suggestions: &Suggestions) {
+    let width = frame_area.width.saturating_sub(2).min(SUGGESTION_PANEL_WIDTH);
+    let text_width = width.saturating_sub(6).max(1) as usize;
+
+    let mut lines = Vec::new();
+    match suggestions {
+        Suggestions::Waiting => lines.push(Line::from(Span::styled(" asking…", app.styling.text_style))),
+        Suggestions::Failed(error) => {
+            for part in export::wrap_words(error, text_width) {
+                lines.push(Line::from(Span::styled(format!(" {}", part), app.styling.text_style)));
+            }
+        }
+        Suggestions::Lines(suggested) => {
+            for (index, line) in suggested.iter().enumerate() {
+                let (marker, style) = if index == app.suggestion_choice {
+                    ("▸", app.styling.current_selected_style)
+                } else {
+                    (" ", app.styling.text_style)
+                };
+                for (part_index, part) in export::wrap_words(line, text_width).into_iter().enumerate() {
+                    let marker = if part_index == 0 { marker } else { " " };
+                    lines.push(Line::from(vec![
+                        Span::styled(format!(" {} ", marker), app.styling.text_style),
+                        Span::styled(part, style),
+                    ]));
+                }
+                lines.push(Line::from(""));
+            }
+            lines.pop();
+        }
+    }
+
+    let area = Rect {
+        x: (frame_area.x + frame_area.width).saturating_sub(width + 1),
+        y: frame_area.y + 1,
+        width,
+        height: frame_area.height.saturating_sub(2).min(lines.len() as u16 + 2),
+    };
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Suggestions ", app.styling.text_style)))
+        .title_bottom(Line::from(Span::styled(" enter: type it  esc: close ", app.styling.text_style)).left_aligned());
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Panel down the right side listing the draft a word or line break per row, scrolled to keep the
+/// cursor in view
+fn render_compose(f: &mut Frame, frame_area: Rect, app: &App, cursor: usize) {
+    let width = frame_area.width.saturating_sub(2).min(COMPOSE_PANEL_WIDTH);
+    let height = frame_area.height.saturating_sub(2);
+    let rows = height.saturating_sub(2) as usize;
+    let first = cursor.saturating_sub(rows.saturating_sub(1));
+
+    let mut lines: Vec<Line> = app
+        .draft
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(index, token)| {
+            let (marker, style) = if index == cursor {
+                ("▸", app.styling.current_selected_style)
+            } else {
+                (" ", app.styling.text_style)
+            };
+            let text = if token == LINE_BREAK { "↵".to_string() } else { token.clone() };
+            Line::from(vec![Span::styled(format!(" {} ", marker), app.styling.text_style), Span::styled(text, style)])
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(" highlight words with ←/→ first", app.styling.text_style)));
+    }
+
+    let area = Rect { x: (frame_area.x + frame_area.width).saturating_sub(width + 1), y: frame_area.y + 1, width, height };
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Compose ", app.styling.text_style)))
+        .title_bottom(
+            Line::from(Span::styled(" ⇧↑/↓ move  del  / break  w save ", app.styling.text_style)).left_aligned(),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Centered panel with a weight bar for each source file, scrolled to keep the selection in view
+fn render_source_weights(f: &mut Frame, frame_area: Rect, app: &App) {
+    let selected = app.source_panel.unwrap_or(0);
+    let area = centered_panel(frame_area, 72, app.source_weights.len() as u16 + 2);
+    let rows = area.height.saturating_sub(2) as usize;
+    let first = selected.saturating_sub(rows.saturating_sub(1));
+
+    let bar_width = MAX_SOURCE_WEIGHT as usize;
+    let name_width = (area.width as usize).saturating_sub(bar_width + 8);
+    let lines: Vec<Line> = app
+        .source_weights
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(index, (source, weight))| {
+            let style = if index == selected { app.styling.selected_text_style } else { app.styling.text_style };
+            let name: String = app.source_name(source).chars().take(name_width).collect();
+            let bar = format!("{}{}", "■".repeat(*weight as usize), "·".repeat(bar_width - *weight as usize));
+            Line::from(vec![
+                Span::styled(format!(" {:<width$} ", name, width = name_width), style),
+                Span::styled(format!("{} {}", bar, weight), style),
+            ])
+        })
+        .collect();
+
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Source Weights ", app.styling.text_style)))
+        .title_bottom(
+            Line::from(Span::styled(" ↑/↓ file  ←/→ 0-5 weight  esc: close ", app.styling.text_style)).left_aligned(),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Centered panel listing favorite seeds, marked with a star, then this session's seeds
+fn render_seeds(f: &mut Frame, frame_area: Rect, app: &App, selected: usize) {
+    let seeds = app.seed_rows();
+    let area = centered_panel(frame_area, 44, seeds.len() as u16 + 2);
+    let rows = area.height.saturating_sub(2) as usize;
+    let first = selected.saturating_sub(rows.saturating_sub(1));
+    let lines: Vec<Line> = seeds
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(index, &((seed, (width, height)), favorite))| {
+            let style = if index == selected { app.styling.current_selected_style } else { app.styling.text_style };
+            let current = seed == app.seed && (width, height) == app.scatter_size;
+            Line::from(vec![
+                Span::styled(if favorite { " ★ " } else { "   " }, app.styling.text_style),
+                Span::styled(format!("{:<20} {}x{}", seed, width, height), style),
+                Span::styled(if current { "  (shown)" } else { "" }, app.styling.text_style),
+            ])
+        })
+        .collect();
+
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Seeds ", app.styling.text_style)))
+        .title_bottom(
+            Line::from(Span::styled(" ↑/↓  enter: replay  f: favorite  esc ", app.styling.text_style)).left_aligned(),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Centered panel with a row per cluster of near-duplicates, the selected spelling marked in the selected row
+fn render_pruning(f: &mut Frame, frame_area: Rect, app: &App, cluster: usize, spelling: usize) {
+    let area = centered_panel(frame_area, 72, app.prune_clusters.len().max(1) as u16 + 2);
+    let rows = area.height.saturating_sub(2) as usize;
+    let first = cluster.saturating_sub(rows.saturating_sub(1));
+    let mut lines: Vec<Line> = app
+        .prune_clusters
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(index, forms)| {
+            let row_style = if index == cluster { app.styling.selected_text_style } else { app.styling.text_style };
+            let mut spans = vec![Span::styled(" ", row_style)];
+            for (position, (word, count)) in forms.iter().enumerate() {
+                if position > 0 {
+                    spans.push(Span::styled(" · ", row_style));
+                }
+                let style = if index == cluster && position == spelling { app.styling.current_selected_style } else { row_style };
+                spans.push(Span::styled(word.clone(), style));
+                spans.push(Span::styled(format!(" {}", count), row_style));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(" No near-duplicates left", app.styling.text_style)));
+    }
+
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(
+            format!(" Near-Duplicates ({}) ", app.prune_clusters.len()),
+            app.styling.text_style,
+        )))
+        .title_bottom(
+            Line::from(Span::styled(" ↑/↓ ←/→  m merge  M all  x delete  X others  esc ", app.styling.text_style))
+                .left_aligned(),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
 }
 
-pub fn ui(f: &mut Frame, app: &mut App) {
-    let frame_area = f.area();
+/// Centered panel listing the themes, with the selected one highlighted in its own styling
+fn render_theme_picker(f: &mut Frame, frame_area: Rect, app: &App, selected: usize) {
+    let area = centered_panel(frame_area, 40, app.themes.len() as u16 + 2);
+    let rows = area.height.saturating_sub(2) as usize;
+    let first = selected.saturating_sub(rows.saturating_sub(1));
+    let lines: Vec<Line> = app
+        .themes
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(index, name)| {
+            let builtin = styling::BUILTIN_THEMES.contains(&name.as_str());
+            let style = if index == selected { app.styling.current_selected_style } else { app.styling.text_style };
+            Line::from(vec![
+                Span::styled(format!(" {}", name), style),
+                Span::styled(if builtin { "" } else { "  (user)" }, app.styling.text_style),
+            ])
+        })
+        .collect();
 
-    // Render unified background for theme when necessary
-    if app.styling.use_background_fill {
-        let background = widget_block(app.styling.border_type)
-            .style(app.styling.text_style)
-            .borders(ratatui::widgets::Borders::empty());
-        f.render_widget(background, frame_area);
-    }
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Themes ", app.styling.text_style)))
+        .title_bottom(Line::from(Span::styled(" ↑/↓ try  enter: keep  esc: back ", app.styling.text_style)).left_aligned());
 
-    if app.fullscreen_mode {
-        // Fullscreen mode: canvas takes entire screen
-        render_canvas(f, frame_area, app);
-    } else {
-        // Normal mode: sidebar + canvas layout
-        // Calculate sidebar width based on content
-        let sidebar_width = calculate_sidebar_width_for_app(app);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
 
-        let main_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Length(sidebar_width), Constraint::Min(0)])
-            .split(frame_area);
+/// Centered panel with the newest log lines that fit, or older ones when scrolled back
+fn render_log(f: &mut Frame, frame_area: Rect, app: &App, scrolled: usize) {
+    let lines = logging::recent();
+    let area = centered_panel(frame_area, 100, frame_area.height);
+    let rows = area.height.saturating_sub(2) as usize;
+    let end = lines.len().saturating_sub(scrolled.min(lines.len().saturating_sub(rows)));
+    let shown: Vec<Line> = lines[end.saturating_sub(rows)..end]
+        .iter()
+        .map(|line| {
+            let style = if line.contains(" warning: ") { app.styling.selected_text_style } else { app.styling.text_style };
+            Line::from(Span::styled(format!(" {}", line), style))
+        })
+        .collect();
 
-        render_sidebar(f, main_layout[0], app);
-        render_canvas(f, main_layout[1], app);
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(format!(" Log ({} lines) ", lines.len()), app.styling.text_style)))
+        .title_bottom(Line::from(Span::styled(" ↑/↓ scroll  esc: close ", app.styling.text_style)).left_aligned());
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(shown).block(block), area);
+}
+
+/// Centered panel with every stored context of the selected word under the name of its file, scrolled
+/// no further than the last line
+fn render_concordance(f: &mut Frame, frame_area: Rect, app: &mut App) {
+    let text_width = centered_panel(frame_area, 80, 0).width.saturating_sub(4).max(1) as usize;
+    let word = app.selected_word_index.and_then(|index| app.scattered_words.get(index)).map(|scattered| scattered.word.clone());
+    let contexts = word.as_ref().and_then(|word| app.word_contexts.get(word)).map(Vec::as_slice).unwrap_or_default();
+
+    let mut lines = Vec::new();
+    for (context, source_file) in contexts {
+        lines.push(Line::from(Span::styled(format!(" {}", app.source_name(source_file)), app.styling.selected_text_style)));
+        for part in export::wrap_words(&format!("…{}…", context), text_width) {
+            lines.push(Line::from(Span::styled(format!("  {}", part), app.styling.text_style)));
+        }
+        lines.push(Line::from(""));
+    }
+    lines.pop();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(" no context recorded for this word", app.styling.text_style)));
     }
+
+    let area = centered_panel(frame_area, 80, lines.len().min(u16::MAX as usize - 2) as u16 + 2);
+    let rows = area.height.saturating_sub(2) as usize;
+    let scrolled = app.concordance.unwrap_or_default().min(lines.len().saturating_sub(rows));
+    app.concordance = Some(scrolled);
+    let shown: Vec<Line> = lines.into_iter().skip(scrolled).take(rows).collect();
+
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(
+            format!(" {} in context ({}) ", word.unwrap_or_default(), contexts.len()),
+            app.styling.text_style,
+        )))
+        .title_bottom(Line::from(Span::styled(" ↑/↓ scroll  esc: close ", app.styling.text_style)).left_aligned());
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(shown).block(block), area);
 }
 
 fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
@@ -328,33 +2123,26 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
 
     // Calculate info box height dynamically if a word is selected
     let info_box_height = if has_selection {
-        if let Some(index) = app.selected_word_index {
-            if let Some(scattered_word) = app.scattered_words.get(index) {
-                let word_text = format!("Word: {}", scattered_word.word);
-                let file_text = format!("File: {}", scattered_word.source_file);
-
-                // Wrap both lines
-                let word_wrapped = wrap_text_line(&word_text, max_width);
-                let file_wrapped = wrap_text_line(&file_text, max_width);
-                let total_lines = word_wrapped.len() + file_wrapped.len();
-
-                (total_lines + 2) as u16 // Add 2 for borders
-            } else {
-                4 // Default height
-            }
-        } else {
-            4 // Default height
-        }
+        (wrapped_info_lines(app, max_width).len() + 2) as u16 // Add 2 for borders
     } else {
         0 // Not used when no selection
     };
 
+    let stats_box_height = if app.show_stats { 6 } else { 0 }; // 4 lines + 2 for borders
+    let timer_box_height = if app.timer.is_some() { 3 } else { 0 }; // 1 line + 2 for borders
+    let lipogram_box_height = if app.lipogram.is_some() { 3 } else { 0 }; // 1 line + 2 for borders
+    let syllables_box_height = if app.syllable_budget.is_some() { 3 } else { 0 }; // 1 line + 2 for borders
+
     // Calculate fixed sections height first to ensure they have priority
-    let fixed_height = if has_selection {
-        4 + 3 + 8 + info_box_height  // Scatters + Density + Controls + Info (dynamic)
-    } else {
-        4 + 3 + 8  // Scatters + Density + Controls
-    };
+    // Scatters + Density + Controls + Info (dynamic) + Stats, Timer, Lipogram and Syllables (all optional)
+    let fixed_height = 5
+        + 3
+        + CONTROLS_BOX_HEIGHT
+        + info_box_height
+        + stats_box_height
+        + timer_box_height
+        + lipogram_box_height
+        + syllables_box_height;
 
     // Calculate path box height dynamically based on wrapped content
     // But cap it to remaining available space
@@ -367,46 +2155,40 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
     let max_path_height = area.height.saturating_sub(fixed_height).max(3);
     let path_box_height = ideal_path_box_height.min(max_path_height);
 
-    let sections = if has_selection {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(4),                  // Scatters - fixed
-                Constraint::Length(3),                  // Density - fixed
-                Constraint::Length(8),                  // Controls - fixed (priority)
-                Constraint::Length(info_box_height),    // Info - dynamically sized to wrapped content
-                Constraint::Length(path_box_height),    // Path - sized to content, capped to available space
-            ])
-            .split(area)
-    } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(4),                  // Scatters - fixed
-                Constraint::Length(3),                  // Density - fixed
-                Constraint::Length(8),                  // Controls - fixed (priority)
-                Constraint::Length(path_box_height),    // Path - sized to content, capped to available space
-            ])
-            .split(area)
-    };
+    let mut constraints = vec![
+        Constraint::Length(if overflow_text(app).is_some() { 6 } else { 5 }),  // Scatters - grows by a line when the canvas is full
+        Constraint::Length(3),                    // Density - fixed
+        Constraint::Length(CONTROLS_BOX_HEIGHT),  // Controls - fixed (priority)
+    ];
+    if has_selection {
+        constraints.push(Constraint::Length(info_box_height)); // Info - dynamically sized to wrapped content
+    }
+    if app.show_stats {
+        constraints.push(Constraint::Length(stats_box_height)); // Stats - optional
+    }
+    if app.timer.is_some() {
+        constraints.push(Constraint::Length(timer_box_height)); // Timer - optional
+    }
+    if app.lipogram.is_some() {
+        constraints.push(Constraint::Length(lipogram_box_height)); // Lipogram - optional
+    }
+    if app.syllable_budget.is_some() {
+        constraints.push(Constraint::Length(syllables_box_height)); // Syllables - optional
+    }
+    constraints.push(Constraint::Length(path_box_height)); // Path - sized to content, capped to available space
+
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
 
     // Calculate container area dynamically based on actual section positions
-    let container_area = if has_selection {
-        let last_section = &sections[4]; // Path section is last
-        Rect {
-            x: area.x,
-            y: sections[0].y,
-            width: area.width,
-            height: (last_section.y + last_section.height).saturating_sub(sections[0].y),
-        }
-    } else {
-        let last_section = &sections[3]; // Path section is last (no Info section)
-        Rect {
-            x: area.x,
-            y: sections[0].y,
-            width: area.width,
-            height: (last_section.y + last_section.height).saturating_sub(sections[0].y),
-        }
+    let last_section = &sections[sections.len() - 1]; // Path section is last
+    let container_area = Rect {
+        x: area.x,
+        y: sections[0].y,
+        width: area.width,
+        height: (last_section.y + last_section.height).saturating_sub(sections[0].y),
     };
 
     let mut sidebar_container = widget_block(app.styling.border_type)
@@ -426,13 +2208,21 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
         scatters_block = scatters_block.style(app.styling.text_style);
     }
 
-    let count_text = format!("{} / {} words", app.scattered_words.len(), app.word_count);
+    let count_text = count_text(app);
     let highlighted_text = format!("{} / {} selected", app.highlighted_words.len(), app.scattered_words.len());
 
-    let scatters_text = vec![
+    let mut scatters_text = vec![
         Line::from(Span::styled(count_text, app.styling.text_style)),
         Line::from(Span::styled(highlighted_text, app.styling.text_style)),
     ];
+    if let Some(overflow) = overflow_text(app) {
+        scatters_text.push(Line::from(Span::styled(overflow, app.styling.text_style)));
+    }
+    scatters_text.push(Line::from(Span::styled(format!("seed {}", app.seed), app.styling.text_style)));
+    if app.generating.is_some() {
+        let style = app.styling.text_style.add_modifier(Modifier::DIM);
+        scatters_block = scatters_block.title_bottom(Line::from(Span::styled(" generating… ", style)).right_aligned());
+    }
 
     let scatters = Paragraph::new(scatters_text)
         .block(scatters_block)
@@ -442,7 +2232,7 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
 
     let mut density_block = widget_block(app.styling.border_type)
         .border_style(app.styling.border_style)
-        .title_top(Line::from(Span::styled(" Density ", app.styling.text_style)));
+        .title_top(Line::from(Span::styled(density_title(app), app.styling.text_style)));
 
     if app.styling.use_background_fill {
         density_block = density_block.style(app.styling.text_style);
@@ -451,8 +2241,9 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
     let available_width = sections[1].width.saturating_sub(2).max(8) as usize;
     let bar_width = available_width;
 
-    // Store the actual bar width for use in key handling
+    // Store the actual bar width for use in key handling, and where it is for clicks
     app.actual_bar_width = bar_width as u16;
+    app.density_bar = Rect { height: 1, ..density_block.inner(sections[1]) };
 
     let density_ratio = (app.density - 0.1) / (6.0 - 0.1);
     let filled_width = (density_ratio * bar_width as f32) as usize;
@@ -503,6 +2294,14 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
             Span::styled("v", app.styling.text_style),
             Span::styled(" - view", app.styling.text_style),
         ]),
+        Line::from(vec![
+            Span::styled("i", app.styling.text_style),
+            Span::styled(" - stats", app.styling.text_style),
+        ]),
+        Line::from(vec![
+            Span::styled("g", app.styling.text_style),
+            Span::styled(" - ruler", app.styling.text_style),
+        ]),
         Line::from(vec![
             Span::styled("q", app.styling.text_style),
             Span::styled(" - quit", app.styling.text_style),
@@ -516,15 +2315,80 @@ fn render_sidebar(f: &mut Frame, area: Rect, app: &mut App) {
 
     f.render_widget(controls, sections[2]);
 
+    // Optional sections follow the fixed ones in layout order
+    let mut next_section = 3;
+
     // Render info box if a word is selected
     if has_selection {
-        render_info_box(f, sections[3], app);
-        render_path_box(f, sections[4], app);
-    } else {
-        render_path_box(f, sections[3], app);
+        render_info_box(f, sections[next_section], app);
+        next_section += 1;
+    }
+
+    if app.show_stats {
+        render_stats_box(f, sections[next_section], app);
+        next_section += 1;
+    }
+
+    if let Some(timer) = &app.timer {
+        render_timer_box(f, sections[next_section], app, timer);
+        next_section += 1;
+    }
+
+    if let Some(letters) = &app.lipogram {
+        let letters: Vec<String> = letters.chars().map(String::from).collect();
+        render_line_box(f, sections[next_section], app, " Lipogram ", format!("no {}", letters.join(", ")));
+        next_section += 1;
+    }
+
+    if let Some(budget) = app.syllable_budget {
+        render_line_box(f, sections[next_section], app, " Syllables ", format!("{} / {}", app.syllable_count(), budget));
+        next_section += 1;
+    }
+
+    render_path_box(f, sections[next_section], app);
+}
+
+/// Lines describing the selected word: text, source file, place in the source and canvas position
+fn info_lines(app: &App) -> Vec<String> {
+    match app.selected_word_index.and_then(|index| app.scattered_words.get(index)) {
+        Some(scattered_word) => {
+            let mut lines = vec![
+                format!("Word: {}", scattered_word.word),
+                format!("File: {}", app.source_name(&scattered_word.source_file)),
+            ];
+            // Word-game figures, kept short for the narrow sidebar
+            let mix = wordplay::letter_mix(&scattered_word.word);
+            lines.push(format!("Letters: {}", mix.letters));
+            lines.push(format!("V:C {}:{}", mix.vowels, mix.consonants));
+            lines.push(format!("Scrabble: {}", wordplay::scrabble_score(&scattered_word.word)));
+            if let (Some(voice), Some(names)) = (scattered_word.voice, &app.voice_names) {
+                let name = match voice {
+                    Voice::A => &names[0],
+                    Voice::B => &names[1],
+                };
+                lines.push(format!("Voice: {}", name));
+            }
+            if let Some(location) = app.word_locations.get(&scattered_word.word) {
+                lines.push(format!("From: {}", location.describe()));
+            }
+            if let Some((context, _)) = app.word_contexts.get(&scattered_word.word).and_then(|contexts| contexts.first()) {
+                lines.push(format!("In context: …{}…", context));
+            }
+            lines.push(format!("Pos: {}, {}", scattered_word.x, scattered_word.y));
+            lines
+        }
+        None => vec!["Word: (none)".to_string(), "File: (none)".to_string()],
     }
 }
 
+/// Info lines wrapped at character boundaries to fit the sidebar
+fn wrapped_info_lines(app: &App, max_width: usize) -> Vec<String> {
+    info_lines(app)
+        .iter()
+        .flat_map(|line| wrap_text_line(line, max_width))
+        .collect()
+}
+
 fn render_info_box(f: &mut Frame, area: Rect, app: &App) {
     let mut info_block = widget_block(app.styling.border_type)
         .border_style(app.styling.border_style)
@@ -534,35 +2398,14 @@ fn render_info_box(f: &mut Frame, area: Rect, app: &App) {
         info_block = info_block.style(app.styling.text_style);
     }
 
-    // Get the selected word and its source file
-    let (word_text, file_text) = if let Some(index) = app.selected_word_index {
-        if let Some(scattered_word) = app.scattered_words.get(index) {
-            (
-                format!("Word: {}", scattered_word.word),
-                format!("File: {}", scattered_word.source_file),
-            )
-        } else {
-            ("Word: (none)".to_string(), "File: (none)".to_string())
-        }
-    } else {
-        ("Word: (none)".to_string(), "File: (none)".to_string())
-    };
-
-    // Wrap both text lines
+    // Wrap all text lines
     let available_width = area.width.saturating_sub(4) as usize; // Subtract borders and padding
     let max_width = available_width.max(10); // Minimum width of 10 chars
 
-    let word_wrapped = wrap_text_line(&word_text, max_width);
-    let file_wrapped = wrap_text_line(&file_text, max_width);
-
-    // Combine all wrapped lines into the display text
-    let mut info_text: Vec<Line> = Vec::new();
-    for line in word_wrapped {
-        info_text.push(Line::from(Span::styled(line, app.styling.text_style)));
-    }
-    for line in file_wrapped {
-        info_text.push(Line::from(Span::styled(line, app.styling.text_style)));
-    }
+    let info_text: Vec<Line> = wrapped_info_lines(app, max_width)
+        .into_iter()
+        .map(|line| Line::from(Span::styled(line, app.styling.text_style)))
+        .collect();
 
     let info = Paragraph::new(info_text)
         .block(info_block)
@@ -571,6 +2414,75 @@ fn render_info_box(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(info, area);
 }
 
+fn render_stats_box(f: &mut Frame, area: Rect, app: &App) {
+    let mut stats_block = widget_block(app.styling.border_type)
+        .border_style(app.styling.border_style)
+        .title_top(Line::from(Span::styled(" Session ", app.styling.text_style)));
+
+    if app.styling.use_background_fill {
+        stats_block = stats_block.style(app.styling.text_style);
+    }
+
+    let stats_lines = [
+        format!("{} rerolls", app.stats.rerolls),
+        format!("{} visited", app.stats.words_visited),
+        format!("{} highlighted", app.stats.words_highlighted),
+        format!("{} elapsed", format_elapsed(app.stats.elapsed())),
+    ];
+
+    let stats_text: Vec<Line> = stats_lines
+        .into_iter()
+        .map(|line| Line::from(Span::styled(line, app.styling.text_style)))
+        .collect();
+
+    let stats = Paragraph::new(stats_text)
+        .block(stats_block)
+        .alignment(Alignment::Left);
+
+    f.render_widget(stats, area);
+}
+
+fn render_timer_box(f: &mut Frame, area: Rect, app: &App, timer: &WritingTimer) {
+    let title = if timer.is_countdown() { " Countdown " } else { " Timer " };
+    // A finished countdown flashes the box a few times, then settles
+    let style = if timer.flashing() {
+        app.styling.text_style.add_modifier(Modifier::REVERSED)
+    } else {
+        app.styling.text_style
+    };
+
+    let mut timer_block = widget_block(app.styling.border_type)
+        .border_style(app.styling.border_style)
+        .title_top(Line::from(Span::styled(title, app.styling.text_style)));
+
+    if app.styling.use_background_fill || timer.flashing() {
+        timer_block = timer_block.style(style);
+    }
+
+    let timer_text = Paragraph::new(Line::from(Span::styled(timer.reading(), style)))
+        .block(timer_block)
+        .alignment(Alignment::Left);
+
+    f.render_widget(timer_text, area);
+}
+
+/// A sidebar section holding a single line, for constraints like the lipogram
+fn render_line_box(f: &mut Frame, area: Rect, app: &App, title: &str, reading: String) {
+    let mut line_block = widget_block(app.styling.border_type)
+        .border_style(app.styling.border_style)
+        .title_top(Line::from(Span::styled(title, app.styling.text_style)));
+
+    if app.styling.use_background_fill {
+        line_block = line_block.style(app.styling.text_style);
+    }
+
+    let line_text = Paragraph::new(Line::from(Span::styled(reading, app.styling.text_style)))
+        .block(line_block)
+        .alignment(Alignment::Left);
+
+    f.render_widget(line_text, area);
+}
+
 fn render_path_box(f: &mut Frame, area: Rect, app: &App) {
     let mut path_block = widget_block(app.styling.border_type)
         .border_style(app.styling.border_style)
@@ -609,22 +2521,117 @@ fn render_path_box(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(path, area);
 }
 
-fn render_canvas(f: &mut Frame, area: Rect, app: &App) {
+/// Faint line through the highlighted words in the order they were visited
+fn render_reading_path(buffer: &mut Buffer, inner: Rect, app: &App) {
+    let style = app.styling.highlighted_border_style.add_modifier(Modifier::DIM);
+    let cells = reading_path::path_cells(&app.scattered_words, &app.highlighted_words, inner.width, inner.height);
+
+    for (x, y, symbol) in cells {
+        buffer[(inner.x + x, inner.y + y)].set_char(symbol).set_style(style);
+    }
+}
+
+fn render_canvas(f: &mut Frame, area: Rect, app: &mut App) {
+    // Status shown along the bottom: the time window or template on the right, prompts and notices on the left
+    let status_right = match (&app.time_window, &app.template) {
+        (Some(window), _) => Some(format!(" {} ", window)),
+        (None, Some(template)) => Some(format!(" template {} ", template.name)),
+        (None, None) => None,
+    };
+    let status_left = if app.exclusion_editor.is_some() {
+        Some(" space: corner  del: undo  x: done ".to_string())
+    } else if app.ghost.is_some() && app.notice.is_none() {
+        Some(" ←/→ earlier word  enter: bring back  T: done ".to_string())
+    } else if let Some(input) = &app.palette {
+        Some(format!(" :{}▏", input))
+    } else if let Some(input) = &app.save_prompt {
+        Some(format!(" save as: {}▏ ", input))
+    } else {
+        app.notice.as_ref().map(|notice| format!(" {} ", notice))
+    };
+
     // Create canvas block with border and background
-    let mut canvas_block = widget_block(app.styling.border_type)
-        .border_style(app.styling.highlighted_border_style);
+    let mut canvas_block = if app.show_canvas_border {
+        widget_block(app.styling.border_type).border_style(app.styling.highlighted_border_style)
+    } else {
+        Block::new()
+    };
 
     if app.styling.use_background_fill {
         canvas_block = canvas_block.style(app.styling.text_style);
     }
 
-    let inner = canvas_block.inner(area);
+    // Without a border the status is drawn over the last row instead, so the canvas doesn't shift
+    if app.show_canvas_border {
+        if let Some(status) = &status_right {
+            canvas_block = canvas_block
+                .title_bottom(Line::from(Span::styled(status.as_str(), app.styling.text_style)).right_aligned());
+        }
+        if let Some(status) = &status_left {
+            canvas_block = canvas_block
+                .title_bottom(Line::from(Span::styled(status.as_str(), app.styling.text_style)).left_aligned());
+        }
+    }
+
+    let view = canvas_block.inner(area);
     f.render_widget(canvas_block, area);
 
+    // Store the canvas area for mouse hit-testing
+    app.canvas_inner = view;
+    app.clamp_pan();
+    if let Some(editor) = &app.exclusion_editor {
+        let (x, y) = editor.cursor;
+        app.pan = (
+            app.pan.0.clamp((x + 1).saturating_sub(view.width), x),
+            app.pan.1.clamp((y + 1).saturating_sub(view.height), y),
+        );
+    }
+
+    // The ruler is drawn on the border
+    if app.show_ruler && app.show_canvas_border {
+        render_ruler(f, area, view, app);
+    }
+
+    // The whole canvas is drawn off screen, then the part in view copied over, so a canvas set bigger
+    // than the terminal with --width/--height can be panned across
+    let (page_width, page_height) = app.page_size();
+    let inner = Rect::new(0, 0, page_width, page_height);
+    let mut page = Buffer::empty(inner);
+    if app.styling.use_background_fill {
+        page.set_style(inner, app.styling.text_style);
+    }
+
+    if let Some(sentence) = &app.watermark {
+        render_watermark(&mut page, inner, app, sentence);
+    }
+
+    if app.exclusion_editor.is_some() {
+        render_exclusions(&mut page, inner, app);
+    }
+
+    if app.show_path && !app.screensaver_active {
+        render_reading_path(&mut page, inner, app);
+    }
+
+    if let Some(cursor) = app.ghost {
+        render_ghosts(&mut page, inner, app, cursor);
+    }
+
     // Render scattered words with highlight effect for selected word
+    let shown_words = app.shown_words();
     for (index, scattered) in app.scattered_words.iter().enumerate() {
+        // Words beyond the reveal point are still hidden by the transition
+        if app.reveal_count.is_some_and(|count| index >= count) {
+            break;
+        }
+
+        // So are words the reroll transition hasn't brought in yet
+        let Some((row, letters, arriving)) = app.arriving_word(index, &shown_words[index]) else {
+            continue;
+        };
+
         let x_pos = inner.x.saturating_add(scattered.x.min(inner.width.saturating_sub(1)));
-        let y_pos = inner.y.saturating_add(scattered.y.min(inner.height.saturating_sub(1)));
+        let y_pos = inner.y.saturating_add(row.min(inner.height.saturating_sub(1)));
 
         if x_pos >= inner.x
             && x_pos < inner.x + inner.width
@@ -635,13 +2642,15 @@ fn render_canvas(f: &mut Frame, area: Rect, app: &App) {
 
             if available_width > 0 {
                 // Truncate word at character boundary if it exceeds available width
-                let word = if scattered.word.chars().count() > available_width as usize {
-                    scattered.word
+                let shown = &shown_words[index].word;
+                let shown_width = (available_width as usize).min(letters);
+                let word = if shown.chars().count() > shown_width {
+                    shown
                         .chars()
-                        .take(available_width as usize)
+                        .take(shown_width)
                         .collect::<String>()
                 } else {
-                    scattered.word.clone()
+                    shown.clone()
                 };
 
                 let word_rect = Rect {
@@ -651,8 +2660,17 @@ fn render_canvas(f: &mut Frame, area: Rect, app: &App) {
                     height: 1,
                 };
 
+                // Unvisited words take their voice's color in dialogue mode
+                let base_style = match scattered.voice {
+                    Some(Voice::B) => app.styling.second_voice_style,
+                    _ => app.styling.text_style,
+                };
+
                 // Apply three-tier styling: current selected, previously highlighted, or default
-                let word_style = if app.selected_word_index == Some(index) {
+                // Highlighting is paused while the screensaver runs, which shows each word's age instead
+                let word_style = if app.screensaver_active {
+                    age_style(base_style, app.word_ages.get(&scattered.word).copied().unwrap_or(0))
+                } else if app.selected_word_index == Some(index) {
                     if app.use_dimmed_current {
                         app.styling.selected_text_style  // Currently selected but dimmed (same as visited)
                     } else {
@@ -660,13 +2678,336 @@ fn render_canvas(f: &mut Frame, area: Rect, app: &App) {
                     }
                 } else if app.highlighted_words.contains(&index) {
                     app.styling.selected_text_style  // Previously visited
+                } else if app.recurring_words.contains(&scattered.word) {
+                    app.styling.highlighted_border_style.add_modifier(Modifier::BOLD)  // Also in the last scatter
+                } else if app.cloud_mode {
+                    app.cloud_style(&scattered.word, base_style)  // Not visited, weighted by frequency
+                } else {
+                    base_style  // Not visited
+                };
+
+                // The current word keeps its selection style as it is, so it stays easy to find
+                let word_style = if app.font_styles && app.selected_word_index != Some(index) {
+                    word_style.add_modifier(styling::font_style(app.seed, &scattered.word))
+                } else {
+                    word_style
+                };
+
+                // Locked and pinned words are underlined whatever their state
+                let word_style = if app.is_locked(&scattered.word) || app.is_pinned(scattered) {
+                    word_style.add_modifier(Modifier::UNDERLINED)
+                } else {
+                    word_style
+                };
+
+                // Both words of a pair share a color and a numbered marker
+                let pair = app.pair_of(index).filter(|_| !app.screensaver_active);
+                let word_style = match pair {
+                    Some(pair) if app.color => word_style.fg(PAIR_COLORS[pair % PAIR_COLORS.len()]),
+                    _ => word_style,
+                };
+                if let Some(pair) = pair {
+                    let marker_x = x_pos + word_rect.width;
+                    if marker_x < inner.x + inner.width {
+                        page.set_stringn(marker_x, y_pos, pair_marker(pair + 1), (inner.x + inner.width - marker_x) as usize, word_style);
+                    }
+                }
+
+                let cued = app.selected_word_index == Some(index) && !app.screensaver_active;
+                let word_style = if cued && app.selection_cue_showing(SelectionCue::Flash) {
+                    word_style.add_modifier(Modifier::REVERSED)
                 } else {
-                    app.styling.text_style  // Not visited
+                    word_style
                 };
+                if cued && app.selection_cue_showing(SelectionCue::Marker) && x_pos > inner.x {
+                    page.set_string(x_pos - 1, y_pos, "▸", app.styling.current_selected_style);
+                }
+
+                let word_widget = Paragraph::new(Line::from(Span::styled(&word, word_style.add_modifier(arriving))));
+                word_widget.render(word_rect, &mut page);
+            }
+        }
+    }
+
+    if let Some(epigraph) = &app.epigraph {
+        let style = app.styling.text_style.add_modifier(Modifier::ITALIC);
+        for (x, y, line) in export::epigraph_lines(epigraph, inner.width, inner.height, app.title.is_some()) {
+            page.set_string(inner.x + x, inner.y + y, line, style);
+        }
+    }
+
+    if let Some(title) = &app.title {
+        let (x, y) = export::title_origin(title, inner.width, inner.height);
+        let title_rect = Rect { x: inner.x + x, y: inner.y + y, width: inner.width - x, height: 1.min(inner.height) };
+        Paragraph::new(Span::styled(title.as_str(), app.styling.highlighted_border_style)).render(title_rect, &mut page);
+    }
+
+    let buffer = f.buffer_mut();
+    for y in 0..view.height.min(page_height.saturating_sub(app.pan.1)) {
+        for x in 0..view.width.min(page_width.saturating_sub(app.pan.0)) {
+            buffer[(view.x + x, view.y + y)] = page[(app.pan.0 + x, app.pan.1 + y)].clone();
+        }
+    }
+
+    // The typewriter line, prompt card and status stay put while the canvas pans
+    let inner = view;
+    if !app.typed_words.is_empty() {
+        render_typewriter(f, inner, app);
+    }
+
+    if let Some((_, card)) = &app.prompt_card {
+        render_prompt_card(f, inner, app, card);
+    }
+
+    if !app.show_canvas_border && inner.height > 0 {
+        let status_row = Rect { y: inner.y + inner.height - 1, height: 1, ..inner };
+        if let Some(status) = status_right {
+            f.render_widget(Paragraph::new(Span::styled(status, app.styling.text_style)).alignment(Alignment::Right), status_row);
+        }
+        if let Some(status) = status_left {
+            let width = (status.chars().count() as u16).min(status_row.width);
+            f.render_widget(Paragraph::new(Span::styled(status, app.styling.text_style)), Rect { width, ..status_row });
+        }
+    }
+}
+
+/// The previous scatter's words, faint, wherever they don't run into a word of this one; the one under
+/// the ghost cursor is shown reversed even where it does
+fn render_ghosts(buffer: &mut Buffer, inner: Rect, app: &App, cursor: usize) {
+    let style = app.styling.text_style.add_modifier(Modifier::DIM | Modifier::ITALIC);
+    for (index, ghost) in app.ghost_words.iter().enumerate() {
+        if ghost.x >= inner.width || ghost.y >= inner.height {
+            continue;
+        }
+        let style = if index == cursor { style.add_modifier(Modifier::REVERSED) } else { style };
+        if index == cursor || app.has_room(ghost) {
+            let width = (inner.width - ghost.x) as usize;
+            buffer.set_stringn(inner.x + ghost.x, inner.y + ghost.y, &ghost.word, width, style);
+        }
+    }
+}
+
+/// The watermark sentence in the faintest style, cleared for a cell either side of each word as in exports
+fn render_watermark(buffer: &mut Buffer, inner: Rect, app: &App, sentence: &str) {
+    let style = app.styling.text_style.add_modifier(Modifier::DIM);
+    for (x, y, line) in export::watermark_lines(sentence, inner.width, inner.height) {
+        buffer.set_string(inner.x + x, inner.y + y, line, style);
+    }
+
+    for word in &app.scattered_words {
+        let end = word.x + word.word.chars().count() as u16;
+        for x in [word.x.checked_sub(1), Some(end)].into_iter().flatten() {
+            if x < inner.width && word.y < inner.height {
+                buffer[(inner.x + x, inner.y + word.y)].set_char(' ');
+            }
+        }
+    }
+}
+
+/// The constraint card in the top right corner of the canvas, over any words beneath it
+fn render_prompt_card(f: &mut Frame, inner: Rect, app: &App, card: &str) {
+    let width = inner.width.min(PROMPT_CARD_WIDTH);
+    let lines = export::wrap_words(card, width.saturating_sub(4).max(1) as usize);
+    let area = Rect {
+        x: inner.x + inner.width - width,
+        y: inner.y,
+        width,
+        height: inner.height.min(lines.len() as u16 + 2),
+    };
+
+    let block = widget_block(app.styling.border_type)
+        .border_style(app.styling.highlighted_border_style)
+        .style(app.styling.text_style)
+        .title_top(Line::from(Span::styled(" Prompt ", app.styling.text_style)));
+    let text: Vec<Line> = lines
+        .into_iter()
+        .map(|line| Line::from(Span::styled(format!(" {}", line), app.styling.text_style.add_modifier(Modifier::BOLD))))
+        .collect();
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+/// The typewriter line across the bottom of the canvas, above the status when the border is hidden,
+/// keeping its end and the blinking cursor in view
+fn render_typewriter(f: &mut Frame, inner: Rect, app: &App) {
+    let from_bottom = if app.show_canvas_border { 1 } else { 2 };
+    if inner.height < from_bottom {
+        return;
+    }
+
+    // Breaks show as the slashes used when quoting verse inline
+    let typed: String = app.typed_text().chars().take(app.typed_shown).collect();
+    let typed = typed.replace("\n\n", " // ").replace('\n', " / ");
+    let line = format!(" {}{}", typed, if app.cursor_visible { "▌" } else { " " });
+    let overflow = line.chars().count().saturating_sub(inner.width as usize);
+    let line: String = line.chars().skip(overflow).collect();
 
-                let word_widget = Paragraph::new(Line::from(Span::styled(&word, word_style)));
-                f.render_widget(word_widget, word_rect);
+    let row = Rect { y: inner.y + inner.height - from_bottom, height: 1, ..inner };
+    f.render_widget(Clear, row);
+    f.render_widget(Paragraph::new(Span::styled(line, app.styling.current_selected_style)), row);
+}
+
+/// Shade exclusion zones, the one being drawn, and the cursor while editing
+fn render_exclusions(buffer: &mut Buffer, inner: Rect, app: &App) {
+    let style = app.styling.highlighted_border_style;
+
+    let mut shade = |area: &Area, symbol: &str| {
+        for y in area.y..(area.y + area.height).min(inner.height) {
+            for x in area.x..(area.x + area.width).min(inner.width) {
+                buffer.set_string(inner.x + x, inner.y + y, symbol, style);
             }
         }
+    };
+
+    for area in &app.exclusions {
+        shade(area, "░");
+    }
+    if let Some(pending) = app.pending_exclusion() {
+        shade(&pending, "▒");
+    }
+    if let Some(editor) = &app.exclusion_editor {
+        let (x, y) = editor.cursor;
+        if x < inner.width && y < inner.height {
+            buffer.set_string(inner.x + x, inner.y + y, "┼", style);
+        }
+    }
+}
+
+/// Draw column numbers along the top border and row ticks down the left border
+/// Coordinates match the `Pos` readout: (0, 0) is the first cell inside the border, wherever the view is panned
+fn render_ruler(f: &mut Frame, area: Rect, inner: Rect, app: &App) {
+    let style = app.styling.highlighted_border_style;
+    let buffer = f.buffer_mut();
+    let (pan_x, pan_y) = app.pan;
+
+    // Top edge: full column numbers every 10 cells, ticks in between
+    for column in (pan_x.next_multiple_of(5)..pan_x + inner.width).step_by(5) {
+        let label = if column % 10 == 0 {
+            column.to_string()
+        } else {
+            "┬".to_string()
+        };
+        let x = inner.x + column - pan_x;
+        let room = (inner.x + inner.width).saturating_sub(x) as usize;
+        if label.chars().count() <= room {
+            buffer.set_string(x, area.y, label, style);
+        }
+    }
+
+    // Left edge is one cell wide, so label tens of rows with their leading digit
+    for row in (pan_y.next_multiple_of(5)..pan_y + inner.height).step_by(5) {
+        let label = if row % 10 == 0 {
+            ((row / 10) % 10).to_string()
+        } else {
+            "├".to_string()
+        };
+        buffer.set_string(area.x, inner.y + row - pan_y, label, style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app(words: &[(&str, u16)]) -> App {
+        let words = words
+            .iter()
+            .map(|&(word, x)| ScatteredWord { word: word.to_string(), x, y: 0, source_file: "a.txt".to_string(), voice: None })
+            .collect();
+        let mut app = App::new(words, 10, AppStyling::from_theme("monochrome").unwrap(), PathBuf::from("corpus"));
+        app.scatter_size = (20, 1);
+        app
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let mut app = app(&[("lantern", 0), ("tide", 10)]);
+        app.note_changes();
+        app.density = 0.5;
+        app.note_changes();
+        app.scattered_words[1].word = "moon".to_string();
+        app.note_changes();
+
+        assert!(app.undo());
+        assert_eq!(app.scattered_words[1].word, "tide");
+        assert!(app.undo());
+        assert_eq!(app.density, 1.0);
+        assert!(!app.undo());
+
+        assert!(app.redo() && app.redo());
+        assert_eq!((app.density, app.scattered_words[1].word.as_str()), (0.5, "moon"));
+        assert!(!app.redo());
+
+        // A reroll still being generated is dropped rather than landing on the restored scatter
+        let (_, receiver) = std::sync::mpsc::channel();
+        app.generating = Some(PendingScatter { seed: 1, size: (20, 1), entrance: Entrance::Appear, receiver });
+        assert!(app.undo());
+        assert!(app.generating.is_none());
+    }
+
+    #[test]
+    fn test_new_change_clears_redo() {
+        let mut app = app(&[("lantern", 0)]);
+        app.note_changes();
+        app.density = 0.5;
+        app.note_changes();
+        assert!(app.undo());
+        assert_eq!(app.redo_history.len(), 1);
+
+        app.density = 0.7;
+        app.note_changes();
+        assert!(app.redo_history.is_empty());
+        assert!(!app.redo());
+        assert!(app.undo());
+        assert_eq!(app.density, 1.0);
+    }
+
+    #[test]
+    fn test_undo_history_capped() {
+        let mut app = app(&[("lantern", 0)]);
+        app.note_changes();
+        for step in 1..=UNDO_LIMIT + 5 {
+            app.seed = step as u64;
+            app.note_changes();
+        }
+        assert_eq!(app.undo_history.len(), UNDO_LIMIT);
+        while app.undo() {}
+        // The oldest states went first, so the earliest left is the sixth change
+        assert_eq!(app.seed, 5);
+    }
+
+    #[test]
+    fn test_ghost_closes_when_an_empty_scatter_is_replaced() {
+        let mut app = app(&[("lantern", 0), ("tide", 10)]);
+        app.update_words(Scatter { words: Vec::new(), requested: 0 });
+        app.open_ghost();
+        assert_eq!(app.ghost, Some(0));
+        app.move_ghost_cursor(-1);
+        assert_eq!(app.ghost, Some(1));
+
+        // A reroll with the overlay open makes the empty scatter the ghost, which closes it
+        app.update_words(Scatter { words: Vec::new(), requested: 0 });
+        assert_eq!(app.ghost, None);
+        // Moving the cursor over no ghosts at all does nothing
+        app.ghost = Some(0);
+        app.move_ghost_cursor(1);
+        assert_eq!(app.ghost, Some(0));
+    }
+
+    #[test]
+    fn test_respelling_kept_clear_of_neighbours() {
+        // "city" respells as "sitee", a letter longer, which keeps its length only where there's room
+        let mut roomy = app(&[("city", 0), ("tide", 10)]);
+        roomy.spelling = Spelling::Phonetic;
+        assert_eq!(roomy.shown_words()[0].word, "sitee");
+
+        let mut crowded = app(&[("city", 0), ("tide", 6)]);
+        crowded.spelling = Spelling::Phonetic;
+        assert_eq!(crowded.shown_words()[0].word, "site");
+
+        let mut edge = app(&[("city", 16)]);
+        edge.spelling = Spelling::Phonetic;
+        assert_eq!(edge.shown_words()[0].word, "site");
     }
 }
@@ -1,85 +1,408 @@
-use std::collections::HashMap;
+use crate::parser::{Location, ParsedWord};
+use crate::translate::Translation;
+use std::collections::{HashMap, HashSet};
+
+/// Everything the bank knows about one unique word
+#[derive(Clone, Debug, PartialEq)]
+pub struct BankEntry {
+    pub word: String,
+    pub source_file: String, // File of the first occurrence
+    pub location: Location,  // Position of the first occurrence in that file
+    pub count: usize,        // Occurrences across the whole corpus
+    pub contexts: Vec<(String, String)>, // Snippets around the first few occurrences with their files, at most CONTEXT_SAMPLES
+}
+
+/// Context snippets kept per word
+pub const CONTEXT_SAMPLES: usize = 3;
+/// Words kept on each side of an occurrence in its context snippet
+const CONTEXT_RADIUS: usize = 5;
+
+/// Up to CONTEXT_RADIUS words either side of `words[index]`, without crossing into another paragraph
+fn context_snippet(words: &[ParsedWord], index: usize) -> String {
+    let location = words[index].location;
+    let start = index.saturating_sub(CONTEXT_RADIUS);
+    let end = (index + CONTEXT_RADIUS + 1).min(words.len());
+    words[start..end]
+        .iter()
+        .filter(|parsed| parsed.location == location)
+        .map(|parsed| parsed.word.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Which spelling represents a group of folded forms
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FoldDisplay {
+    Base,     // The singular, non-possessive word: "poet"
+    Surface,  // The form the corpus uses most: "poets" if it outnumbers "poet" and "poet's"
+}
+
+impl FoldDisplay {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "base" => Ok(Self::Base),
+            "surface" => Ok(Self::Surface),
+            _ => Err(format!("unknown fold display '{}' (expected base or surface)", value)),
+        }
+    }
+}
+
+/// The word a possessive or plural folds into: "poet's" to "poet", and "shadows" to "shadow" or "stories"
+/// to "story" only when the singular is `known` too, so words like "glass" are left alone
+fn fold_key(word: &str, known: impl Fn(&str) -> bool) -> String {
+    let word = word.strip_suffix("'s").filter(|base| base.len() >= 3).unwrap_or(word);
+    if word.ends_with("ss") {
+        return word.to_string();
+    }
+
+    // "es" only comes off after a sibilant ("boxes", "churches"), so "notes" never folds into "not"
+    let sibilant = |stem: &&str| ["s", "x", "z", "ch", "sh"].iter().any(|ending| stem.ends_with(ending));
+    let singulars = [
+        word.strip_suffix("ies").map(|stem| format!("{}y", stem)),
+        word.strip_suffix('s').map(str::to_string),
+        word.strip_suffix("es").filter(sibilant).map(str::to_string),
+    ];
+    singulars
+        .into_iter()
+        .flatten()
+        .find(|singular| singular.len() >= 3 && known(singular))
+        .unwrap_or_else(|| word.to_string())
+}
+
+/// What the pruning panel decided about a near-duplicate, replayed whenever its corpus is loaded again
+#[derive(Clone, Debug, PartialEq)]
+pub enum PruneDecision {
+    Merge { from: String, into: String },  // Count every `from` as an `into` from now on
+    Delete(String),
+}
+
+/// The spelling near-duplicates share before plurals are folded: lowercase and without hyphens
+fn near_key(word: &str) -> String {
+    word.to_lowercase().replace('-', "")
+}
 
 pub struct WordBank {
-    words: HashMap<String, String>, // Maps word -> source file path
+    words: HashMap<String, BankEntry>,
+    titles: HashMap<String, String>, // Display names for source files that have one, e.g. an EPUB's title
 }
 
 impl WordBank {
     pub fn new() -> Self {
         Self {
             words: HashMap::new(),
+            titles: HashMap::new(),
+        }
+    }
+
+    /// Rebuild a bank from already-filtered entries, e.g. from the cache
+    pub fn from_entries(entries: Vec<BankEntry>) -> Self {
+        Self {
+            words: entries
+                .into_iter()
+                .map(|entry| (entry.word.clone(), entry))
+                .collect(),
+            titles: HashMap::new(),
         }
     }
 
-    pub fn add_words(&mut self, words: Vec<String>, file_path: String) {
-        for word in words {
-            if !is_stop_word(&word) && word.len() >= 3 {
-                // Only store the first occurrence of each word
-                self.words.entry(word).or_insert(file_path.clone());
+    pub fn add_words(&mut self, words: Vec<ParsedWord>, file_path: String) {
+        for (index, parsed) in words.iter().enumerate() {
+            // Stop words are kept so the list can be edited at runtime; the generator leaves them out
+            if parsed.word.len() >= 3 {
+                // Keep the source and location of the first occurrence, but count every one
+                let entry = self.words.entry(parsed.word.clone()).or_insert_with(|| BankEntry {
+                    word: parsed.word.clone(),
+                    source_file: file_path.clone(),
+                    location: parsed.location,
+                    count: 0,
+                    contexts: Vec::new(),
+                });
+                entry.count += 1;
+                if entry.contexts.len() < CONTEXT_SAMPLES {
+                    entry.contexts.push((context_snippet(&words, index), file_path.clone()));
+                }
             }
         }
     }
 
+    /// Show a source file under a readable name instead of its path
+    pub fn set_title(&mut self, source_file: String, title: String) {
+        self.titles.insert(source_file, title);
+    }
+
+    /// Display names of the source files that have one
+    pub fn titles(&self) -> &HashMap<String, String> {
+        &self.titles
+    }
+
+    /// Fold another bank into this one, keeping this bank's first occurrence for shared words
+    pub fn merge(&mut self, other: WordBank) {
+        self.titles.extend(other.titles);
+        for entry in other.words.into_values() {
+            self.add_entry(entry);
+        }
+    }
+
+    /// Add an entry, or fold its count and contexts into the entry already there for the same word
+    fn add_entry(&mut self, entry: BankEntry) {
+        self.words
+            .entry(entry.word.clone())
+            .and_modify(|existing| {
+                existing.count += entry.count;
+                let room = CONTEXT_SAMPLES.saturating_sub(existing.contexts.len());
+                existing.contexts.extend(entry.contexts.iter().take(room).cloned());
+            })
+            .or_insert(entry);
+    }
+
+    /// Show every word the dictionary knows with its translation, alongside or instead; words that end up
+    /// spelled the same are merged, and words it doesn't know are left as they are
+    pub fn translate(&mut self, translation: &Translation) {
+        let mut entries: Vec<BankEntry> = std::mem::take(&mut self.words).into_values().collect();
+        entries.sort_unstable_by(|a, b| a.word.cmp(&b.word));
+        for entry in entries {
+            let word = match translation.dictionary.get(&entry.word.to_lowercase()) {
+                Some(translated) => translation.display.show(&entry.word, translated),
+                None => entry.word.clone(),
+            };
+            self.add_entry(BankEntry { word, ..entry });
+        }
+    }
+
+    /// All words with their source file, sorted so seeded generation is reproducible across runs
     pub fn get_words(&self) -> Vec<(String, String)> {
+        let mut words: Vec<(String, String)> = self
+            .words
+            .values()
+            .map(|entry| (entry.word.clone(), entry.source_file.clone()))
+            .collect();
+        words.sort_unstable();
+        words
+    }
+
+    /// Where each word first appeared in its source file
+    pub fn locations(&self) -> HashMap<String, Location> {
+        self.words
+            .iter()
+            .map(|(word, entry)| (word.clone(), entry.location))
+            .collect()
+    }
+
+    /// Snippets of text around each word's first few occurrences, with the file each is from
+    pub fn contexts(&self) -> HashMap<String, Vec<(String, String)>> {
+        self.words
+            .iter()
+            .map(|(word, entry)| (word.clone(), entry.contexts.clone()))
+            .collect()
+    }
+
+    /// How often each word occurs across the corpus
+    pub fn frequencies(&self) -> HashMap<String, usize> {
         self.words
             .iter()
-            .map(|(word, path)| (word.clone(), path.clone()))
+            .map(|(word, entry)| (word.clone(), entry.count))
             .collect()
     }
 
+    /// All entries, sorted by word
+    pub fn entries(&self) -> Vec<BankEntry> {
+        let mut entries: Vec<BankEntry> = self.words.values().cloned().collect();
+        entries.sort_unstable_by(|a, b| a.word.cmp(&b.word));
+        entries
+    }
+
     pub fn word_count(&self) -> usize {
         self.words.len()
     }
-}
 
-fn is_stop_word(word: &str) -> bool {
-    const STOP_WORDS: &[&str] = &[
-        "the", "be", "to", "of", "and", "a", "in", "that", "have", "i", "it", "for", "not", "on",
-        "with", "he", "as", "you", "do", "at", "this", "but", "his", "by", "from", "they", "we",
-        "say", "her", "she", "or", "an", "will", "my", "one", "all", "would", "there", "their",
-        "what", "so", "up", "out", "if", "about", "who", "get", "which", "go", "me", "when",
-        "make", "can", "like", "time", "no", "just", "him", "know", "take", "people", "into",
-        "year", "your", "good", "some", "could", "them", "see", "other", "than", "then", "now",
-        "look", "only", "come", "its", "over", "think", "also", "back", "after", "use", "two",
-        "how", "our", "work", "first", "well", "way", "even", "new", "want", "because", "any",
-        "these", "give", "day", "most", "us", "is", "was", "are", "been", "has", "had", "were",
-        "said", "did", "having", "may", "should", "am", "being", "does",
-    ];
+    /// Merge possessives and plurals into one entry per base word, counting every form, spelled as `display` says
+    pub fn fold_forms(&mut self, display: FoldDisplay) {
+        let mut groups: HashMap<String, Vec<BankEntry>> = HashMap::new();
+        for entry in self.words.values() {
+            groups.entry(fold_key(&entry.word, |word| self.words.contains_key(word))).or_default().push(entry.clone());
+        }
+
+        self.words = groups
+            .into_iter()
+            .map(|(base, mut forms)| {
+                // Most frequent form first, alphabetical on ties so the choice is stable
+                forms.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+                let count = forms.iter().map(|form| form.count).sum();
+                let word = match display {
+                    FoldDisplay::Base => base.clone(),
+                    FoldDisplay::Surface => forms[0].word.clone(),
+                };
+                // Source and location come from the base word itself when the corpus has it
+                let first = forms.iter().find(|form| form.word == base).unwrap_or(&forms[0]);
+                let contexts = forms.iter().flat_map(|form| form.contexts.clone()).take(CONTEXT_SAMPLES).collect();
+                let entry = BankEntry { word: word.clone(), count, contexts, ..first.clone() };
+                (word, entry)
+            })
+            .collect();
+    }
+
+    /// Groups of entries that differ only in case, hyphens or being plural or possessive, each most frequent
+    /// first, in alphabetical order of that spelling
+    pub fn near_duplicates(&self) -> Vec<Vec<BankEntry>> {
+        let keys: HashSet<String> = self.words.keys().map(|word| near_key(word)).collect();
+        let mut groups: HashMap<String, Vec<BankEntry>> = HashMap::new();
+        for entry in self.words.values() {
+            let key = fold_key(&near_key(&entry.word), |word| keys.contains(word));
+            groups.entry(key).or_default().push(entry.clone());
+        }
 
-    STOP_WORDS.contains(&word)
+        let mut clusters: Vec<Vec<BankEntry>> = groups
+            .into_values()
+            .filter(|forms| forms.len() > 1)
+            .map(|mut forms| {
+                forms.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+                forms
+            })
+            .collect();
+        clusters.sort_by(|a, b| a[0].word.cmp(&b[0].word));
+        clusters
+    }
+
+    /// Merge one word into another or delete it; a word no longer in the bank is left alone
+    pub fn prune(&mut self, decision: &PruneDecision) {
+        match decision {
+            PruneDecision::Merge { from, into } if from != into => {
+                if let Some(entry) = self.words.remove(from) {
+                    self.add_entry(BankEntry { word: into.clone(), ..entry });
+                }
+            }
+            PruneDecision::Merge { .. } => {}
+            PruneDecision::Delete(word) => {
+                self.words.remove(word);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::translate::TranslationDisplay;
+
+    fn parsed(words: &[&str]) -> Vec<ParsedWord> {
+        words
+            .iter()
+            .map(|word| ParsedWord {
+                word: word.to_string(),
+                location: Location::default(),
+            })
+            .collect()
+    }
 
     #[test]
-    fn test_stop_word_filtering() {
+    fn test_stop_words_kept_for_runtime_editing() {
         let mut bank = WordBank::new();
         bank.add_words(
-            vec![
-                "the".to_string(),
-                "wonderful".to_string(),
-                "and".to_string(),
-                "beautiful".to_string(),
-            ],
+            parsed(&["the", "wonderful", "of", "beautiful"]),
             "test.txt".to_string(),
         );
 
         let words: Vec<String> = bank.get_words().iter().map(|(w, _)| w.clone()).collect();
         assert!(words.contains(&"wonderful".to_string()));
         assert!(words.contains(&"beautiful".to_string()));
-        assert!(!words.contains(&"the".to_string()));
-        assert!(!words.contains(&"and".to_string()));
+        assert!(words.contains(&"the".to_string()));
+        assert!(!words.contains(&"of".to_string()));
     }
 
     #[test]
     fn test_minimum_word_length() {
         let mut bank = WordBank::new();
-        bank.add_words(vec!["hi".to_string(), "hello".to_string()], "test.txt".to_string());
+        bank.add_words(parsed(&["hi", "hello"]), "test.txt".to_string());
 
         let words = bank.get_words();
         assert_eq!(words.len(), 1);
         assert!(words.iter().any(|(w, _)| w == "hello"));
     }
+
+    #[test]
+    fn test_context_snippets() {
+        let mut bank = WordBank::new();
+        let mut words = parsed(&["one", "two", "three", "four", "five", "six", "lantern", "seven", "eight"]);
+        words.extend((0..4).map(|_| ParsedWord { word: "lantern".to_string(), location: Location { chapter: None, paragraph: 2 } }));
+        bank.add_words(words, "a.txt".to_string());
+
+        let contexts = &bank.contexts()["lantern"];
+        assert_eq!(contexts.len(), CONTEXT_SAMPLES);
+        assert_eq!(contexts[0].0, "two three four five six lantern seven eight");
+        // The second paragraph's snippets stay out of the first
+        assert_eq!(contexts[1].0, "lantern lantern lantern lantern");
+        assert_eq!(contexts[2].1, "a.txt");
+    }
+
+    #[test]
+    fn test_fold_forms() {
+        let mut bank = WordBank::new();
+        bank.add_words(parsed(&["shadows", "shadows", "shadow", "poet's", "glass", "stories", "story"]), "a.txt".to_string());
+
+        let mut base = WordBank::from_entries(bank.entries());
+        base.fold_forms(FoldDisplay::Base);
+        let frequencies = base.frequencies();
+        assert_eq!(frequencies["shadow"], 3);
+        assert_eq!(frequencies["poet"], 1);
+        assert_eq!(frequencies["glass"], 1);
+        assert_eq!(frequencies["story"], 2);
+        assert_eq!(base.word_count(), 4);
+
+        bank.fold_forms(FoldDisplay::Surface);
+        assert_eq!(bank.frequencies()["shadows"], 3);
+        assert!(!bank.frequencies().contains_key("shadow"));
+
+        let known = |word: &str| ["not", "hop", "box", "church"].contains(&word);
+        assert_eq!(fold_key("notes", known), "notes");
+        assert_eq!(fold_key("hopes", known), "hopes");
+        assert_eq!(fold_key("boxes", known), "box");
+        assert_eq!(fold_key("churches", known), "church");
+        assert_eq!(fold_key("notes", |word| word == "note"), "note");
+    }
+
+    #[test]
+    fn test_translate() {
+        let mut bank = WordBank::new();
+        bank.add_words(parsed(&["harbor", "port", "port", "salt"]), "a.txt".to_string());
+        let dictionary: HashMap<String, String> =
+            [("harbor", "port"), ("port", "port")].iter().map(|&(word, tr)| (word.to_string(), tr.to_string())).collect();
+
+        let mut alongside = WordBank::from_entries(bank.entries());
+        alongside.translate(&Translation { dictionary: dictionary.clone(), display: TranslationDisplay::Alongside });
+        assert_eq!(alongside.frequencies()["harbor/port"], 1);
+        assert_eq!(alongside.frequencies()["salt"], 1);
+
+        bank.translate(&Translation { dictionary, display: TranslationDisplay::Replace });
+        assert_eq!(bank.frequencies()["port"], 3);
+        assert_eq!(bank.word_count(), 2);
+    }
+
+    #[test]
+    fn test_counts_every_occurrence() {
+        let mut bank = WordBank::new();
+        bank.add_words(parsed(&["lantern", "harbor", "lantern"]), "a.txt".to_string());
+        bank.add_words(parsed(&["lantern"]), "b.txt".to_string());
+
+        let frequencies = bank.frequencies();
+        assert_eq!(frequencies["lantern"], 3);
+        assert_eq!(frequencies["harbor"], 1);
+        assert!(bank.get_words().contains(&("lantern".to_string(), "a.txt".to_string())));
+    }
+
+    #[test]
+    fn test_near_duplicates_pruned() {
+        let mut bank = WordBank::new();
+        bank.add_words(parsed(&["Lantern", "lantern", "lanterns", "lantern", "sea-glass", "seaglass", "glass"]), "a.txt".to_string());
+
+        let clusters: Vec<Vec<String>> =
+            bank.near_duplicates().iter().map(|forms| forms.iter().map(|form| form.word.clone()).collect()).collect();
+        assert_eq!(clusters, vec![vec!["lantern", "Lantern", "lanterns"], vec!["sea-glass", "seaglass"]]);
+
+        bank.prune(&PruneDecision::Merge { from: "lanterns".to_string(), into: "lantern".to_string() });
+        bank.prune(&PruneDecision::Delete("Lantern".to_string()));
+        bank.prune(&PruneDecision::Delete("gone".to_string()));
+        assert_eq!(bank.frequencies()["lantern"], 3);
+        assert!(!bank.frequencies().contains_key("Lantern"));
+        assert_eq!(bank.near_duplicates().len(), 1);
+    }
 }
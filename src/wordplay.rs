@@ -0,0 +1,57 @@
+//! Word-game figures for the selected word in the Info box: its Scrabble score and how its letters split
+//! between vowels and consonants.
+
+/// Points on the English Scrabble tiles, a to z
+const TILE_POINTS: [u32; 26] = [1, 3, 3, 2, 1, 4, 2, 4, 1, 8, 5, 1, 3, 1, 1, 3, 10, 1, 1, 1, 1, 4, 4, 8, 4, 10];
+
+/// How many letters a word has, and how many of them are vowels and consonants
+#[derive(Debug, PartialEq)]
+pub struct LetterMix {
+    pub letters: usize,
+    pub vowels: usize,  // a, e, i, o and u, accented or not; y is counted with the consonants, as on a Scrabble rack
+    pub consonants: usize,
+}
+
+/// Scrabble score of a word's letters on a plain square, with no bonuses; letters without a tile,
+/// such as accented ones, score nothing
+pub fn scrabble_score(word: &str) -> u32 {
+    word.chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| TILE_POINTS[(c.to_ascii_lowercase() as u8 - b'a') as usize])
+        .sum()
+}
+
+/// The letters of a word, punctuation left out
+pub fn letter_mix(word: &str) -> LetterMix {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).flat_map(char::to_lowercase).collect();
+    let vowels = letters.iter().filter(|&&c| is_vowel(c)).count();
+    LetterMix { letters: letters.len(), vowels, consonants: letters.len() - vowels }
+}
+
+/// Whether a lowercase letter is a, e, i, o or u, with or without an accent
+fn is_vowel(c: char) -> bool {
+    matches!(
+        c,
+        'a' | 'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą'
+            | 'e' | 'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě'
+            | 'i' | 'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į'
+            | 'o' | 'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő'
+            | 'u' | 'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrabble_score_and_letters() {
+        assert_eq!(scrabble_score("quixotic"), 26);
+        assert_eq!(scrabble_score("Harbor"), 11);
+        assert_eq!(scrabble_score("don't"), 5);
+        assert_eq!(letter_mix("rhythm's"), LetterMix { letters: 7, vowels: 0, consonants: 7 });
+        assert_eq!(letter_mix("lantern"), LetterMix { letters: 7, vowels: 2, consonants: 5 });
+        assert_eq!(letter_mix("cafés"), LetterMix { letters: 5, vowels: 2, consonants: 3 });
+        assert_eq!(letter_mix("Über"), LetterMix { letters: 4, vowels: 2, consonants: 2 });
+    }
+}